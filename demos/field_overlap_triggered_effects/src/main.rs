@@ -5,6 +5,10 @@ use v0::physical_quantities::*;
 use v0::simulation::Simulation;
 use v0::simulation_objects::*;
 
+// Floor particles only push fallers, and fallers only stick to other fallers.
+const FLOOR_LAYER: u32 = 1;
+const FALLER_LAYER: u32 = 2;
+
 #[macroquad::main("Physics Demo")]
 async fn main() {
 	let sim_speed = 1.0;
@@ -13,6 +17,8 @@ async fn main() {
 		Some(sim_speed),
 		None,
 	);
+	sim.set_layer_mask(FLOOR_LAYER, Layers::layer(FALLER_LAYER));
+	sim.set_layer_mask(FALLER_LAYER, Layers::layer(FALLER_LAYER));
 
 	let mut particles = Vec::new();
 
@@ -164,19 +170,10 @@ impl Field for FloorAndWallField {
 		triggered_by: HashMap<Uuid, Vec<Option<FieldInfo>>>,
 		_field_owner_id: Uuid,
 	) {
+		// Layers keep other parts of the wall from triggering this field, so
+		//	every particle here should be pushed.
 		for id in triggered_by.keys() {
-			// Only apply a force if the detected particle isn't part of the
-			//	wall.
-			let info = simulation.get_field_info(*id);
-			let mut is_wall = false;
-			for i in info {
-				if i.get_name() == self.get_name() {
-					is_wall = true;
-				}
-			}
-			if !is_wall {
-				simulation.apply_force(*id, self.force);
-			}
+			simulation.apply_force(*id, self.force);
 		}
 	}
 
@@ -203,6 +200,10 @@ impl Field for FloorAndWallField {
 	fn get_name(&self) -> &String {
 		&self.name
 	}
+
+	fn get_layers(&self) -> Layers {
+		Layers::layer(FLOOR_LAYER)
+	}
 }
 
 // Just here to provide something to set rendered circle size and trigger the
@@ -283,47 +284,46 @@ impl Field for StickyField {
 		// Don't bother trying to calculate or reason too much about how to
 		//	make this work properly. Just do something naive and see what
 		//	happens. It should be entertaining.
+		// Layers make sure only other sticky fields trigger this one.
 		for (particle_id, field_info_vec) in triggered_by.iter() {
 			for field_info in field_info_vec {
-				if let Some(info) = field_info {
-					if info.get_name() == self.get_name() {
-						// Stick.
-						let my_velocity = simulation.get_velocity(field_owner_id);
-						let other_velocity =
-							simulation.get_velocity(*particle_id);
-						// Naively assume that each particle can just apply half of
-						//	the required velocity change to set their relative
-						//	velocity to 0. What happens if three particles act on
-						//	each other? Doesn't matter.
-						let double_velocity =
-							 Velocity::new(
-								(my_velocity.x() - other_velocity.x()),
-								(my_velocity.y() - other_velocity.y()),
-							);
-
-						let half_magnitude =
-							0.5 * (
-								double_velocity.x().powf(2.0) 
-								+ double_velocity.y().powf(2.0)
-							).sqrt();
-
-						let other_delta_velocity =
-							Velocity::new(
-								(double_velocity.x() * 0.5),
-								(double_velocity.y() * 0.5),
-							);
-						// a = m/s/s => a = delta_v / t
-						let one_over_time =
-							(1.0 / simulation.get_tick_duration().get_number());
-						let other_acceleration =
-							Acceleration::new(
-								other_delta_velocity.x() * one_over_time,
-								other_delta_velocity.y() * one_over_time);
-						// f = ma
-						let force =
-							 simulation.get_mass(*particle_id) * other_acceleration;
-						simulation.apply_force(*particle_id, force);
-					}
+				if field_info.is_some() {
+					// Stick.
+					let my_velocity = simulation.get_velocity(field_owner_id);
+					let other_velocity =
+						simulation.get_velocity(*particle_id);
+					// Naively assume that each particle can just apply half of
+					//	the required velocity change to set their relative
+					//	velocity to 0. What happens if three particles act on
+					//	each other? Doesn't matter.
+					let double_velocity =
+						 Velocity::new(
+							(my_velocity.x() - other_velocity.x()),
+							(my_velocity.y() - other_velocity.y()),
+						);
+
+					let half_magnitude =
+						0.5 * (
+							double_velocity.x().powf(2.0) 
+							+ double_velocity.y().powf(2.0)
+						).sqrt();
+
+					let other_delta_velocity =
+						Velocity::new(
+							(double_velocity.x() * 0.5),
+							(double_velocity.y() * 0.5),
+						);
+					// a = m/s/s => a = delta_v / t
+					let one_over_time =
+						(1.0 / simulation.get_tick_duration().get_number());
+					let other_acceleration =
+						Acceleration::new(
+							other_delta_velocity.x() * one_over_time,
+							other_delta_velocity.y() * one_over_time);
+					// f = ma
+					let force =
+						 simulation.get_mass(*particle_id) * other_acceleration;
+					simulation.apply_force(*particle_id, force);
 				}
 			}
 		}
@@ -352,4 +352,8 @@ impl Field for StickyField {
 	fn get_name(&self) -> &String {
 		&self.name
 	}
+
+	fn get_layers(&self) -> Layers {
+		Layers::layer(FALLER_LAYER)
+	}
 }
@@ -25,6 +25,9 @@ async fn main() {
 					//Some(5e-2),
 					None,
 					None,
+					None,
+					None,
+					None,
 				))],
 			);
 
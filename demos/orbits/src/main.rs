@@ -1,17 +1,38 @@
 use macroquad::prelude::*;
+use uuid::Uuid;
 use v0::physical_quantities::*;
 use v0::simulation::Simulation;
 use v0::simulation_objects::*;
+use std::sync::OnceLock;
 use std::time::{Instant, Duration};
 
+// The particles pushed into orbit, and the force each one is pushed with.
+static PUSHES: OnceLock<Vec<(Uuid, Force)>> = OnceLock::new();
+
+// Pushes the orbiting particles for the first half second. Runs once per tick,
+//	so the same force is applied however the ticks line up with frames.
+fn push_into_orbit(sim: &Simulation) {
+	if sim.get_elapsed_time().get_number() > 0.5 {
+		return;
+	}
+	if let Some(pushes) = PUSHES.get() {
+		for &(p_id, force) in pushes {
+			sim.apply_force(p_id, force);
+		}
+	}
+}
+
 #[macroquad::main("Physics Demo")]
 async fn main() {
 	let sim_speed = 1.0;
 	let sim = Simulation::new(
 		Time::new(0.001),
 		Some(sim_speed),
-		None,
+		Some(push_into_orbit),
 	);
+	// Ticks are much shorter than frames, so allow enough of them per frame
+	//	to keep up with real time.
+	sim.set_max_ticks_per_advance(Ticks::new(100));
 	let p_id1 = sim.create_particle(
 		Mass::new(3.5e15),
 		Displacement::new(400.0, -300.0),
@@ -19,6 +40,9 @@ async fn main() {
 			10000.0,
 			None,
 			None,
+			None,
+			None,
+			None,
 		))],
 	);
 	let p_id2 = sim.create_particle(
@@ -28,6 +52,9 @@ async fn main() {
 			10000.0,
 			None,
 			None,
+			None,
+			None,
+			None,
 		))],
 	);
 	let p_id3 = sim.create_particle(
@@ -37,6 +64,9 @@ async fn main() {
 			10000.0,
 			None,
 			None,
+			None,
+			None,
+			None,
 		))],
 	);
 	let p_id4 = sim.create_particle(
@@ -46,6 +76,9 @@ async fn main() {
 			10000.0,
 			None,
 			None,
+			None,
+			None,
+			None,
 		))],
 	);
 	let p_id5 = sim.create_particle(
@@ -55,6 +88,9 @@ async fn main() {
 			10000.0,
 			None,
 			None,
+			None,
+			None,
+			None,
 		))],
 	);
 	let p_id6 = sim.create_particle(
@@ -64,9 +100,20 @@ async fn main() {
 			10000.0,
 			None,
 			None,
+			None,
+			None,
+			None,
 		))],
 	);
 
+	PUSHES.set(vec![
+		(p_id2, Force::new(4.0e5, 0.0)),
+		(p_id3, Force::new(4.0e5, 0.0)),
+		(p_id4, Force::new(3.0e5, 0.0)),
+		(p_id5, Force::new(3.0e5, 0.0)),
+		(p_id6, Force::new(2.0e5, 0.0)),
+	]).unwrap();
+
 	// Step once to get the simulation to actually add the new particles.
 	sim.step();
 
@@ -86,46 +133,36 @@ async fn main() {
 	let segment_time = Time::new(0.25);
 	let mut last_seg_time = Time::new(0.0);
 	let mut timer = Instant::now();
-	let mut prev_frame_time = 0.0;
-	let mut last_tick;
-	let mut last_force_tick = Ticks::new(0);
+	let mut last_frame = Instant::now();
+	let mut alpha;
 	//for i in 0..1000 {
 	loop {
+		// Run as many ticks as fit in the real time since the last frame.
+		let now = Instant::now();
+		alpha = sim.advance(now - last_frame).get_alpha();
+		last_frame = now;
 		elapsed_sim_time = sim.get_elapsed_time();
-		last_tick = sim.get_elapsed_ticks();
-
-		// Apply a force for a few seconds. Need to avoid doing this more than
-		//	once per tick, to avoid non-determinism.
-		if elapsed_sim_time.get_number() <= 0.5
-			&& last_tick.get_number() > last_force_tick.get_number() {
-			last_force_tick = last_tick;
-			sim.apply_force(p_id2, Force::new(4.0e5, 0.0));
-			sim.apply_force(p_id3, Force::new(4.0e5, 0.0));
-			sim.apply_force(p_id4, Force::new(3.0e5, 0.0));
-			sim.apply_force(p_id5, Force::new(3.0e5, 0.0));
-			sim.apply_force(p_id6, Force::new(2.0e5, 0.0));
-		}
-		sim.step_synchronized();
 
 		clear_background(BLACK);
 
-		position1 = sim.get_position(p_id1);
-		position2 = sim.get_position(p_id2);
-		position3 = sim.get_position(p_id3);
-		position4 = sim.get_position(p_id4);
-		position5 = sim.get_position(p_id5);
-		position6 = sim.get_position(p_id6);
-
-
 		if elapsed_sim_time - last_seg_time >= segment_time {
-			segment_points2.push(position2);
-			segment_points3.push(position3);
-			segment_points4.push(position4);
-			segment_points5.push(position5);
-			segment_points6.push(position6);
+			segment_points2.push(sim.get_position(p_id2));
+			segment_points3.push(sim.get_position(p_id3));
+			segment_points4.push(sim.get_position(p_id4));
+			segment_points5.push(sim.get_position(p_id5));
+			segment_points6.push(sim.get_position(p_id6));
 			last_seg_time = elapsed_sim_time;
 		}
 
+		// Draw between the last two ticks, so motion is smooth even though
+		//	frames don't line up with ticks.
+		position1 = sim.get_interpolated_position(p_id1, alpha);
+		position2 = sim.get_interpolated_position(p_id2, alpha);
+		position3 = sim.get_interpolated_position(p_id3, alpha);
+		position4 = sim.get_interpolated_position(p_id4, alpha);
+		position5 = sim.get_interpolated_position(p_id5, alpha);
+		position6 = sim.get_interpolated_position(p_id6, alpha);
+
 		// Draw the particles.
 		draw_circle(position1.x() as f32, -position1.y() as f32, 10.0, BLUE);
 		draw_circle(position2.x() as f32, -position2.y() as f32, 5.0, RED);
@@ -210,16 +247,7 @@ async fn main() {
 			}
 		}
 
-		
-		// Don't await the next animation frame until at least 1/60 of a second
-		//	(more or less to respect the simulation speed setting). has elapsed
-		//	in the simulation. Otherwise, we're limiting the number of times we
-		//	can call step_synchronized based on framerate, which seems to max
-		//	out at around 60fps.
-		if elapsed_sim_time.get_number() >= prev_frame_time + (0.01666667 * sim_speed) {
-			prev_frame_time = elapsed_sim_time.get_number();
-			next_frame().await
-		}
+		next_frame().await
 	}
 	print!("1000 loops took {0} seconds.", timer.elapsed().as_secs());
 }
@@ -24,6 +24,9 @@ async fn main() {
 						10000.0,
 						Some(-0e3),
 						None,
+						None,
+						None,
+						None,
 					)),
 					Box::new(SimpleSelfGravityField::new(
 						Acceleration::new(0.0, -9.81),
@@ -46,6 +49,9 @@ async fn main() {
 					100.0,
 					Some(-1e-15),
 					None,
+					None,
+					None,
+					None,
 				)),
 			],
 		);
@@ -0,0 +1,321 @@
+//! Runs a scene without a window, as fast as possible, and writes what
+//! happened to files. Useful for batch jobs and CI.
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process;
+use std::time::Instant;
+use v0::physical_quantities::*;
+use v0::simulation::Simulation;
+use v0::{dimensions, persistence, recording, scene, units};
+
+const USAGE: &str = "\
+Usage: physics-run <scene.ron> (--ticks <N> | --seconds <T>) [options]
+
+Runs a scene as fast as possible, ignoring its simulation_speed.
+
+Options:
+	--ticks <N>              Run N ticks.
+	--seconds <T>            Run T simulated seconds, rounded to whole ticks.
+	                         Seconds are converted to the scene's unit
+	                         system.
+	--trajectory <path>      Write particle trajectories to <path>. Uses CSV
+	                         if <path> ends in .csv and the binary format
+	                         otherwise.
+	--diagnostics <path>     Write energy, momentum and center of mass to
+	                         <path> as CSV, in the scene's units.
+	--interval <N>           Write to the output files every N ticks.
+	                         Defaults to 1.
+	--help                   Print this message.";
+
+const DIAGNOSTICS_HEADER: &str = "tick,time,kinetic_energy,potential_energy,total_energy,\
+momentum_x,momentum_y,angular_momentum,center_of_mass_x,center_of_mass_y";
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn args(text: &str) -> Vec<String> {
+		text.split_whitespace().map(String::from).collect()
+	}
+
+	#[test]
+	fn parses_tick_count() {
+		let options = parse_args(args("scene.ron --ticks 100")).unwrap().unwrap();
+
+		assert_eq!(options.scene, PathBuf::from("scene.ron"));
+		assert_eq!(options.length, RunLength::Ticks(100));
+		assert_eq!(options.trajectory, None);
+		assert_eq!(options.diagnostics, None);
+		assert_eq!(options.interval, 1);
+	}
+
+	#[test]
+	fn parses_all_options() {
+		let options = parse_args(args(
+			"--seconds 2.5 --trajectory out.csv scene.ron --diagnostics d.csv --interval 10"
+		)).unwrap().unwrap();
+
+		assert_eq!(options.scene, PathBuf::from("scene.ron"));
+		assert_eq!(options.length, RunLength::Seconds(2.5));
+		assert_eq!(options.trajectory, Some(PathBuf::from("out.csv")));
+		assert_eq!(options.diagnostics, Some(PathBuf::from("d.csv")));
+		assert_eq!(options.interval, 10);
+	}
+
+	#[test]
+	fn help_returns_none() {
+		assert!(parse_args(args("--help")).unwrap().is_none());
+	}
+
+	#[test]
+	fn rejects_bad_arguments() {
+		assert!(parse_args(args("--ticks 10")).is_err());
+		assert!(parse_args(args("scene.ron")).is_err());
+		assert!(parse_args(args("scene.ron --ticks 10 --seconds 1")).is_err());
+		assert!(parse_args(args("scene.ron --ticks ten")).is_err());
+		assert!(parse_args(args("scene.ron --seconds -1")).is_err());
+		assert!(parse_args(args("scene.ron --ticks 10 --interval 0")).is_err());
+		assert!(parse_args(args("scene.ron --ticks")).is_err());
+		assert!(parse_args(args("scene.ron other.ron --ticks 10")).is_err());
+		assert!(parse_args(args("scene.ron --ticks 10 --frobnicate")).is_err());
+	}
+
+	#[test]
+	fn seconds_round_to_whole_ticks() {
+		let si = units::UnitSystem::SI;
+		assert_eq!(RunLength::Seconds(1.0).get_ticks(Time::new(0.3), &si), 3);
+		assert_eq!(RunLength::Seconds(1.0).get_ticks(Time::new(0.1), &si), 10);
+		assert_eq!(RunLength::Ticks(7).get_ticks(Time::new(0.1), &si), 7);
+	}
+
+	#[test]
+	fn seconds_are_converted_to_the_scene_unit_system() {
+		let sim = scene::load_str(
+			"(unit_system: Astronomical, tick_duration: \"1 d\")",
+			&persistence::Registry::new(),
+			None,
+		).unwrap();
+		let tick_duration = sim.get_tick_duration();
+		let unit_system = sim.get_unit_system();
+
+		// A day per tick.
+		assert_eq!(RunLength::Seconds(2.0 * 86400.0).get_ticks(tick_duration, &unit_system), 2);
+		assert_eq!(RunLength::Seconds(1.0).get_ticks(tick_duration, &unit_system), 0);
+	}
+
+	#[test]
+	fn writes_diagnostics_rows() {
+		let sim = Simulation::new(Time::new(0.5), None, None);
+		sim.create_particle_with_velocity(
+			Mass::new(2.0),
+			Displacement::new(1.0, 0.0),
+			Velocity::new(0.0, 3.0),
+			Vec::new(),
+		);
+		sim.step();
+
+		let mut output = Vec::new();
+		write_diagnostics_row(&mut output, &sim).unwrap();
+
+		assert_eq!(String::from_utf8(output).unwrap(), "1,0.5,9,0,9,0,6,6,1,0\n");
+	}
+}
+
+
+// How long to run the simulation for.
+#[derive(Debug, PartialEq)]
+enum RunLength {
+	Ticks(u64),
+	Seconds(f64),
+}
+
+impl RunLength {
+	// `tick_duration` is in the units of `unit_system`.
+	fn get_ticks(&self, tick_duration: Time, unit_system: &units::UnitSystem) -> u64 {
+		match self {
+			RunLength::Ticks(ticks) => *ticks,
+			RunLength::Seconds(seconds) => {
+				let duration = unit_system.from_si::<dimensions::Time>(*seconds);
+				(duration / tick_duration.get_number()).round() as u64
+			},
+		}
+	}
+}
+
+struct Options {
+	scene: PathBuf,
+	length: RunLength,
+	trajectory: Option<PathBuf>,
+	diagnostics: Option<PathBuf>,
+	interval: u64,
+}
+
+// Returns None if the user asked for help.
+fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<Options>, String> {
+	let mut scene = None;
+	let mut length = None;
+	let mut trajectory = None;
+	let mut diagnostics = None;
+	let mut interval = 1;
+
+	let mut args = args.into_iter();
+	while let Some(arg) = args.next() {
+		let mut value = || args.next().ok_or(format!("{} requires a value", arg));
+		match arg.as_str() {
+			"--help" | "-h" => return Ok(None),
+			"--ticks" | "--seconds" => {
+				if length.is_some() {
+					return Err(String::from("only one of --ticks and --seconds may be given"));
+				}
+				let value = value()?;
+				length = Some(if arg == "--ticks" {
+					RunLength::Ticks(value.parse()
+						.map_err(|_| format!("--ticks must be a whole number: {}", value))?)
+				} else {
+					match value.parse::<f64>() {
+						Ok(seconds) if seconds >= 0.0 => RunLength::Seconds(seconds),
+						_ => return Err(format!(
+							"--seconds must be a non-negative number: {}", value
+						)),
+					}
+				});
+			},
+			"--trajectory" => trajectory = Some(PathBuf::from(value()?)),
+			"--diagnostics" => diagnostics = Some(PathBuf::from(value()?)),
+			"--interval" => {
+				let value = value()?;
+				interval = match value.parse::<u64>() {
+					Ok(n) if n > 0 => n,
+					_ => return Err(format!("--interval must be a positive whole number: {}", value)),
+				};
+			},
+			_ if arg.starts_with('-') => return Err(format!("unknown option: {}", arg)),
+			_ => {
+				if scene.is_some() {
+					return Err(format!("unexpected argument: {}", arg));
+				}
+				scene = Some(PathBuf::from(arg));
+			},
+		}
+	}
+
+	Ok(Some(Options {
+		scene: scene.ok_or("no scene file given")?,
+		length: length.ok_or("one of --ticks or --seconds is required")?,
+		trajectory: trajectory,
+		diagnostics: diagnostics,
+		interval: interval,
+	}))
+}
+
+fn write_diagnostics_row<W: Write>(writer: &mut W, sim: &Simulation) -> io::Result<()> {
+	let kinetic = sim.get_kinetic_energy();
+	let potential = sim.get_potential_energy();
+	let momentum = sim.get_linear_momentum();
+	let angular_momentum = sim.get_angular_momentum(Displacement::new(0.0, 0.0));
+	let (com_x, com_y) = match sim.get_center_of_mass() {
+		Some(com) => (com.x().to_string(), com.y().to_string()),
+		None => (String::new(), String::new()),
+	};
+
+	writeln!(
+		writer,
+		"{},{},{},{},{},{},{},{},{},{}",
+		sim.get_elapsed_ticks().get_number(),
+		sim.get_elapsed_time().get_number(),
+		kinetic.get_number(),
+		potential.get_number(),
+		(kinetic + potential).get_number(),
+		momentum.x(),
+		momentum.y(),
+		angular_momentum.get_number(),
+		com_x,
+		com_y,
+	)
+}
+
+fn run(options: &Options) -> Result<(), String> {
+	let sim = scene::load_file(&options.scene, &persistence::Registry::new(), None)
+		.map_err(|e| e.to_string())?;
+	let ticks = options.length.get_ticks(sim.get_tick_duration(), &sim.get_unit_system());
+	let interval = Ticks::new(options.interval);
+
+	let recorder_id = match &options.trajectory {
+		Some(path) => {
+			let recorder = if path.extension().is_some_and(|e| e == "csv") {
+				recording::Recorder::csv_file(path, interval)
+			} else {
+				recording::Recorder::binary_file(path, interval)
+			};
+			let recorder = recorder.map_err(|e| format!("{}: {}", path.display(), e))?;
+			Some(sim.attach_recorder(recorder))
+		},
+		None => None,
+	};
+
+	let mut diagnostics = match &options.diagnostics {
+		Some(path) => {
+			let mut writer = File::create(path).map(BufWriter::new)
+				.map_err(|e| format!("{}: {}", path.display(), e))?;
+			writeln!(writer, "{}", DIAGNOSTICS_HEADER)
+				.map_err(|e| format!("{}: {}", path.display(), e))?;
+			Some((path, writer))
+		},
+		None => None,
+	};
+
+	let start = Instant::now();
+	for _ in 0..ticks {
+		sim.step();
+
+		if let Some((path, writer)) = &mut diagnostics
+			&& sim.get_elapsed_ticks().get_number().is_multiple_of(options.interval)
+		{
+			write_diagnostics_row(writer, &sim)
+				.map_err(|e| format!("{}: {}", path.display(), e))?;
+		}
+	}
+	let wall_time = start.elapsed().as_secs_f64();
+
+	if let Some((path, mut writer)) = diagnostics {
+		writer.flush().map_err(|e| format!("{}: {}", path.display(), e))?;
+	}
+	if let Some(id) = recorder_id {
+		sim.detach_recorder(id).finish().map_err(|e| {
+			format!("{}: {}", options.trajectory.as_ref().unwrap().display(), e)
+		})?;
+	}
+
+	println!(
+		"Ran {} ticks ({} simulated seconds) in {:.3} s: {:.0} ticks/s",
+		ticks,
+		sim.get_unit_system().to_si::<dimensions::Time>(sim.get_elapsed_time().get_number()),
+		wall_time,
+		ticks as f64 / wall_time,
+	);
+
+	Ok(())
+}
+
+fn main() {
+	let options = match parse_args(env::args().skip(1)) {
+		Ok(Some(options)) => options,
+		Ok(None) => {
+			println!("{}", USAGE);
+			return;
+		},
+		Err(message) => {
+			eprintln!("physics-run: {}\n\n{}", message, USAGE);
+			process::exit(2);
+		},
+	};
+
+	if let Err(message) = run(&options) {
+		eprintln!("physics-run: {}", message);
+		process::exit(1);
+	}
+}
@@ -0,0 +1,179 @@
+//! Physical dimensions as types, so the compiler can check the units of every
+//! calculation.
+//!
+//! A dimension is the exponents of length, mass and time in a quantity's
+//! units, stored as `typenum` integers. For example, force is
+//! length¹ mass¹ time⁻², i.e. kg m / s². Multiplying two quantities adds their
+//! exponents and dividing subtracts them, so `Mass * Acceleration` has the
+//! dimension of `Force` and `Force * Length` has the dimension of `Energy`.
+//! Quantities with different dimensions are different types, so adding a
+//! `Time` to a `Mass` doesn't compile.
+//!
+//! Dimensions only exist at compile time. `physical_quantities::Quantity` and
+//! `physical_quantities::QuantityN` attach one to a number or a vector without
+//! making it any bigger.
+
+use std::marker;
+use std::ops;
+use typenum::{Integer, N1, N2, N3, P1, P2, P3, Z0};
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	// Compiles only if `A` and `B` are the same type.
+	fn assert_same<A, B>()
+	where
+		A: ops::Mul<Dimensionless, Output = B>,
+	{
+	}
+
+	#[test]
+	fn products_add_exponents() {
+		assert_same::<typenum::Prod<Mass, Acceleration>, Force>();
+		assert_same::<typenum::Prod<Force, Length>, Energy>();
+		assert_same::<typenum::Prod<Mass, Velocity>, Momentum>();
+		assert_same::<typenum::Prod<Force, Time>, Momentum>();
+		assert_same::<typenum::Prod<Length, Length>, Area>();
+		assert_same::<typenum::Prod<Momentum, Length>, AngularMomentum>();
+		assert_same::<typenum::Prod<MomentOfInertia, Frequency>, AngularMomentum>();
+	}
+
+	#[test]
+	fn quotients_subtract_exponents() {
+		assert_same::<typenum::Quot<Length, Time>, Velocity>();
+		assert_same::<typenum::Quot<Energy, Time>, Power>();
+		assert_same::<typenum::Quot<Force, Area>, Pressure>();
+		assert_same::<typenum::Quot<Time, Time>, Dimensionless>();
+		assert_same::<typenum::Quot<Dimensionless, Time>, Frequency>();
+		// F = G m_1 m_2 / r^2
+		assert_same::<typenum::Quot<typenum::Prod<Force, Area>, typenum::Prod<Mass, Mass>>, GravitationalConstant>();
+	}
+
+	#[test]
+	fn square_roots_halve_exponents() {
+		assert_same::<SquareRoot<Area>, Length>();
+		assert_same::<SquareRoot<typenum::Prod<Velocity, Velocity>>, Velocity>();
+	}
+
+	#[test]
+	fn dimensions_get_exponents() {
+		assert_eq!(get_exponents::<Dimensionless>(), (0, 0, 0));
+		assert_eq!(get_exponents::<Force>(), (1, 1, -2));
+		assert_eq!(get_exponents::<GravitationalConstant>(), (3, -1, -2));
+	}
+}
+
+/// A physical dimension: the exponents of length, mass and time in a
+/// quantity's units. Implemented by `Dim`.
+pub trait Dimension: 'static {
+	type Length: Integer;
+	type Mass: Integer;
+	type Time: Integer;
+}
+
+/// The dimension with length exponent `L`, mass exponent `M` and time
+/// exponent `T`, which are `typenum` integers (e.g., `typenum::N2` for -2).
+/// Never created. Only used as a type parameter.
+#[derive(PartialEq, Eq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct Dim<L, M, T>(marker::PhantomData<(L, M, T)>);
+
+impl<L: Integer, M: Integer, T: Integer> Dimension for Dim<L, M, T> {
+	type Length = L;
+	type Mass = M;
+	type Time = T;
+}
+
+// Multiplying two quantities multiplies their dimensions, which adds the
+//	exponents. These are only ever used as types (e.g., `typenum::Prod<A, B>`),
+//	so there is never a value to multiply.
+impl<L1, M1, T1, L2, M2, T2> ops::Mul<Dim<L2, M2, T2>> for Dim<L1, M1, T1>
+where
+	L1: ops::Add<L2>,
+	M1: ops::Add<M2>,
+	T1: ops::Add<T2>,
+{
+	type Output = Dim<typenum::Sum<L1, L2>, typenum::Sum<M1, M2>, typenum::Sum<T1, T2>>;
+
+	fn mul(self, _rhs: Dim<L2, M2, T2>) -> Self::Output {
+		Dim(marker::PhantomData)
+	}
+}
+
+// Dividing subtracts the exponents.
+impl<L1, M1, T1, L2, M2, T2> ops::Div<Dim<L2, M2, T2>> for Dim<L1, M1, T1>
+where
+	L1: ops::Sub<L2>,
+	M1: ops::Sub<M2>,
+	T1: ops::Sub<T2>,
+{
+	type Output = Dim<typenum::Diff<L1, L2>, typenum::Diff<M1, M2>, typenum::Diff<T1, T2>>;
+
+	fn div(self, _rhs: Dim<L2, M2, T2>) -> Self::Output {
+		Dim(marker::PhantomData)
+	}
+}
+
+/// Dimensions that have a square root, i.e. every exponent is even. Taking
+/// the square root halves the exponents.
+pub trait HasSquareRoot: Dimension {
+	type Output: Dimension;
+}
+
+impl<L, M, T> HasSquareRoot for Dim<L, M, T>
+where
+	L: Integer + typenum::PartialDiv<P2, Output: Integer>,
+	M: Integer + typenum::PartialDiv<P2, Output: Integer>,
+	T: Integer + typenum::PartialDiv<P2, Output: Integer>,
+{
+	type Output = Dim<typenum::PartialQuot<L, P2>, typenum::PartialQuot<M, P2>, typenum::PartialQuot<T, P2>>;
+}
+
+/// The square root of dimension `D` (e.g., `SquareRoot<Area>` is `Length`).
+pub type SquareRoot<D> = <D as HasSquareRoot>::Output;
+
+/// Gets the exponents of length, mass and time in dimension `D`.
+pub fn get_exponents<D: Dimension>() -> (i32, i32, i32) {
+	(D::Length::I32, D::Mass::I32, D::Time::I32)
+}
+
+/// Pure numbers, such as ratios and coefficients. Also angles, since a radian
+/// is a length divided by a length.
+pub type Dimensionless = Dim<Z0, Z0, Z0>;
+/// m
+pub type Length = Dim<P1, Z0, Z0>;
+/// kg
+pub type Mass = Dim<Z0, P1, Z0>;
+/// s
+pub type Time = Dim<Z0, Z0, P1>;
+/// m²
+pub type Area = Dim<P2, Z0, Z0>;
+/// m³
+pub type Volume = Dim<P3, Z0, Z0>;
+/// 1 / s. Also the dimension of angular velocity (rad / s).
+pub type Frequency = Dim<Z0, Z0, N1>;
+/// 1 / s², the dimension of angular acceleration (rad / s²).
+pub type AngularAcceleration = Dim<Z0, Z0, N2>;
+/// m / s
+pub type Velocity = Dim<P1, Z0, N1>;
+/// m / s²
+pub type Acceleration = Dim<P1, Z0, N2>;
+/// kg m / s², i.e. N
+pub type Force = Dim<P1, P1, N2>;
+/// kg m / s, i.e. N s. Also the dimension of impulse.
+pub type Momentum = Dim<P1, P1, N1>;
+/// kg m² / s², i.e. J. Also the dimension of torque (N m).
+pub type Energy = Dim<P2, P1, N2>;
+/// kg m² / s³, i.e. W
+pub type Power = Dim<P2, P1, N3>;
+/// kg / (m s²), i.e. Pa
+pub type Pressure = Dim<N1, P1, N2>;
+/// kg m² / s
+pub type AngularMomentum = Dim<P2, P1, N1>;
+/// kg m²
+pub type MomentOfInertia = Dim<P2, P1, Z0>;
+/// m³ / (kg s²), the dimension of the gravitational constant, G.
+pub type GravitationalConstant = Dim<P3, N1, N2>;
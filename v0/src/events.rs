@@ -0,0 +1,146 @@
+use crate::{physical_quantities, simulation};
+use std::any::Any;
+use uuid::Uuid;
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/********************* Event ********************/
+
+	#[test]
+	fn new_creates_event() {
+		let particle_id = Uuid::new_v4();
+		let event = Event::new(
+			physical_quantities::Ticks::new(5),
+			EventKind::ParticleCreated { particle_id: particle_id },
+		);
+
+		assert_eq!(event.get_tick(), physical_quantities::Ticks::new(5));
+		match event.get_kind() {
+			EventKind::ParticleCreated { particle_id: id } =>
+				assert_eq!(*id, particle_id),
+			other => panic!("Unexpected event kind: {:?}", other),
+		}
+	}
+
+	#[test]
+	fn event_into_kind_returns_owned_kind() {
+		let event = Event::new(
+			physical_quantities::Ticks::new(0),
+			EventKind::Custom {
+				source_id: Uuid::nil(),
+				name: String::from("boom"),
+				payload: Box::new(42_u32),
+			},
+		);
+
+		match event.into_kind() {
+			EventKind::Custom { name, payload, .. } => {
+				assert_eq!(name, "boom");
+				assert_eq!(*payload.downcast::<u32>().expect("Should be a u32."), 42);
+			},
+			other => panic!("Unexpected event kind: {:?}", other),
+		}
+	}
+}
+
+
+/// Describes something that happened inside the simulation during a tick.
+#[derive(Debug)]
+pub enum EventKind {
+	/// A particle queued with `Simulation::create_particle` was added to the
+	/// simulation.
+	ParticleCreated {
+		particle_id: Uuid,
+	},
+	/// A particle queued with `Simulation::delete_particle` was removed from
+	/// the simulation.
+	ParticleDeleted {
+		particle_id: Uuid,
+	},
+	/// A `Collider` changed the velocities of two colliding particles. Emitted
+	/// once per colliding pair.
+	CollisionResolved {
+		particle_id_a: Uuid,
+		particle_id_b: Uuid,
+	},
+	/// A particle started triggering a field (i.e., it entered the field or
+	/// one of its fields started overlapping the field) on this tick.
+	TriggerEnter {
+		field_owner_id: Uuid,
+		field_name: String,
+		particle_id: Uuid,
+	},
+	/// A particle that triggered a field on the previous tick no longer
+	/// triggers it. This is also emitted when either particle is deleted.
+	TriggerExit {
+		field_owner_id: Uuid,
+		field_name: String,
+		particle_id: Uuid,
+	},
+	/// A user-defined event, emitted with `Simulation::emit_event`.
+	/// `payload` can be anything and can be recovered with
+	/// `payload.downcast_ref::<T>()`.
+	Custom {
+		source_id: Uuid,
+		name: String,
+		payload: Box<dyn Any>,
+	},
+}
+
+/// An event, along with the tick during which it occurred.
+#[derive(Debug)]
+pub struct Event {
+	tick: physical_quantities::Ticks,
+	kind: EventKind,
+}
+
+impl Event {
+	pub fn new(tick: physical_quantities::Ticks, kind: EventKind) -> Self {
+		Self {
+			tick: tick,
+			kind: kind,
+		}
+	}
+
+	/// Gets the tick during which the event occurred. This is the value
+	/// `Simulation::get_elapsed_ticks()` returned before that tick started.
+	pub fn get_tick(&self) -> physical_quantities::Ticks {
+		self.tick
+	}
+
+	pub fn get_kind(&self) -> &EventKind {
+		&self.kind
+	}
+
+	/// Consumes the event, returning what happened. Useful for taking
+	/// ownership of a `Custom` event's payload.
+	pub fn into_kind(self) -> EventKind {
+		self.kind
+	}
+}
+
+/// Receives every event emitted by a simulation. Register an implementation
+/// with `Simulation::subscribe`. Subscribers are called at the end of each tick,
+/// in the order in which they were registered, once for each event emitted
+/// during that tick. Like `Field`, the type parameter is the vector type of the
+/// simulation and defaults to `Vector2`.
+pub trait EventSubscriber<V: physical_quantities::Vector = physical_quantities::Vector2> {
+	/// Called by the simulation for each event emitted during a tick.
+	///
+	/// # Arguments
+	/// * `simulation` - The Simulation that emitted the event. Anything queued
+	///		through it (forces, new particles, etc.) will happen on the next
+	///		tick.
+	/// * `event` - The event. The same event will also be available from
+	///		`Simulation::drain_events`.
+	///
+	/// <div class="warning">
+	///
+	/// Calling `Simulation::subscribe` from this method will panic.
+	///
+	/// </div>
+	fn on_event(&mut self, simulation: &simulation::SimulationN<V>, event: &Event);
+}
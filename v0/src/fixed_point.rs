@@ -0,0 +1,368 @@
+//! A fixed-point number type for simulations that must produce bit-identical
+//! results on every machine (e.g., lockstep multiplayer or replays).
+//!
+//! Floating point arithmetic is deterministic for a single machine and build,
+//! but `sqrt`, `sin`, `atan2` and friends can differ between platforms and
+//! compilers. `Fixed` only uses integer arithmetic, so the same inputs always
+//! give the same outputs. Use it as the scalar type of a simulation:
+//!
+//! ```text
+//! let simulation: Simulation<Fixed> =
+//! 	Simulation::new(Time::new(Fixed::from_f64(0.01)), None, None);
+//! ```
+//!
+//! `Fixed` has 32 integer bits and 32 fractional bits, so it can represent
+//! numbers between roughly -2.1e9 and 2.1e9 with a resolution of about 2.3e-10.
+//! Choose units that keep quantities in that range. For example, the SI
+//! gravitational constant rounds to zero.
+
+use crate::physical_quantities;
+use std::fmt;
+use std::ops;
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::physical_quantities::Scalar;
+
+	fn fixed(value: f64) -> Fixed {
+		Fixed::from_f64(value)
+	}
+
+	#[test]
+	fn converts_to_and_from_f64() {
+		assert_eq!(Fixed::from_raw(1 << 32), fixed(1.0));
+		assert_eq!(fixed(-2.5).get_raw(), -(5 << 31));
+		assert_eq!(fixed(0.25).to_f64(), 0.25);
+		assert_eq!(fixed(f64::INFINITY), Fixed::MAX);
+		assert_eq!(fixed(f64::NEG_INFINITY), Fixed::MIN);
+		assert_eq!(fixed(f64::NAN), Fixed::zero());
+	}
+
+	#[test]
+	fn supports_arithmetic() {
+		assert_eq!(fixed(1.5) + fixed(2.25), fixed(3.75));
+		assert_eq!(fixed(1.5) - fixed(2.25), fixed(-0.75));
+		assert_eq!(fixed(1.5) * fixed(-2.25), fixed(-3.375));
+		assert_eq!(fixed(-3.375) / fixed(1.5), fixed(-2.25));
+		assert_eq!(-fixed(1.5), fixed(-1.5));
+		assert_eq!(fixed(-1.5).abs(), fixed(1.5));
+		assert!(fixed(-1.5) < fixed(0.25));
+	}
+
+	#[test]
+	fn saturates_instead_of_overflowing() {
+		assert_eq!(Fixed::MAX + fixed(1.0), Fixed::MAX);
+		assert_eq!(Fixed::MIN - fixed(1.0), Fixed::MIN);
+		assert_eq!(fixed(1.0e6) * fixed(1.0e6), Fixed::MAX);
+		assert_eq!(fixed(1.0e6) / fixed(-1.0e-6), Fixed::MIN);
+		assert_eq!(-Fixed::MIN, Fixed::MAX);
+	}
+
+	#[test]
+	fn division_by_zero_saturates() {
+		assert_eq!(fixed(2.0) / Fixed::zero(), Fixed::MAX);
+		assert_eq!(fixed(-2.0) / Fixed::zero(), Fixed::MIN);
+		assert_eq!(Fixed::zero() / Fixed::zero(), Fixed::zero());
+	}
+
+	#[test]
+	fn sqrt_matches_f64() {
+		for value in [0.0, 1.0e-9, 0.5, 1.0, 2.0, 1000.0, 123456.789, 2.0e9] {
+			// Compare with the number that was actually stored. 1e-9, for
+			//	example, is only a few multiples of the resolution.
+			let value = fixed(value).to_f64();
+			let error = (fixed(value).sqrt().to_f64() - value.sqrt()).abs();
+			assert!(error < 1.0e-9 * value.sqrt().max(1.0), "sqrt({}) was off by {}", value, error);
+		}
+		assert_eq!(fixed(16.0).sqrt(), fixed(4.0));
+	}
+
+	#[test]
+	#[should_panic(expected = "Fixed.sqrt(): the number must not be negative: -1")]
+	fn sqrt_panics_on_negative_numbers() {
+		fixed(-1.0).sqrt();
+	}
+
+	#[test]
+	fn trig_matches_f64() {
+		let mut angle = -10.0;
+		while angle < 10.0 {
+			let sin_error = (fixed(angle).sin().to_f64() - angle.sin()).abs();
+			let cos_error = (fixed(angle).cos().to_f64() - angle.cos()).abs();
+			assert!(sin_error < 1.0e-8, "sin({}) was off by {}", angle, sin_error);
+			assert!(cos_error < 1.0e-8, "cos({}) was off by {}", angle, cos_error);
+			angle += 0.0137;
+		}
+	}
+
+	#[test]
+	fn atan2_matches_f64_in_every_quadrant() {
+		let points = [
+			(0.0, 1.0), (1.0, 1.0), (1.0, 0.0), (1.0, -1.0), (0.0, -1.0),
+			(-1.0, -1.0), (-1.0, 0.0), (-1.0, 1.0), (3.0e-9, 2.0), (-5000.0, 0.001),
+		];
+		for (y, x) in points {
+			let error = (fixed(y).atan2(fixed(x)).to_f64() - f64::atan2(y, x)).abs();
+			assert!(error < 1.0e-8, "atan2({}, {}) was off by {}", y, x, error);
+		}
+		assert_eq!(Fixed::zero().atan2(Fixed::zero()), Fixed::zero());
+	}
+
+	#[test]
+	fn scales_vector_quantities() {
+		let velocity = physical_quantities::Velocity::new(fixed(1.5), fixed(-2.0));
+		assert_eq!(velocity * fixed(2.0), physical_quantities::Velocity::new(fixed(3.0), fixed(-4.0)));
+		assert_eq!(fixed(2.0) * velocity, physical_quantities::Velocity::new(fixed(3.0), fixed(-4.0)));
+		assert_eq!(velocity / fixed(0.5), physical_quantities::Velocity::new(fixed(3.0), fixed(-4.0)));
+	}
+
+	#[test]
+	fn results_are_bit_identical() {
+		let run = || {
+			let mut value = fixed(0.3);
+			for _ in 0..1000 {
+				value = (value.sin() + value.cos() * fixed(1.7)).abs().sqrt() / fixed(0.9);
+			}
+			value.get_raw()
+		};
+		assert_eq!(run(), run());
+	}
+}
+
+
+// The number of fractional bits.
+const FRACTIONAL_BITS: u32 = 32;
+const ONE: i64 = 1 << FRACTIONAL_BITS;
+
+// Angles, as raw values.
+const PI: i128 = 13_493_037_705;
+const HALF_PI: i128 = 6_746_518_852;
+const TAU: i128 = 26_986_075_409;
+
+// atan(2^-i) for each CORDIC iteration, as raw values.
+const ATAN_TABLE: [i128; 32] = [
+	3_373_259_426, 1_991_351_318, 1_052_175_346, 534_100_635, 268_086_748, 134_174_063,
+	67_103_403, 33_553_749, 16_777_131, 8_388_597, 4_194_303, 2_097_152, 1_048_576, 524_288,
+	262_144, 131_072, 65_536, 32_768, 16_384, 8_192, 4_096, 2_048, 1_024, 512, 256, 128, 64,
+	32, 16, 8, 4, 2,
+];
+
+// The product of 1 / sqrt(1 + 2^(-2i)) over all iterations. Starting CORDIC
+//	with this length cancels out the growth of the vector as it's rotated.
+const CORDIC_GAIN: i128 = 2_608_131_496;
+
+/// A signed Q32.32 fixed-point number. All operations, including `sqrt`,
+/// `sin`, `cos` and `atan2`, are implemented with integer arithmetic and give
+/// identical results on every machine.
+///
+/// # Notes
+/// * Results that are out of range saturate at `Fixed::MAX` or `Fixed::MIN`
+///		instead of overflowing. Dividing a nonzero number by zero gives
+///		`Fixed::MAX` or `Fixed::MIN` depending on the sign of the number, and
+///		dividing zero by zero gives zero.
+/// * Multiplication rounds to the nearest representable number. Division and
+///		`sqrt` round toward zero.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy)]
+#[derive(Hash)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct Fixed(i64);
+
+impl Fixed {
+	/// The largest representable number, a little under 2^31.
+	pub const MAX: Fixed = Fixed(i64::MAX);
+	/// The smallest representable number, -2^31.
+	pub const MIN: Fixed = Fixed(i64::MIN);
+
+	/// Creates a number from its underlying integer, which is the number
+	/// multiplied by 2^32.
+	pub fn from_raw(raw: i64) -> Self {
+		Self(raw)
+	}
+
+	/// Gets the underlying integer, which is the number multiplied by 2^32.
+	pub fn get_raw(&self) -> i64 {
+		self.0
+	}
+
+	// Clamps a wide intermediate result into range.
+	fn saturate(raw: i128) -> Self {
+		Self(raw.clamp(i64::MIN as i128, i64::MAX as i128) as i64)
+	}
+
+	// Gets the sine and cosine of this angle using CORDIC in rotation mode.
+	fn sin_cos(self) -> (Self, Self) {
+		// Reduce the angle to [-π, π], then to [-π/2, π/2], where CORDIC
+		//	converges. Reflecting the angle about ±π/2 keeps the sine and
+		//	negates the cosine.
+		let mut angle = self.0 as i128 % TAU;
+		if angle > PI {
+			angle -= TAU;
+		} else if angle < -PI {
+			angle += TAU;
+		}
+		let mut negate_cosine = false;
+		if angle > HALF_PI {
+			angle = PI - angle;
+			negate_cosine = true;
+		} else if angle < -HALF_PI {
+			angle = -PI - angle;
+			negate_cosine = true;
+		}
+
+		// Rotate (CORDIC_GAIN, 0) by the angle in steps of ±atan(2^-i).
+		let (mut x, mut y, mut z) = (CORDIC_GAIN, 0, angle);
+		for (i, step) in ATAN_TABLE.iter().enumerate() {
+			let (dx, dy) = (y >> i, x >> i);
+			if z >= 0 {
+				x -= dx;
+				y += dy;
+				z -= step;
+			} else {
+				x += dx;
+				y -= dy;
+				z += step;
+			}
+		}
+
+		let cosine = if negate_cosine { -x } else { x };
+		(Self::saturate(y), Self::saturate(cosine))
+	}
+}
+
+impl physical_quantities::Scalar for Fixed {
+	fn from_f64(value: f64) -> Self {
+		// Multiplying by a power of two is exact, and `as` saturates (and
+		//	turns NaN into zero).
+		Self((value * ONE as f64).round() as i64)
+	}
+
+	fn to_f64(self) -> f64 {
+		self.0 as f64 / ONE as f64
+	}
+
+	fn zero() -> Self {
+		Self(0)
+	}
+
+	fn sqrt(self) -> Self {
+		if self.0 < 0 {
+			panic!("Fixed.sqrt(): the number must not be negative: {}", self.to_f64());
+		}
+
+		// sqrt(raw * 2^32) = sqrt(x) * 2^32, the raw value of the result.
+		Self((((self.0 as u128) << FRACTIONAL_BITS).isqrt()) as i64)
+	}
+
+	fn abs(self) -> Self {
+		Self(self.0.saturating_abs())
+	}
+
+	fn sin(self) -> Self {
+		self.sin_cos().0
+	}
+
+	fn cos(self) -> Self {
+		self.sin_cos().1
+	}
+
+	fn atan2(self, x: Self) -> Self {
+		let (mut x, mut y) = (x.0 as i128, self.0 as i128);
+		if x == 0 && y == 0 {
+			return Self(0);
+		}
+
+		// CORDIC in vectoring mode only converges in the right half-plane.
+		//	Rotate points on the left by π first.
+		let mut z = 0;
+		if x < 0 {
+			z = if y >= 0 { PI } else { -PI };
+			x = -x;
+			y = -y;
+		}
+
+		// Scale the point up so that small inputs don't lose precision to the
+		//	shifts below. The angle doesn't depend on the length.
+		let shift = (x.abs().max(y.abs())).leading_zeros().saturating_sub(66);
+		x <<= shift;
+		y <<= shift;
+
+		// Rotate the point onto the x axis in steps of ±atan(2^-i), keeping
+		//	track of the total rotation.
+		for (i, step) in ATAN_TABLE.iter().enumerate() {
+			let (dx, dy) = (y >> i, x >> i);
+			if y > 0 {
+				x += dx;
+				y -= dy;
+				z += step;
+			} else {
+				x -= dx;
+				y += dy;
+				z -= step;
+			}
+		}
+
+		Self::saturate(z)
+	}
+}
+
+impl fmt::Debug for Fixed {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Fixed({})", physical_quantities::Scalar::to_f64(*self))
+	}
+}
+
+impl ops::Add for Fixed {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		Self(self.0.saturating_add(rhs.0))
+	}
+}
+
+impl ops::Sub for Fixed {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self(self.0.saturating_sub(rhs.0))
+	}
+}
+
+impl ops::Mul for Fixed {
+	type Output = Self;
+
+	fn mul(self, rhs: Self) -> Self::Output {
+		// Round to nearest by adding half of the last bit before shifting.
+		let product = self.0 as i128 * rhs.0 as i128;
+		Self::saturate((product + (1 << (FRACTIONAL_BITS - 1))) >> FRACTIONAL_BITS)
+	}
+}
+
+impl ops::Div for Fixed {
+	type Output = Self;
+
+	fn div(self, rhs: Self) -> Self::Output {
+		if rhs.0 == 0 {
+			return match self.0 {
+				0 => Self(0),
+				n if n > 0 => Self::MAX,
+				_ => Self::MIN,
+			};
+		}
+
+		Self::saturate(((self.0 as i128) << FRACTIONAL_BITS) / rhs.0 as i128)
+	}
+}
+
+impl ops::Neg for Fixed {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		Self(self.0.saturating_neg())
+	}
+}
+
+// `Velocity<Fixed> * Fixed` and the rest. See `physical_quantities`.
+physical_quantities::impl_scalar_operations!(Fixed);
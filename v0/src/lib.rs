@@ -1,7 +1,20 @@
+pub mod dimensions;
+pub mod events;
+#[cfg(feature = "fixed-point")]
+pub mod fixed_point;
+#[cfg(feature = "persistence")]
+pub mod persistence;
 pub mod physical_quantities;
+pub mod recording;
+#[cfg(feature = "scenes")]
+pub mod scene;
 pub mod simulation;
 pub mod simulation_objects;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod units;
 pub mod utilities;
+pub mod validation;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,1015 @@
+//! Saving and loading simulations as JSON or a compact binary format.
+//! Enabled by the `persistence` feature.
+//!
+//! Only two-dimensional simulations with `f64` numbers (`Simulation`) can be
+//! saved. The saved state stores particles, regions and rotations in two
+//! dimensions, so `Simulation3` and simulations with other number types have
+//! no `save_state` or `from_state`:
+//! ```compile_fail
+//! use v0::physical_quantities::Time;
+//! use v0::simulation::Simulation3;
+//!
+//! let simulation = Simulation3::new(Time::new(0.01), None, None);
+//! v0::persistence::to_json(&simulation);
+//! ```
+//! Use `recording` to keep the trajectories of other simulations instead.
+
+use crate::{physical_quantities, simulation, simulation_objects, units};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use uuid::Uuid;
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::simulation::Simulation;
+
+	/********************* Helpers ********************/
+
+	// A user-defined field that is registered by type tag.
+	#[derive(Serialize, Deserialize)]
+	struct SpinField {
+		strength: f64,
+		name: String,
+	}
+
+	impl simulation_objects::Field for SpinField {
+		fn effect(
+			&self,
+			_simulation: &Simulation,
+			_position: physical_quantities::Displacement,
+			_triggered_by: HashMap<Uuid, Vec<Option<simulation_objects::FieldInfo>>>,
+			_field_owner_id: Uuid,
+		) {
+		}
+
+		fn get_radius(&self) -> f64 {
+			0.0
+		}
+
+		fn affects_self(&self) -> bool {
+			false
+		}
+
+		fn affects_others(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_fields(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_particles(&self) -> bool {
+			false
+		}
+
+		fn get_name(&self) -> &String {
+			&self.name
+		}
+
+		fn save(&self) -> Option<SavedObject> {
+			Some(SavedObject::new("SpinField", self))
+		}
+	}
+
+	// A field that doesn't implement save().
+	struct UnsavableField {
+		name: String,
+	}
+
+	impl simulation_objects::Field for UnsavableField {
+		fn effect(
+			&self,
+			_simulation: &Simulation,
+			_position: physical_quantities::Displacement,
+			_triggered_by: HashMap<Uuid, Vec<Option<simulation_objects::FieldInfo>>>,
+			_field_owner_id: Uuid,
+		) {
+		}
+
+		fn get_radius(&self) -> f64 {
+			0.0
+		}
+
+		fn affects_self(&self) -> bool {
+			false
+		}
+
+		fn affects_others(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_fields(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_particles(&self) -> bool {
+			false
+		}
+
+		fn get_name(&self) -> &String {
+			&self.name
+		}
+	}
+
+	// Creates a simulation with a bit of everything in it, including queued
+	//	actions that haven't happened yet. Returns the simulation and the IDs
+	//	of the (existing, pending) particles.
+	fn create_busy_simulation() -> (Simulation, Uuid, Uuid, Uuid) {
+		let simulation = Simulation::new(
+			physical_quantities::Time::new(0.01),
+			Some(2.0),
+			None,
+		);
+		simulation.set_layer_interaction(3, 4, false);
+		simulation.create_pair_interaction(
+			String::from("gravity"),
+			String::from("gravity"),
+			Box::new(simulation_objects::UniversalGravitation::new(
+				f64::INFINITY,
+				Some(1.0),
+				Some(0.1),
+			)),
+		);
+		simulation.create_world_field(
+			simulation_objects::Region::Circle {
+				center: physical_quantities::Displacement::new(0.0, 0.0),
+				radius: 100.0,
+			},
+			Box::new(simulation_objects::LinearDragField::new(0.1, None)),
+		);
+		let particle_id_a = simulation.create_particle(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(-1.0, 0.0),
+			vec!(
+				Box::new(simulation_objects::MarkerField::new(String::from("gravity"))),
+				Box::new(simulation_objects::Collider::new(
+					0.5,
+					0.9,
+					Some(simulation_objects::Layers::layer(3)),
+					None,
+				)),
+			),
+		);
+		let particle_id_b = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(1.0, 0.0),
+			vec!(
+				Box::new(simulation_objects::MarkerField::new(String::from("gravity"))),
+				Box::new(SpinField { strength: 3.0, name: String::from("spin") }),
+			),
+		);
+		simulation.step();
+		simulation.step();
+
+		// Queue up some things that will happen on the next tick.
+		simulation.apply_force(particle_id_a, physical_quantities::Force::new(0.0, 5.0));
+		let pending_id = simulation.create_particle(
+			physical_quantities::Mass::new(3.0),
+			physical_quantities::Displacement::new(0.0, 1.0),
+			vec!(Box::new(simulation_objects::MarkerField::new(String::from("gravity")))),
+		);
+
+		(simulation, particle_id_a, particle_id_b, pending_id)
+	}
+
+	fn create_registry() -> Registry {
+		let mut registry = Registry::new();
+		registry.register_field::<SpinField>("SpinField");
+		registry
+	}
+
+	// Steps both simulations and verifies that they stay in lockstep.
+	fn assert_simulations_match(original: &Simulation, loaded: &Simulation, ids: &[Uuid]) {
+		assert_eq!(original.get_elapsed_ticks(), loaded.get_elapsed_ticks());
+		assert_eq!(original.get_tick_duration(), loaded.get_tick_duration());
+		for _ in 0..50 {
+			original.step();
+			loaded.step();
+		}
+		for id in ids {
+			assert_eq!(original.get_position(*id), loaded.get_position(*id));
+			assert_eq!(original.get_velocity(*id), loaded.get_velocity(*id));
+			assert_eq!(original.get_mass(*id), loaded.get_mass(*id));
+			let original_names: Vec<String> = original.get_field_info(*id)
+				.iter().map(|info| info.get_name().clone()).collect();
+			let loaded_names: Vec<String> = loaded.get_field_info(*id)
+				.iter().map(|info| info.get_name().clone()).collect();
+			assert_eq!(original_names, loaded_names);
+		}
+	}
+
+	/********************* Round trips ********************/
+
+	#[test]
+	fn json_round_trip_preserves_simulation() {
+		let (original, a, b, pending) = create_busy_simulation();
+		let json = to_json(&original).expect("Should save.");
+		let loaded = from_json(&json, &create_registry(), None).expect("Should load.");
+
+		// The pending particle hasn't been added yet in either simulation.
+		assert!(std::panic::catch_unwind(
+			std::panic::AssertUnwindSafe(|| loaded.get_mass(pending))
+		).is_err());
+		assert_eq!(loaded.get_layer_mask(3), original.get_layer_mask(3));
+
+		assert_simulations_match(&original, &loaded, &[a, b, pending]);
+	}
+
+	#[test]
+	fn binary_round_trip_preserves_simulation() {
+		let (original, a, b, pending) = create_busy_simulation();
+		let bytes = to_binary(&original).expect("Should save.");
+		let json = to_json(&original).expect("Should save.");
+		assert!(bytes.len() < json.len());
+
+		let loaded = from_binary(&bytes, &create_registry(), None).expect("Should load.");
+		assert_simulations_match(&original, &loaded, &[a, b, pending]);
+	}
+
+	#[test]
+	fn round_trip_preserves_pending_deletions() {
+		let (original, a, b, pending) = create_busy_simulation();
+		original.delete_particle(b);
+		let loaded = from_json(
+			&to_json(&original).expect("Should save."),
+			&create_registry(),
+			None,
+		).expect("Should load.");
+
+		loaded.step();
+		assert!(std::panic::catch_unwind(
+			std::panic::AssertUnwindSafe(|| loaded.get_mass(b))
+		).is_err());
+		original.step();
+		assert_simulations_match(&original, &loaded, &[a, pending]);
+	}
+
+	#[test]
+	fn round_trip_preserves_rotation_and_pending_torques() {
+		let original = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let particle_id = original.create_particle_with_rotation(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation_objects::Rotation::new(
+				physical_quantities::Angle::new(0.5),
+				physical_quantities::AngularVelocity::new(1.0),
+				physical_quantities::MomentOfInertia::new(2.0),
+			),
+			Vec::new(),
+		);
+		original.step();
+		original.apply_torque(particle_id, physical_quantities::Torque::new(4.0));
+
+		let loaded = from_json(&to_json(&original).expect("Should save."), &Registry::new(), None)
+			.expect("Should load.");
+		assert_eq!(loaded.get_rotation(particle_id), original.get_rotation(particle_id));
+
+		original.step();
+		loaded.step();
+		assert_eq!(loaded.get_rotation(particle_id), original.get_rotation(particle_id));
+		assert_eq!(loaded.get_angular_velocity(particle_id), physical_quantities::AngularVelocity::new(3.0));
+	}
+
+	#[test]
+	fn round_trip_preserves_advance_scheduling_and_previous_positions() {
+		let original = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		original.set_max_ticks_per_advance(physical_quantities::Ticks::new(7));
+		original.set_scheduling_strategy(simulation::SchedulingStrategy::Hybrid {
+			spin: std::time::Duration::from_millis(3),
+		});
+		let particle_id = original.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(2.0, 0.0),
+			Vec::new(),
+		);
+		original.step();
+		original.step();
+
+		let json = to_json(&original).expect("Should save.");
+		let loaded = from_json(&json, &Registry::new(), None).expect("Should load.");
+		assert_eq!(loaded.get_max_ticks_per_advance(), physical_quantities::Ticks::new(7));
+		assert_eq!(loaded.get_scheduling_strategy(), original.get_scheduling_strategy());
+		assert_ne!(original.get_previous_position(particle_id), original.get_position(particle_id));
+		assert_eq!(
+			loaded.get_previous_position(particle_id),
+			original.get_previous_position(particle_id),
+		);
+
+		// State saved before these settings uses the defaults, and particles
+		//	start with their previous position at their current position.
+		let mut json: serde_json::Value = serde_json::from_str(&json).unwrap();
+		let object = json.as_object_mut().unwrap();
+		object.remove("max_ticks_per_advance");
+		object.remove("scheduling_strategy");
+		for particle in object["particles"].as_array_mut().unwrap() {
+			particle.as_object_mut().unwrap().remove("previous_position");
+		}
+		let loaded = from_json(&json.to_string(), &Registry::new(), None).expect("Should load.");
+		let defaults = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		assert_eq!(loaded.get_max_ticks_per_advance(), defaults.get_max_ticks_per_advance());
+		assert_eq!(loaded.get_scheduling_strategy(), defaults.get_scheduling_strategy());
+		assert_eq!(
+			loaded.get_previous_position(particle_id),
+			loaded.get_position(particle_id),
+		);
+	}
+
+	/********************* Errors ********************/
+
+	#[test]
+	fn load_fails_on_invalid_settings() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), Some(1.0), None);
+		let json: serde_json::Value =
+			serde_json::from_str(&to_json(&simulation).expect("Should save.")).unwrap();
+		let too_many_masks = vec!(
+			json["layer_masks"][0].clone();
+			simulation_objects::Layers::COUNT as usize + 1
+		);
+
+		for (key, value) in [
+			("tick_duration", serde_json::json!(0.0)),
+			("tick_duration", serde_json::json!(-1.0)),
+			("simulation_speed", serde_json::json!(0.0)),
+			("simulation_speed", serde_json::json!(-2.0)),
+			("layer_masks", serde_json::json!(too_many_masks)),
+			("max_ticks_per_advance", serde_json::json!(0)),
+		] {
+			let mut json = json.clone();
+			json[key] = value;
+			match from_json(&json.to_string(), &Registry::new(), None) {
+				Err(PersistenceError::InvalidState { .. }) => (),
+				other => panic!("Unexpected result for {}: {:?}", key, other.map(|_| ())),
+			}
+		}
+	}
+
+	// Saves a simulation with one spinning particle that has a gravity field,
+	//	and a gravity pair interaction, as JSON that tests can tamper with.
+	fn save_spinning_particle_as_json() -> serde_json::Value {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle_with_rotation(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation_objects::Rotation::new(
+				physical_quantities::Angle::new(0.0),
+				physical_quantities::AngularVelocity::new(1.0),
+				physical_quantities::MomentOfInertia::new(3.0),
+			),
+			vec!(Box::new(simulation_objects::UniversalGravitationField::new(
+				10.0,
+				None,
+				None,
+				None,
+				None,
+				None,
+			))),
+		);
+		simulation.create_pair_interaction(
+			String::from("a"),
+			String::from("b"),
+			Box::new(simulation_objects::UniversalGravitation::new(10.0, None, None)),
+		);
+		// Add the particle and the pair interaction.
+		simulation.step();
+
+		serde_json::from_str(&to_json(&simulation).expect("Should save.")).unwrap()
+	}
+
+	fn expect_invalid_state(json: serde_json::Value) -> String {
+		match from_json(&json.to_string(), &Registry::new(), None) {
+			Err(PersistenceError::InvalidState { message }) => message,
+			other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+		}
+	}
+
+	#[test]
+	fn load_succeeds_on_untampered_state() {
+		let json = save_spinning_particle_as_json();
+
+		assert!(from_json(&json.to_string(), &Registry::new(), None).is_ok());
+	}
+
+	#[test]
+	fn load_fails_on_non_positive_mass() {
+		for mass in [0.0, -5.0] {
+			let mut json = save_spinning_particle_as_json();
+			json["particles"][0]["mass"] = serde_json::json!(mass);
+
+			let message = expect_invalid_state(json);
+			assert!(message.contains("mass must be positive"), "{}", message);
+		}
+	}
+
+	#[test]
+	fn load_fails_on_non_positive_moment_of_inertia() {
+		for moment_of_inertia in [0.0, -3.0] {
+			let mut json = save_spinning_particle_as_json();
+			json["particles"][0]["rotation"]["moment_of_inertia"] = serde_json::json!(moment_of_inertia);
+
+			let message = expect_invalid_state(json);
+			assert!(message.contains("moment of inertia must be positive"), "{}", message);
+		}
+	}
+
+	#[test]
+	fn load_fails_on_duplicate_particle_ids() {
+		let mut json = save_spinning_particle_as_json();
+		let particle = json["particles"][0].clone();
+		json["particles_to_add"] = serde_json::json!([particle]);
+		let message = expect_invalid_state(json);
+		assert!(message.contains("more than one particle"), "{}", message);
+
+		let mut json = save_spinning_particle_as_json();
+		let particle = json["particles"][0].clone();
+		json["particles"] = serde_json::json!([particle.clone(), particle]);
+		let message = expect_invalid_state(json);
+		assert!(message.contains("more than one particle"), "{}", message);
+	}
+
+	#[test]
+	fn load_fails_on_negative_softening_length() {
+		let mut json = save_spinning_particle_as_json();
+		json["particles"][0]["fields"][0]["data"]["gravitation"]["softening_length"] =
+			serde_json::json!(-1.0);
+		let message = expect_invalid_state(json);
+		assert!(message.contains("softening_length must not be negative"), "{}", message);
+
+		let mut json = save_spinning_particle_as_json();
+		json["pair_interactions"][0]["interaction"]["data"]["softening_length"] =
+			serde_json::json!(-1.0);
+		let message = expect_invalid_state(json);
+		assert!(message.contains("softening_length must not be negative"), "{}", message);
+	}
+
+	#[test]
+	fn save_fails_on_field_without_save() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec!(Box::new(UnsavableField { name: String::from("nope") })),
+		);
+
+		match to_json(&simulation) {
+			Err(PersistenceError::UnsavableField { name }) => assert_eq!(name, "nope"),
+			other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+		}
+	}
+
+	#[test]
+	fn load_fails_on_unregistered_type() {
+		let (original, _, _, _) = create_busy_simulation();
+		let json = to_json(&original).expect("Should save.");
+
+		// SpinField isn't registered.
+		match from_json(&json, &Registry::new(), None) {
+			Err(PersistenceError::UnknownType { type_tag }) => assert_eq!(type_tag, "SpinField"),
+			other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+		}
+	}
+
+	#[test]
+	fn round_trip_preserves_elapsed_time_across_tick_duration_changes() {
+		let original = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		original.step();
+		original.set_tick_duration(physical_quantities::Time::new(0.25));
+		original.step();
+
+		let loaded = from_json(&to_json(&original).expect("Should save."), &Registry::new(), None)
+			.expect("Should load.");
+		assert_eq!(loaded.get_elapsed_time(), physical_quantities::Time::new(0.75));
+
+		loaded.step();
+		assert_eq!(loaded.get_elapsed_time(), physical_quantities::Time::new(1.0));
+	}
+
+	#[test]
+	fn round_trip_preserves_unit_system() {
+		let original = Simulation::new(physical_quantities::Time::new(0.01), None, None);
+		original.set_unit_system(units::UnitSystem::MOLECULAR);
+
+		let loaded = from_json(&to_json(&original).expect("Should save."), &Registry::new(), None)
+			.expect("Should load.");
+		assert_eq!(loaded.get_unit_system(), units::UnitSystem::MOLECULAR);
+
+		// State saved before unit systems was always in SI units.
+		let mut json: serde_json::Value =
+			serde_json::from_str(&to_json(&original).expect("Should save.")).unwrap();
+		json.as_object_mut().unwrap().remove("unit_system");
+		let loaded = from_json(&json.to_string(), &Registry::new(), None).expect("Should load.");
+		assert_eq!(loaded.get_unit_system(), units::UnitSystem::SI);
+	}
+
+	#[test]
+	fn load_computes_elapsed_time_missing_from_older_state() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		simulation.step();
+		simulation.step();
+		let mut json: serde_json::Value =
+			serde_json::from_str(&to_json(&simulation).expect("Should save.")).unwrap();
+		json.as_object_mut().unwrap().remove("elapsed_time");
+
+		let loaded = from_json(&json.to_string(), &Registry::new(), None).expect("Should load.");
+		assert_eq!(loaded.get_elapsed_time(), physical_quantities::Time::new(1.0));
+	}
+
+	#[test]
+	fn load_fails_on_unsupported_version() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let json = to_json(&simulation).expect("Should save.")
+			.replacen(
+				&format!("\"version\": {}", FORMAT_VERSION),
+				&format!("\"version\": {}", FORMAT_VERSION + 1),
+				1,
+			);
+
+		match from_json(&json, &Registry::new(), None) {
+			Err(PersistenceError::UnsupportedVersion { found, supported }) => {
+				assert_eq!(found, FORMAT_VERSION + 1);
+				assert_eq!(supported, FORMAT_VERSION);
+			},
+			other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+		}
+	}
+
+	#[test]
+	fn load_fails_on_invalid_field_data() {
+		let mut registry = Registry::new();
+		registry.register_field::<SpinField>("SpinField");
+		let saved = SavedObject {
+			type_tag: String::from("SpinField"),
+			data: serde_json::json!({ "strength": "very" }),
+		};
+
+		match registry.load_field(saved) {
+			Err(PersistenceError::InvalidData { type_tag, .. }) => assert_eq!(type_tag, "SpinField"),
+			other => panic!("Unexpected result: {:?}", other.map(|_| ())),
+		}
+	}
+
+	/********************* extended_f64 ********************/
+
+	#[test]
+	fn extended_f64_round_trips_infinity() {
+		#[derive(Serialize, Deserialize)]
+		struct Wrapper {
+			#[serde(with = "extended_f64")]
+			value: f64,
+		}
+
+		for value in [f64::INFINITY, f64::NEG_INFINITY, 1.5] {
+			let json = serde_json::to_string(&Wrapper { value: value }).expect("Should serialize.");
+			let wrapper: Wrapper = serde_json::from_str(&json).expect("Should deserialize.");
+			assert_eq!(wrapper.value, value);
+		}
+		let json = serde_json::to_string(&Wrapper { value: f64::NAN }).expect("Should serialize.");
+		let wrapper: Wrapper = serde_json::from_str(&json).expect("Should deserialize.");
+		assert!(wrapper.value.is_nan());
+	}
+}
+
+
+/// The version of the saved state format written by this version of the
+/// library. Loading state with a different version fails with
+/// `PersistenceError::UnsupportedVersion`.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Something that went wrong while saving or loading a simulation.
+#[derive(Debug)]
+pub enum PersistenceError {
+	/// The JSON could not be read or written.
+	Json(serde_json::Error),
+	/// The binary data could not be read or written.
+	Binary(String),
+	/// The saved state was written by an incompatible version of the library.
+	UnsupportedVersion {
+		found: u32,
+		supported: u32,
+	},
+	/// A saved field or pair interaction has a type tag that isn't in the
+	/// `Registry`.
+	UnknownType {
+		type_tag: String,
+	},
+	/// A saved field or pair interaction couldn't be recreated from its data.
+	InvalidData {
+		type_tag: String,
+		message: String,
+	},
+	/// A field doesn't support saving (its `save` method returned `None`).
+	UnsavableField {
+		name: String,
+	},
+	/// A pair interaction doesn't support saving (its `save` method returned
+	/// `None`).
+	UnsavablePairInteraction {
+		field_name_a: String,
+		field_name_b: String,
+	},
+	/// The saved state has a setting the simulation can't use, e.g., a tick
+	/// duration that isn't positive.
+	InvalidState {
+		message: String,
+	},
+}
+
+impl fmt::Display for PersistenceError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			PersistenceError::Json(e) => write!(f, "invalid JSON: {}", e),
+			PersistenceError::Binary(message) => write!(f, "invalid binary data: {}", message),
+			PersistenceError::UnsupportedVersion { found, supported } => write!(
+				f,
+				"unsupported format version {} (this library reads version {})",
+				found,
+				supported,
+			),
+			PersistenceError::UnknownType { type_tag } =>
+				write!(f, "no type is registered with the tag \"{}\"", type_tag),
+			PersistenceError::InvalidData { type_tag, message } =>
+				write!(f, "invalid data for \"{}\": {}", type_tag, message),
+			PersistenceError::UnsavableField { name } =>
+				write!(f, "the field \"{}\" does not support saving", name),
+			PersistenceError::UnsavablePairInteraction { field_name_a, field_name_b } => write!(
+				f,
+				"the pair interaction between \"{}\" and \"{}\" does not support saving",
+				field_name_a,
+				field_name_b,
+			),
+			PersistenceError::InvalidState { message } =>
+				write!(f, "invalid simulation state: {}", message),
+		}
+	}
+}
+
+impl std::error::Error for PersistenceError {}
+
+impl From<serde_json::Error> for PersistenceError {
+	fn from(e: serde_json::Error) -> Self {
+		PersistenceError::Json(e)
+	}
+}
+
+/// A serialized field or pair interaction, along with the type tag used to
+/// find the code that can recreate it.
+#[derive(Debug)]
+#[derive(Clone, PartialEq)]
+#[derive(Serialize, Deserialize)]
+pub struct SavedObject {
+	type_tag: String,
+	data: serde_json::Value,
+}
+
+impl SavedObject {
+	/// Creates an instance of `SavedObject`. Usually called from
+	/// `Field::save` as `SavedObject::new("MyField", self)`.
+	///
+	/// # Arguments
+	/// * `type_tag` - The tag the object's type is registered with.
+	/// * `object` - The object to serialize.
+	///
+	/// # Panics
+	/// Panics if `object` can't be represented as JSON (e.g., it contains a
+	/// map with non-string keys).
+	pub fn new<T: Serialize>(type_tag: &str, object: &T) -> Self {
+		let data = match serde_json::to_value(object) {
+			Ok(data) => data,
+			Err(e) => panic!("SavedObject.new(): could not serialize \"{}\": {}", type_tag, e),
+		};
+
+		Self {
+			type_tag: String::from(type_tag),
+			data: data,
+		}
+	}
+
+	pub fn get_type_tag(&self) -> &String {
+		&self.type_tag
+	}
+
+	pub fn get_data(&self) -> &serde_json::Value {
+		&self.data
+	}
+}
+
+type FieldLoader =
+	Box<dyn Fn(serde_json::Value) -> Result<Box<dyn simulation_objects::Field>, String>>;
+type PairInteractionLoader =
+	Box<dyn Fn(serde_json::Value) -> Result<Box<dyn simulation_objects::PairInteraction>, String>>;
+
+/// Maps type tags to the code that recreates fields and pair interactions of
+/// those types. `Registry::new` registers all of the library's built-in types.
+/// User-defined types must be registered before loading a simulation that
+/// contains them.
+pub struct Registry {
+	fields: HashMap<String, FieldLoader>,
+	pair_interactions: HashMap<String, PairInteractionLoader>,
+}
+
+impl Registry {
+	/// Creates a registry containing the library's built-in field and pair
+	/// interaction types.
+	pub fn new() -> Self {
+		let mut registry = Self {
+			fields: HashMap::new(),
+			pair_interactions: HashMap::new(),
+		};
+
+		registry.register_field::<simulation_objects::SimpleSelfGravityField>("SimpleSelfGravityField");
+		registry.register_field::<simulation_objects::MarkerField>("MarkerField");
+		registry.register_field::<simulation_objects::UniformForceField>("UniformForceField");
+		registry.register_field::<simulation_objects::LinearDragField>("LinearDragField");
+		registry.register_field::<simulation_objects::UniversalGravitationField>("UniversalGravitationField");
+		registry.register_field::<simulation_objects::Collider>("Collider");
+		registry.register_pair_interaction::<simulation_objects::UniversalGravitation>("UniversalGravitation");
+
+		registry
+	}
+
+	/// Registers a field type that can be deserialized with serde. Its `save`
+	/// method should return `Some(SavedObject::new(type_tag, self))`.
+	/// Registering a tag again replaces the previous registration.
+	pub fn register_field<T>(&mut self, type_tag: &str)
+	where
+		T: simulation_objects::Field + DeserializeOwned + 'static,
+	{
+		self.register_field_loader(type_tag, |data| {
+			let field: T = serde_json::from_value(data).map_err(|e| e.to_string())?;
+			Ok(Box::new(field))
+		});
+	}
+
+	/// Registers a function that creates a field from saved data. Useful for
+	/// field types that can't simply be deserialized. The function should
+	/// return an error message if the data is invalid.
+	pub fn register_field_loader<F>(&mut self, type_tag: &str, loader: F)
+	where
+		F: Fn(serde_json::Value) -> Result<Box<dyn simulation_objects::Field>, String> + 'static,
+	{
+		self.fields.insert(String::from(type_tag), Box::new(loader));
+	}
+
+	/// Registers a pair interaction type that can be deserialized with serde.
+	/// See `register_field`.
+	pub fn register_pair_interaction<T>(&mut self, type_tag: &str)
+	where
+		T: simulation_objects::PairInteraction + DeserializeOwned + 'static,
+	{
+		self.register_pair_interaction_loader(type_tag, |data| {
+			let interaction: T = serde_json::from_value(data).map_err(|e| e.to_string())?;
+			Ok(Box::new(interaction))
+		});
+	}
+
+	/// Registers a function that creates a pair interaction from saved data.
+	/// See `register_field_loader`.
+	pub fn register_pair_interaction_loader<F>(&mut self, type_tag: &str, loader: F)
+	where
+		F: Fn(serde_json::Value) -> Result<Box<dyn simulation_objects::PairInteraction>, String>
+			+ 'static,
+	{
+		self.pair_interactions.insert(String::from(type_tag), Box::new(loader));
+	}
+
+	/// Recreates a field from saved data.
+	pub fn load_field(
+		&self,
+		saved: SavedObject,
+	) -> Result<Box<dyn simulation_objects::Field>, PersistenceError> {
+		match self.fields.get(&saved.type_tag) {
+			Some(loader) => loader(saved.data).map_err(|message| PersistenceError::InvalidData {
+				type_tag: saved.type_tag,
+				message: message,
+			}),
+			None => Err(PersistenceError::UnknownType { type_tag: saved.type_tag }),
+		}
+	}
+
+	/// Recreates a pair interaction from saved data.
+	pub fn load_pair_interaction(
+		&self,
+		saved: SavedObject,
+	) -> Result<Box<dyn simulation_objects::PairInteraction>, PersistenceError> {
+		match self.pair_interactions.get(&saved.type_tag) {
+			Some(loader) => loader(saved.data).map_err(|message| PersistenceError::InvalidData {
+				type_tag: saved.type_tag,
+				message: message,
+			}),
+			None => Err(PersistenceError::UnknownType { type_tag: saved.type_tag }),
+		}
+	}
+}
+
+impl Default for Registry {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Everything needed to recreate a two-dimensional `f64` `Simulation`, as
+/// returned by `Simulation::save_state`. The `on_tick` function and event subscribers
+/// can't be saved and must be provided again when loading. Events that
+/// haven't been drained are not saved.
+#[derive(Serialize, Deserialize)]
+pub struct SimulationState {
+	pub(crate) version: u32,
+	pub(crate) tick_duration: physical_quantities::Time,
+	pub(crate) simulation_speed: Option<f64>,
+	// Missing from state saved before unit systems, which was always SI.
+	#[serde(default)]
+	pub(crate) unit_system: units::UnitSystem,
+	pub(crate) elapsed_ticks: physical_quantities::Ticks,
+	// Missing from state saved before the tick duration could be changed.
+	#[serde(default)]
+	pub(crate) elapsed_time: Option<physical_quantities::Time>,
+	pub(crate) particles: Vec<ParticleState>,
+	pub(crate) world_fields: Vec<WorldFieldState>,
+	pub(crate) pair_interactions: Vec<PairInteractionState>,
+	pub(crate) layer_masks: Vec<simulation_objects::Layers>,
+	pub(crate) active_triggers: Vec<TriggerState>,
+	pub(crate) applied_forces: Vec<(Uuid, Vec<physical_quantities::Force>)>,
+	// Missing from state saved before particles could spin.
+	#[serde(default)]
+	pub(crate) applied_torques: Vec<(Uuid, Vec<physical_quantities::Torque>)>,
+	pub(crate) particle_ids_to_delete: Vec<Uuid>,
+	pub(crate) particles_to_add: Vec<ParticleState>,
+	pub(crate) world_field_ids_to_delete: Vec<Uuid>,
+	pub(crate) world_fields_to_add: Vec<WorldFieldState>,
+	pub(crate) pair_interaction_ids_to_delete: Vec<Uuid>,
+	pub(crate) pair_interactions_to_add: Vec<PairInteractionState>,
+	// Missing from state saved before `advance` could be limited.
+	#[serde(default)]
+	pub(crate) max_ticks_per_advance: Option<physical_quantities::Ticks>,
+	// Missing from state saved before the scheduling strategy could be chosen.
+	#[serde(default)]
+	pub(crate) scheduling_strategy: Option<simulation::SchedulingStrategy>,
+}
+
+impl SimulationState {
+	pub fn get_version(&self) -> u32 {
+		self.version
+	}
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct ParticleState {
+	pub(crate) id: Uuid,
+	pub(crate) mass: physical_quantities::Mass,
+	pub(crate) position: physical_quantities::Displacement,
+	pub(crate) velocity: physical_quantities::Velocity,
+	// Missing from state saved before particles could spin.
+	#[serde(default)]
+	pub(crate) rotation: Option<simulation_objects::Rotation>,
+	// Missing from state saved before particles remembered their previous
+	//	position.
+	#[serde(default)]
+	pub(crate) previous_position: Option<physical_quantities::Displacement>,
+	pub(crate) fields: Vec<SavedObject>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct WorldFieldState {
+	pub(crate) id: Uuid,
+	pub(crate) region: simulation_objects::Region,
+	pub(crate) field: SavedObject,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PairInteractionState {
+	pub(crate) id: Uuid,
+	pub(crate) field_name_a: String,
+	pub(crate) field_name_b: String,
+	pub(crate) interaction: SavedObject,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TriggerState {
+	pub(crate) field_owner_id: Uuid,
+	pub(crate) field_index: usize,
+	pub(crate) particle_id: Uuid,
+	pub(crate) field_name: String,
+}
+
+// Just enough of a SimulationState to check its version before trying to read
+//	the rest of it.
+#[derive(Deserialize)]
+struct VersionHeader {
+	version: u32,
+}
+
+fn check_version(version: u32) -> Result<(), PersistenceError> {
+	if version != FORMAT_VERSION {
+		return Err(PersistenceError::UnsupportedVersion {
+			found: version,
+			supported: FORMAT_VERSION,
+		});
+	}
+
+	Ok(())
+}
+
+/// Saves a simulation as human-readable JSON.
+///
+/// # Examples
+/// ```
+/// use v0::physical_quantities::Time;
+/// use v0::persistence::{self, Registry};
+/// use v0::simulation::Simulation;
+///
+/// let simulation = Simulation::new(Time::new(0.01), None, None);
+/// let json = persistence::to_json(&simulation).unwrap();
+/// let loaded = persistence::from_json(&json, &Registry::new(), None).unwrap();
+/// assert_eq!(loaded.get_tick_duration(), Time::new(0.01));
+/// ```
+pub fn to_json(simulation: &simulation::Simulation) -> Result<String, PersistenceError> {
+	let state = simulation.save_state()?;
+	Ok(serde_json::to_string_pretty(&state)?)
+}
+
+/// Loads a simulation saved with `to_json`. The loaded simulation is paused.
+///
+/// # Arguments
+/// * `json` - The saved simulation.
+/// * `registry` - Used to recreate fields and pair interactions.
+/// * `on_tick` - The function to call on each tick. See `Simulation::new`.
+pub fn from_json(
+	json: &str,
+	registry: &Registry,
+	on_tick: Option<fn(&simulation::Simulation)>,
+) -> Result<simulation::Simulation, PersistenceError> {
+	let header: VersionHeader = serde_json::from_str(json)?;
+	check_version(header.version)?;
+	let state: SimulationState = serde_json::from_str(json)?;
+	simulation::Simulation::from_state(state, registry, on_tick)
+}
+
+/// Saves a simulation in a compact binary format (CBOR).
+pub fn to_binary(simulation: &simulation::Simulation) -> Result<Vec<u8>, PersistenceError> {
+	let state = simulation.save_state()?;
+	let mut bytes = Vec::new();
+	ciborium::into_writer(&state, &mut bytes)
+		.map_err(|e| PersistenceError::Binary(e.to_string()))?;
+	Ok(bytes)
+}
+
+/// Loads a simulation saved with `to_binary`. The loaded simulation is paused.
+/// See `from_json`.
+pub fn from_binary(
+	bytes: &[u8],
+	registry: &Registry,
+	on_tick: Option<fn(&simulation::Simulation)>,
+) -> Result<simulation::Simulation, PersistenceError> {
+	let header: VersionHeader = ciborium::from_reader(bytes)
+		.map_err(|e| PersistenceError::Binary(e.to_string()))?;
+	check_version(header.version)?;
+	let state: SimulationState = ciborium::from_reader(bytes)
+		.map_err(|e| PersistenceError::Binary(e.to_string()))?;
+	simulation::Simulation::from_state(state, registry, on_tick)
+}
+
+/// Serializes an `f64` that may be infinite or NaN, which JSON can't
+/// represent as a number. Such values are written as the strings "inf",
+/// "-inf", and "NaN". Use with `#[serde(with = "extended_f64")]`.
+pub mod extended_f64 {
+	use serde::{Deserialize, Deserializer, Serializer};
+
+	pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+		if value.is_finite() {
+			serializer.serialize_f64(*value)
+		} else if value.is_nan() {
+			serializer.serialize_str("NaN")
+		} else if *value > 0.0 {
+			serializer.serialize_str("inf")
+		} else {
+			serializer.serialize_str("-inf")
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+		#[derive(Deserialize)]
+		#[serde(untagged)]
+		enum Repr {
+			Number(f64),
+			Text(String),
+		}
+
+		match Repr::deserialize(deserializer)? {
+			Repr::Number(n) => Ok(n),
+			Repr::Text(s) => match s.as_str() {
+				"inf" => Ok(f64::INFINITY),
+				"-inf" => Ok(f64::NEG_INFINITY),
+				"NaN" => Ok(f64::NAN),
+				_ => Err(serde::de::Error::custom(
+					format!("expected a number, \"inf\", \"-inf\", or \"NaN\", found \"{}\"", s),
+				)),
+			},
+		}
+	}
+}
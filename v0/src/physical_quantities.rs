@@ -1,78 +1,84 @@
+use crate::{dimensions, units};
+use std::fmt;
+use std::iter;
+use std::marker;
 use std::ops;
+use std::str;
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing;
 
 	/********************* Time ********************/
 
 	#[test]
 	fn time_supports_partialEq() {
 		assert!(
-			Time(-1.0) == Time(-1.0),
-			"Time(-1.0) did not equal Time(-1.0)."
+			Time::new(-1.0) == Time::new(-1.0),
+			"Time::new(-1.0) did not equal Time::new(-1.0)."
 		);
 		assert!(
-			Time(0.0) == Time(0.0),
-			"Time(0.0) did not equal Time(0.0)."
+			Time::new(0.0) == Time::new(0.0),
+			"Time::new(0.0) did not equal Time::new(0.0)."
 		);
 		assert!(
-			Time(1.0) == Time(1.0),
-			"Time(1.0) did not equal Time(1.0)."
+			Time::new(1.0) == Time::new(1.0),
+			"Time::new(1.0) did not equal Time::new(1.0)."
 		);
 
 		assert!(
-			Time(-1.0) != Time(0.0),
-			"Time(-1.0) was equal Time(0.0)."
+			Time::new(-1.0) != Time::new(0.0),
+			"Time::new(-1.0) was equal Time::new(0.0)."
 		);
 		assert!(
-			Time(0.0) != Time(1.0),
-			"Time(0.0) was equal to Time(1.0)."
+			Time::new(0.0) != Time::new(1.0),
+			"Time::new(0.0) was equal to Time::new(1.0)."
 		);
 		assert!(
-			Time(1.0) != Time(-1.0),
-			"Time(1.0) was equal to Time(-1.0)."
+			Time::new(1.0) != Time::new(-1.0),
+			"Time::new(1.0) was equal to Time::new(-1.0)."
 		);
 	}
 
 	#[test]
 	fn time_supports_multiplication_by_a_coefficient() {
-		assert_eq!(Time(2.0) * 5.0, Time(10.0));
-		assert_eq!(Time(-2.0) * 5.0, Time(-10.0));
+		assert_eq!(Time::new(2.0) * 5.0, Time::new(10.0));
+		assert_eq!(Time::new(-2.0) * 5.0, Time::new(-10.0));
 	}
 
 	#[test]
 	fn time_supports_addition() {
-        assert_eq!(Time(0.0), Time(0.0) + Time(0.0));
-        assert_eq!(Time(1.0), Time(0.0) + Time(1.0));
-        assert_eq!(Time(-1.0), Time(0.0) + Time(-1.0));
-        assert_eq!(Time(1.0), Time(1.0) + Time(0.0));
-        assert_eq!(Time(2.0), Time(1.0) + Time(1.0));
-        assert_eq!(Time(0.0), Time(1.0) + Time(-1.0));
-        assert_eq!(Time(-1.0), Time(-1.0) + Time(0.0));
-        assert_eq!(Time(0.0), Time(-1.0) + Time(1.0));
-        assert_eq!(Time(-2.0), Time(-1.0) + Time(-1.0));
+        assert_eq!(Time::new(0.0), Time::new(0.0) + Time::new(0.0));
+        assert_eq!(Time::new(1.0), Time::new(0.0) + Time::new(1.0));
+        assert_eq!(Time::new(-1.0), Time::new(0.0) + Time::new(-1.0));
+        assert_eq!(Time::new(1.0), Time::new(1.0) + Time::new(0.0));
+        assert_eq!(Time::new(2.0), Time::new(1.0) + Time::new(1.0));
+        assert_eq!(Time::new(0.0), Time::new(1.0) + Time::new(-1.0));
+        assert_eq!(Time::new(-1.0), Time::new(-1.0) + Time::new(0.0));
+        assert_eq!(Time::new(0.0), Time::new(-1.0) + Time::new(1.0));
+        assert_eq!(Time::new(-2.0), Time::new(-1.0) + Time::new(-1.0));
 	}
 
 	#[test]
 	fn time_supports_subtraction() {
-        assert_eq!(Time(0.0), Time(0.0) - Time(0.0));
-        assert_eq!(Time(-1.0), Time(0.0) - Time(1.0));
-        assert_eq!(Time(1.0), Time(0.0) - Time(-1.0));
-        assert_eq!(Time(1.0), Time(1.0) - Time(0.0));
-        assert_eq!(Time(0.0), Time(1.0) - Time(1.0));
-        assert_eq!(Time(2.0), Time(1.0) - Time(-1.0));
-        assert_eq!(Time(-1.0), Time(-1.0) - Time(0.0));
-        assert_eq!(Time(-2.0), Time(-1.0) - Time(1.0));
-        assert_eq!(Time(0.0), Time(-1.0) - Time(-1.0));
+        assert_eq!(Time::new(0.0), Time::new(0.0) - Time::new(0.0));
+        assert_eq!(Time::new(-1.0), Time::new(0.0) - Time::new(1.0));
+        assert_eq!(Time::new(1.0), Time::new(0.0) - Time::new(-1.0));
+        assert_eq!(Time::new(1.0), Time::new(1.0) - Time::new(0.0));
+        assert_eq!(Time::new(0.0), Time::new(1.0) - Time::new(1.0));
+        assert_eq!(Time::new(2.0), Time::new(1.0) - Time::new(-1.0));
+        assert_eq!(Time::new(-1.0), Time::new(-1.0) - Time::new(0.0));
+        assert_eq!(Time::new(-2.0), Time::new(-1.0) - Time::new(1.0));
+        assert_eq!(Time::new(0.0), Time::new(-1.0) - Time::new(-1.0));
 	}
 
 	#[test]
 	fn time_gets_number() {
-		assert_eq!(Time(-1.0).get_number(), -1.0);
-		assert_eq!(Time(0.0).get_number(), 0.0);
-		assert_eq!(Time(1.0).get_number(), 1.0);
+		assert_eq!(Time::new(-1.0).get_number(), -1.0);
+		assert_eq!(Time::new(0.0).get_number(), 0.0);
+		assert_eq!(Time::new(1.0).get_number(), 1.0);
 	}
 
 	
@@ -178,6 +184,183 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn vector2_supports_negation_and_sum() {
+		assert_eq!(-Vector2::new(1.0, -2.0), Vector2::new(-1.0, 2.0));
+		assert_eq!(
+			[Vector2::new(1.0, 2.0), Vector2::new(3.0, 4.0), Vector2::new(-1.0, 0.5)]
+				.into_iter()
+				.sum::<Vector2>(),
+			Vector2::new(3.0, 6.5),
+		);
+		assert_eq!(Vec::<Vector2>::new().into_iter().sum::<Vector2>(), Vector2::new(0.0, 0.0));
+	}
+
+	#[test]
+	fn vector2_gets_cross_product_and_angle() {
+		assert_eq!(Vector2::new(1.0, 0.0).cross(Vector2::new(0.0, 1.0)), 1.0);
+		assert_eq!(Vector2::new(0.0, 1.0).cross(Vector2::new(1.0, 0.0)), -1.0);
+		assert_eq!(Vector2::new(2.0, 4.0).cross(Vector2::new(1.0, 2.0)), 0.0);
+		assert_eq!(Vector2::new(1.0, 0.0).get_angle(), 0.0);
+		assert_eq!(Vector2::new(0.0, 2.0).get_angle(), std::f64::consts::FRAC_PI_2);
+		assert_eq!(Vector2::new(-1.0, 0.0).get_angle(), std::f64::consts::PI);
+		assert_eq!(Vector2::new(0.0, -3.0).get_angle(), -std::f64::consts::FRAC_PI_2);
+	}
+
+	#[test]
+	fn vector2_rotates_counterclockwise() {
+		let rotated = Vector2::new(1.0, 0.0).rotate(std::f64::consts::FRAC_PI_2);
+		assert!((rotated - Vector2::new(0.0, 1.0)).get_magnitude() < 1e-12);
+		let rotated = Vector2::new(1.0, 1.0).rotate(-std::f64::consts::PI);
+		assert!((rotated - Vector2::new(-1.0, -1.0)).get_magnitude() < 1e-12);
+		let rotated = Vector2::new(3.0, 4.0).rotate(1.0);
+		assert!((rotated.get_magnitude() - 5.0).abs() < 1e-12);
+		assert!((rotated.get_angle() - (Vector2::new(3.0, 4.0).get_angle() + 1.0)).abs() < 1e-12);
+	}
+
+	#[test]
+	fn vector2_converts_to_and_from_polar_coordinates() {
+		let vector = Vector2::from_polar(2.0, std::f64::consts::FRAC_PI_2);
+		crate::assert_approx_eq!(vector, Vector2::new(0.0, 2.0), testing::Tolerance::Absolute(1e-12));
+		assert_eq!(Vector2::from_polar(5.0, 0.0), Vector2::new(5.0, 0.0));
+		let (magnitude, angle) = Vector2::new(3.0, 4.0).to_polar();
+		assert_eq!(magnitude, 5.0);
+		crate::assert_approx_eq!(
+			Vector2::from_polar(magnitude, angle),
+			Vector2::new(3.0, 4.0),
+			testing::Tolerance::Absolute(1e-12),
+		);
+		assert_eq!(Vector2::new(3.0, 4.0).get_perpendicular(), Vector2::new(-4.0, 3.0));
+		assert_eq!(Vector2::new(3.0, 4.0).get_perpendicular().dot(Vector2::new(3.0, 4.0)), 0.0);
+	}
+
+	#[test]
+	fn vectors_lerp_project_and_get_angle_between() {
+		assert_eq!(Vector2::new(0.0, 0.0).lerp(Vector2::new(2.0, 4.0), 0.0), Vector2::new(0.0, 0.0));
+		assert_eq!(Vector2::new(0.0, 0.0).lerp(Vector2::new(2.0, 4.0), 0.5), Vector2::new(1.0, 2.0));
+		assert_eq!(Vector2::new(0.0, 0.0).lerp(Vector2::new(2.0, 4.0), 1.0), Vector2::new(2.0, 4.0));
+		assert_eq!(Vector2::new(3.0, 4.0).project_onto(Vector2::new(2.0, 0.0)), Vector2::new(3.0, 0.0));
+		assert_eq!(
+			Vector3::new(1.0, 2.0, 3.0).project_onto(Vector3::new(0.0, 0.0, -1.0)),
+			Vector3::new(0.0, 0.0, 3.0),
+		);
+		assert_eq!(Vector2::new(1.0, 0.0).angle_between(Vector2::new(5.0, 0.0)), 0.0);
+		assert!(
+			(Vector2::new(1.0, 0.0).angle_between(Vector2::new(0.0, -2.0)) - std::f64::consts::FRAC_PI_2).abs()
+				< 1e-12
+		);
+		assert!(
+			(Vector2::new(1.0, 1.0).angle_between(Vector2::new(-1.0, -1.0)) - std::f64::consts::PI).abs()
+				< 1e-12
+		);
+		// Nearly parallel vectors still get an accurate, small angle.
+		assert!((Vector2::new(1.0, 0.0).angle_between(Vector2::new(1.0, 1e-9)) - 1e-9).abs() < 1e-20);
+	}
+
+	/********************* Vector3 ********************/
+
+	#[test]
+	fn new_creates_vector3() {
+		let v = Vector3::new(-1.0, 1.0, 2.0);
+		assert_eq!(v.x(), -1.0);
+		assert_eq!(v.y(), 1.0);
+		assert_eq!(v.z(), 2.0);
+	}
+
+	#[test]
+	fn vector3_supports_arithmetic() {
+		assert_eq!(Vector3::new(1.0, 2.0, 3.0) * 2.0, Vector3::new(2.0, 4.0, 6.0));
+		assert_eq!(2.0 * Vector3::new(1.0, 2.0, 3.0), Vector3::new(2.0, 4.0, 6.0));
+		assert_eq!(Vector3::new(2.0, 4.0, 6.0) / 2.0, Vector3::new(1.0, 2.0, 3.0));
+		assert_eq!(
+			Vector3::new(1.0, 2.0, 3.0) + Vector3::new(-1.0, 1.0, 0.5),
+			Vector3::new(0.0, 3.0, 3.5),
+		);
+		assert_eq!(
+			Vector3::new(1.0, 2.0, 3.0) - Vector3::new(-1.0, 1.0, 0.5),
+			Vector3::new(2.0, 1.0, 2.5),
+		);
+	}
+
+	#[test]
+	fn vector3_gets_magnitude_dot_and_cross() {
+		let v = Vector3::new(2.0, 3.0, 6.0);
+		assert_eq!(v.get_magnitude(), 7.0);
+		assert_eq!(v.get_unit_vector(), Vector3::new(2.0 / 7.0, 3.0 / 7.0, 6.0 / 7.0));
+		assert_eq!(v.dot(Vector3::new(1.0, -1.0, 1.0)), 5.0);
+		assert_eq!(
+			Vector3::new(1.0, 0.0, 0.0).cross(Vector3::new(0.0, 1.0, 0.0)),
+			Vector3::new(0.0, 0.0, 1.0),
+		);
+	}
+
+	#[test]
+	fn vectors_get_components() {
+		assert_eq!(Vector2::<f64>::DIMENSIONS, 2);
+		assert_eq!(Vector3::<f64>::DIMENSIONS, 3);
+		assert_eq!(Vector2::new(4.0, 5.0).get_component(1), 5.0);
+		assert_eq!(Vector3::new(4.0, 5.0, 6.0).get_component(2), 6.0);
+		assert_eq!(Vector3::zero(), Vector3::new(0.0, 0.0, 0.0));
+		assert_eq!(Vector2::from_components(&[4.0, 5.0]), Vector2::new(4.0, 5.0));
+		assert_eq!(Vector3::from_components(&[4.0, 5.0, 6.0]), Vector3::new(4.0, 5.0, 6.0));
+	}
+
+	#[test]
+	#[should_panic(expected = "Vector3 needs 3 components, not 2")]
+	fn vector3_from_components_panics_on_too_few() {
+		Vector3::from_components(&[1.0, 2.0]);
+	}
+
+	#[test]
+	#[should_panic(expected = "Vector2 has no component 2")]
+	fn vector2_get_component_panics_on_z() {
+		Vector2::new(1.0, 2.0).get_component(2);
+	}
+
+	#[test]
+	fn three_dimensional_quantities_follow_two_dimensional_rules() {
+		let acceleration = Force3::new(2.0, 4.0, -6.0) / Mass::new(2.0);
+		assert_eq!(acceleration, Acceleration3::new(1.0, 2.0, -3.0));
+
+		let velocity = acceleration * Time::new(2.0);
+		assert_eq!(velocity, Velocity3::new(2.0, 4.0, -6.0));
+		assert_eq!(Mass::new(3.0) * velocity, Momentum3::new(6.0, 12.0, -18.0));
+
+		let mut position = Displacement3::new(1.0, 1.0, 1.0);
+		position += velocity * Time::new(0.5);
+		assert_eq!(position, Displacement3::new(2.0, 3.0, -2.0));
+		assert_eq!(position.z(), -2.0);
+		assert_eq!(Displacement3::new(2.0, 3.0, 6.0).get_magnitude(), 7.0);
+	}
+
+	/********************* Scalar ********************/
+
+	#[test]
+	fn scalars_convert_to_and_from_f64() {
+		assert_eq!(<f64 as Scalar>::from_f64(0.1), 0.1);
+		assert_eq!(<f32 as Scalar>::from_f64(0.1), 0.1_f32);
+		assert_eq!(0.5_f32.to_f64(), 0.5);
+		assert_eq!(<f32 as Scalar>::zero(), 0.0);
+		assert_eq!(Scalar::sqrt(16.0_f32), 4.0);
+		assert_eq!(Scalar::abs(-2.0_f32), 2.0);
+	}
+
+	#[test]
+	fn single_precision_quantities_follow_double_precision_rules() {
+		let acceleration = Force::<f32>::new(2.0, 4.0) / Mass::new(2.0);
+		assert_eq!(acceleration, Acceleration::new(1.0_f32, 2.0));
+		assert_eq!(acceleration * 2.0_f32, Acceleration::new(2.0_f32, 4.0));
+
+		let velocity = acceleration * Time::new(2.0);
+		assert_eq!(velocity, Velocity::new(2.0_f32, 4.0));
+		assert_eq!(Mass::new(3.0_f32) * velocity, Momentum::new(6.0, 12.0));
+
+		let position = Displacement::new(1.0_f32, 1.0) + velocity * Time::new(0.5);
+		assert_eq!(position, Displacement::new(2.0, 3.0));
+		assert_eq!(Displacement3::<f32>::new(2.0, 3.0, 6.0).get_magnitude(), 7.0);
+		assert_eq!(std::mem::size_of::<Vector2<f32>>(), 8);
+	}
+
 	/********************* Mass ********************/
 
 	#[test]
@@ -279,6 +462,24 @@ mod tests {
 		assert_eq!(displacement, Displacement::new(-1.0, -2.0));
 	}
 
+	#[test]
+	fn displacement_supports_sub_assign() {
+		let mut displacement = Displacement::new(-1.0, -2.0);
+		displacement -= Displacement::new(10.0, 20.0);
+		assert_eq!(displacement, Displacement::new(-11.0, -22.0));
+		displacement -= Displacement::new(-10.0, -20.0);
+		assert_eq!(displacement, Displacement::new(-1.0, -2.0));
+	}
+
+	#[test]
+	fn displacement_supports_scalar_operations_and_division_by_time() {
+		assert_eq!(Displacement::new(1.0, -2.0) * 3.0, Displacement::new(3.0, -6.0));
+		assert_eq!(3.0 * Displacement::new(1.0, -2.0), Displacement::new(3.0, -6.0));
+		assert_eq!(Displacement::new(3.0, -6.0) / 3.0, Displacement::new(1.0, -2.0));
+		assert_eq!(-Displacement::new(3.0, -6.0), Displacement::new(-3.0, 6.0));
+		assert_eq!(Displacement::new(3.0, -6.0) / Time::new(2.0), Velocity::new(1.5, -3.0));
+	}
+
 	#[test]
 	fn displacement_supports_subtraction() {
 		assert_eq!(
@@ -353,19 +554,19 @@ mod tests {
 	#[test]
 	fn velocity_supports_multiplication_by_time() {
 		assert_eq!(
-			Velocity::new(1.0, 2.0) * Time(5.0),
+			Velocity::new(1.0, 2.0) * Time::new(5.0),
 			Displacement::new(5.0, 10.0)
 		);
 		assert_eq!(
-			Time(5.0) * Velocity::new(1.0, 2.0),
+			Time::new(5.0) * Velocity::new(1.0, 2.0),
 			Displacement::new(5.0, 10.0)
 		);
 		assert_eq!(
-			Velocity::new(1.0, 2.0) * Time(-5.0),
+			Velocity::new(1.0, 2.0) * Time::new(-5.0),
 			Displacement::new(-5.0, -10.0)
 		);
 		assert_eq!(
-			Time(-5.0) * Velocity::new(1.0, 2.0),
+			Time::new(-5.0) * Velocity::new(1.0, 2.0),
 			Displacement::new(-5.0, -10.0)
 		);
 	}
@@ -422,6 +623,41 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn velocity_supports_scalar_operations_and_division_by_time() {
+		assert_eq!(Velocity::new(1.0, 2.0) * 5.0, Velocity::new(5.0, 10.0));
+		assert_eq!(5.0 * Velocity::new(1.0, 2.0), Velocity::new(5.0, 10.0));
+		assert_eq!(Velocity::new(5.0, 10.0) / 5.0, Velocity::new(1.0, 2.0));
+		assert_eq!(-Velocity::new(5.0, -10.0), Velocity::new(-5.0, 10.0));
+		assert_eq!(Velocity::new(5.0, 10.0) / Time::new(5.0), Acceleration::new(1.0, 2.0));
+		assert_eq!(Velocity::new(1.0, 2.0) * 5.0_f32, Velocity::new(5.0_f32, 10.0));
+	}
+
+	#[test]
+	fn velocity_supports_vector_quantity_methods() {
+		let velocity = Velocity::new(3.0, 4.0);
+		assert_eq!(velocity.get_direction(), Vector2::new(0.6, 0.8));
+		assert_eq!(velocity.dot(Vector2::new(1.0, 1.0)), 7.0);
+		assert_eq!(velocity.cross(Vector2::new(1.0, 0.0)), -4.0);
+		assert_eq!(velocity.lerp(Velocity::new(5.0, 6.0), 0.5), Velocity::new(4.0, 5.0));
+		// E.g., the part of a velocity along a surface normal.
+		assert_eq!(velocity.project_onto(Vector2::new(0.0, -1.0)), Velocity::new(0.0, 4.0));
+		assert_eq!(velocity.angle_between(Vector2::new(3.0, 4.0)), 0.0);
+		assert_eq!(velocity.get_angle(), 4.0_f64.atan2(3.0));
+		let rotated = velocity.rotate(std::f64::consts::PI);
+		assert!((rotated - Velocity::new(-3.0, -4.0)).get_magnitude() < 1e-12);
+	}
+
+	#[test]
+	fn velocity_converts_to_and_from_polar_coordinates() {
+		let velocity = Velocity::from_polar(Speed::new(2.0), std::f64::consts::PI);
+		crate::assert_approx_eq!(velocity, Velocity::new(-2.0, 0.0), testing::Tolerance::Absolute(1e-12));
+		let (speed, angle) = Velocity::new(0.0, -3.0).to_polar();
+		assert_eq!(speed, Speed::new(3.0));
+		assert_eq!(angle, -std::f64::consts::FRAC_PI_2);
+		assert_eq!(Velocity::new(1.0, 2.0).get_perpendicular(), Velocity::new(-2.0, 1.0));
+	}
+
 	/********************* Acceleration ********************/
 
 	#[test]
@@ -488,19 +724,19 @@ mod tests {
 	#[test]
 	fn acceleration_supports_multiplication_by_time() {
 		assert_eq!(
-			Acceleration::new(1.0, 2.0) * Time(5.0),
+			Acceleration::new(1.0, 2.0) * Time::new(5.0),
 			Velocity::new(5.0, 10.0)
 		);
 		assert_eq!(
-			Time(5.0) * Acceleration::new(1.0, 2.0),
+			Time::new(5.0) * Acceleration::new(1.0, 2.0),
 			Velocity::new(5.0, 10.0)
 		);
 		assert_eq!(
-			Acceleration::new(1.0, 2.0) * Time(-5.0),
+			Acceleration::new(1.0, 2.0) * Time::new(-5.0),
 			Velocity::new(-5.0, -10.0)
 		);
 		assert_eq!(
-			Time(-5.0) * Acceleration::new(1.0, 2.0),
+			Time::new(-5.0) * Acceleration::new(1.0, 2.0),
 			Velocity::new(-5.0, -10.0)
 		);
 	}
@@ -603,11 +839,11 @@ mod tests {
 	#[test]
 	fn force_supports_division_by_mass() {
 		assert_eq!(
-			Force::new(5.0, 10.0) / Mass(5.0),
+			Force::new(5.0, 10.0) / Mass::new(5.0),
 			Acceleration::new(1.0, 2.0)
 		);
 		assert_eq!(
-			Force::new(5.0, 10.0) / Mass(-5.0),
+			Force::new(5.0, 10.0) / Mass::from_number(-5.0),
 			Acceleration::new(-1.0, -2.0)
 		);
 	}
@@ -664,6 +900,180 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn acceleration_and_force_support_division_negation_and_assignment() {
+		assert_eq!(Acceleration::new(5.0, 10.0) / 5.0, Acceleration::new(1.0, 2.0));
+		assert_eq!(-Acceleration::new(5.0, -10.0), Acceleration::new(-5.0, 10.0));
+		let mut acceleration = Acceleration::new(1.0, 2.0);
+		acceleration += Acceleration::new(1.0, 1.0);
+		acceleration -= Acceleration::new(0.5, 0.5);
+		assert_eq!(acceleration, Acceleration::new(1.5, 2.5));
+
+		assert_eq!(Force::new(1.0, 2.0) * 2.0, Force::new(2.0, 4.0));
+		assert_eq!(Force::new(4.0, 2.0) / 2.0, Force::new(2.0, 1.0));
+		assert_eq!(-Force::new(1.0, -2.0), Force::new(-1.0, 2.0));
+		let mut force = Force::new(1.0, 2.0);
+		force += Force::new(1.0, 1.0);
+		force -= Force::new(0.5, 0.5);
+		assert_eq!(force, Force::new(1.5, 2.5));
+	}
+
+	#[test]
+	fn forces_support_sum() {
+		let forces = [Force::new(1.0, 2.0), Force::new(-3.0, 0.5), Force::new(0.0, 1.5)];
+		assert_eq!(forces.iter().copied().sum::<Force>(), Force::new(-2.0, 4.0));
+		assert_eq!(Vec::<Force3>::new().into_iter().sum::<Force3>(), Force3::new(0.0, 0.0, 0.0));
+	}
+
+	/********************* Energy ********************/
+
+	#[test]
+	fn energy_supports_arithmetic() {
+		assert_eq!(Energy::new(1.5) + Energy::new(-2.0), Energy::new(-0.5));
+		assert_eq!(Energy::new(1.5) - Energy::new(-2.0), Energy::new(3.5));
+		assert_eq!(Energy::new(-1.0).get_number(), -1.0);
+		assert!(Energy::new(-1.0) < Energy::new(0.0));
+	}
+
+	/********************* Momentum ********************/
+
+	#[test]
+	fn mass_times_velocity_is_momentum() {
+		assert_eq!(
+			Mass::new(2.0) * Velocity::new(3.0, -4.0),
+			Momentum::new(6.0, -8.0),
+		);
+		assert_eq!(Momentum::new(6.0, -8.0).get_magnitude(), 10.0);
+	}
+
+	#[test]
+	fn momentum_supports_arithmetic() {
+		assert_eq!(
+			Momentum::new(1.0, 2.0) + Momentum::new(3.0, 4.0),
+			Momentum::new(4.0, 6.0),
+		);
+		assert_eq!(
+			Momentum::new(1.0, 2.0) - Momentum::new(3.0, 4.0),
+			Momentum::new(-2.0, -2.0),
+		);
+		assert_eq!(Momentum::new(6.0, -8.0) / Mass::new(2.0), Velocity::new(3.0, -4.0));
+	}
+
+	/********************* AngularMomentum ********************/
+
+	#[test]
+	fn angular_momentum_supports_arithmetic() {
+		assert_eq!(
+			AngularMomentum::new(1.0) + AngularMomentum::new(2.0),
+			AngularMomentum::new(3.0),
+		);
+		assert_eq!(
+			AngularMomentum::new(1.0) - AngularMomentum::new(2.0),
+			AngularMomentum::new(-1.0),
+		);
+		assert_eq!(AngularMomentum::new(-1.0).get_number(), -1.0);
+	}
+
+	/********************* Derived quantities ********************/
+
+	#[test]
+	fn products_and_quotients_have_derived_dimensions() {
+		let energy: Energy = Force::new(3.0, 4.0).get_norm() * Length::new(2.0);
+		assert_eq!(energy, Energy::new(10.0));
+		let power: Power = energy / Time::new(4.0);
+		assert_eq!(power, Power::new(2.5));
+		let pressure: Pressure = Force::new(0.0, 6.0).get_norm() / Area::new(3.0);
+		assert_eq!(pressure, Pressure::new(2.0));
+		let impulse: Impulse = Force::new(1.0, -2.0) * Time::new(0.5);
+		assert_eq!(impulse, Mass::new(0.5) * Velocity::new(1.0, -2.0));
+		let speed: Speed = Displacement::new(3.0, 4.0).get_norm() / Time::new(5.0);
+		assert_eq!(speed, Speed::new(1.0));
+	}
+
+	#[test]
+	fn gravitational_constant_gives_a_force() {
+		let big_g = GravitationalConstant::new(2.0);
+		let r = Displacement::new(0.0, 2.0);
+		// F = G m_1 m_2 / r^2, pointing along r.
+		let force: Force =
+			(big_g * Mass::new(3.0) * Mass::new(4.0) / r.get_norm_squared()).along(r.get_direction());
+		assert_eq!(force, Force::new(0.0, 6.0));
+	}
+
+	#[test]
+	fn quantities_get_square_roots_and_reciprocals() {
+		assert_eq!(Area::new(9.0).sqrt(), Length::new(3.0));
+		assert_eq!((Speed::new(2.0) * Speed::new(2.0)).sqrt(), Speed::new(2.0));
+		let frequency: Quantity<dimensions::Frequency> = Time::new(0.25).recip();
+		assert_eq!(frequency.get_number(), 4.0);
+		assert_eq!(Displacement::new(3.0, 4.0).get_norm_squared(), Area::new(25.0));
+	}
+
+	#[test]
+	fn scalar_quantities_support_arithmetic() {
+		assert_eq!(2.0 * Energy::new(1.5), Energy::new(3.0));
+		assert_eq!(Energy::new(1.5) / 3.0, Energy::new(0.5));
+		assert_eq!(-Energy::new(1.5), Energy::new(-1.5));
+		let mut time = Time::new(1.0);
+		time += Time::new(2.0);
+		time -= Time::new(0.5);
+		assert_eq!(time, Time::new(2.5));
+		assert_eq!([Mass::new(1.0), Mass::new(2.5)].into_iter().sum::<Mass>(), Mass::new(3.5));
+		assert!(Time::new(1.0) < Time::new(2.0));
+	}
+
+	#[test]
+	fn rotational_quantities_have_derived_dimensions() {
+		let angular_acceleration: AngularAcceleration =
+			Torque::new(6.0) / MomentOfInertia::new(2.0);
+		assert_eq!(angular_acceleration, AngularAcceleration::new(3.0));
+		let angular_velocity: AngularVelocity = angular_acceleration * Time::new(2.0);
+		assert_eq!(angular_velocity, AngularVelocity::new(6.0));
+		let angle: Angle = angular_velocity * Time::new(0.5);
+		assert_eq!(angle, Angle::new(3.0));
+		let angular_momentum: AngularMomentum = MomentOfInertia::new(2.0) * angular_velocity;
+		assert_eq!(angular_momentum, AngularMomentum::new(12.0));
+	}
+
+	#[test]
+	#[should_panic(expected = "Moment of inertia must be positive.")]
+	fn moment_of_inertia_new_panics_if_not_positive() {
+		MomentOfInertia::new(0.0);
+	}
+
+	/********************* Formatting and parsing ********************/
+
+	#[test]
+	fn quantities_display_with_si_units() {
+		let gravity = Quantity::<dimensions::Acceleration>::from_number(9.81);
+		assert_eq!(gravity.to_string(), "9.81 m/s²");
+		assert_eq!(format!("{:.1}", Energy::new(2.26)), "2.3 J");
+		assert_eq!(Angle::new(0.5).to_string(), "0.5");
+		assert_eq!(Velocity::new(3.0, -4.0).to_string(), "(3, -4) m/s");
+		assert_eq!(Displacement3::new(1.0, 2.0, 3.5).to_string(), "(1, 2, 3.5) m");
+	}
+
+	#[test]
+	fn quantities_parse_with_units() {
+		assert_eq!("2 days".parse::<Time>(), Ok(Time::new(172800.0)));
+		assert_eq!("1.5 km".parse::<Length<f32>>(), Ok(Length::new(1500.0f32)));
+		assert_eq!("9.81 m/s²".parse::<Speed>(), Err(units::ParseQuantityError::DimensionMismatch {
+			text: String::from("9.81 m/s²"),
+			expected: (1, 0, -1),
+			found: (1, 0, -2),
+		}));
+
+		let energy = Energy::new(2.5);
+		assert_eq!(energy.to_string().parse::<Energy>(), Ok(energy));
+	}
+
+	#[test]
+	fn quantities_convert_to_other_units() {
+		assert_eq!(Length::new(1500.0).get_number_in("km"), Ok(1.5));
+		assert_eq!(Time::new(5400.0).get_number_in("h"), Ok(1.5));
+		assert!(Length::new(1500.0).get_number_in("kg").is_err());
+	}
+
 	/********************* Ticks ********************/
 
 	#[test]
@@ -675,6 +1085,13 @@ mod tests {
 		assert_ne!(Ticks(0), Ticks(1));
 	}
 
+	#[test]
+	fn ticks_supports_ordering() {
+		assert!(Ticks(0) < Ticks(1));
+		assert!(Ticks(2) > Ticks(1));
+		assert!(Ticks(1) <= Ticks(1));
+	}
+
 	#[test]
 	fn ticks_gets_number() {
 		assert_eq!(Ticks(0).get_number(), 0);
@@ -709,449 +1126,1364 @@ mod tests {
 }
 
 
-// Using a tuple struct to wrap an f64 so the compiler treats Time as a
-//	distinct type. This is the "newtype pattern."
-// The PartialEq trait is automatically implemented using "derive" here. The
-//	derived implementation will report equality between two structs if all
-//	fields are equal, and non-equality otherwise.
-/// Represents a length of time. Could be though of as milliseconds, seconds,
-/// minutes, etc.
-#[derive(PartialEq, PartialOrd)]
-#[derive(Debug)]
-#[derive(Clone, Copy)]
-pub struct Time(f64);
-
-// Implement multiplication of time by a coefficient.
-impl ops::Mul<f64> for Time {
-	type Output = Self;
-
-	fn mul(self, rhs: f64) -> Self::Output {
-		Self(self.0 * rhs)
-	}
+/// The number type that every physical quantity wraps. `f64` is the default
+/// everywhere; `f32` halves the memory used by each quantity, which helps with
+/// large particle counts, at the cost of precision. With the `fixed-point`
+/// feature, `fixed_point::Fixed` gives results that are identical on every
+/// machine.
+pub trait Scalar:
+	Copy
+	+ PartialEq
+	+ PartialOrd
+	+ fmt::Debug
+	+ Persistable
+	+ ops::Add<Output = Self>
+	+ ops::Sub<Output = Self>
+	+ ops::Mul<Output = Self>
+	+ ops::Div<Output = Self>
+	+ ops::Neg<Output = Self>
+	+ 'static
+{
+	/// Converts an `f64` to this type, rounding if necessary. Used for
+	/// constants and for parameters (radii, coefficients, etc.) that are
+	/// always given as `f64`.
+	fn from_f64(value: f64) -> Self;
+
+	/// Converts this number to an `f64`.
+	fn to_f64(self) -> f64;
+
+	fn zero() -> Self {
+		Self::from_f64(0.0)
+	}
+
+	fn sqrt(self) -> Self;
+
+	fn abs(self) -> Self;
+
+	/// Gets the sine of this angle, in radians.
+	fn sin(self) -> Self;
+
+	/// Gets the cosine of this angle, in radians.
+	fn cos(self) -> Self;
+
+	/// Gets the angle, in radians, between the positive x axis and the point
+	/// (`x`, `self`). The result is between -π and π.
+	fn atan2(self, x: Self) -> Self;
 }
 
-impl ops::Add for Time {
-	type Output = Self;
-
-	fn add(self, rhs: Self) -> Self::Output {
-		Self(self.0 + rhs.0)
+impl Scalar for f64 {
+	fn from_f64(value: f64) -> Self {
+		value
 	}
-}
-
-impl ops::Sub for Time {
-	type Output = Self;
 
-	fn sub(self, rhs: Self) -> Self::Output {
-		Self(self.0 - rhs.0)
+	fn to_f64(self) -> f64 {
+		self
 	}
-}
 
-impl ops::Mul<Velocity> for Time {
-	type Output = Displacement;
-
-	fn mul(self, rhs: Velocity) -> Self::Output {
-		Displacement(self.0 * rhs.0)
+	fn sqrt(self) -> Self {
+		f64::sqrt(self)
 	}
-}
 
-impl ops::Mul<Acceleration> for Time {
-	type Output = Velocity;
-
-	fn mul(self, rhs: Acceleration) -> Self::Output {
-		Velocity(self.0 * rhs.0)
+	fn abs(self) -> Self {
+		f64::abs(self)
 	}
-}
 
-impl Time {
-	pub fn new(t: f64) -> Self {
-		Self(t)
+	fn sin(self) -> Self {
+		f64::sin(self)
 	}
 
-	/// Gets the raw f64 value representing the number of time units.
-	pub fn get_number(&self) -> f64 {
-		self.0
+	fn cos(self) -> Self {
+		f64::cos(self)
 	}
-}
 
-// TODO: Vector2 should probably be a Trait, implemented by every vector
-//	quantity. Then I could provide default implementations for things like
-//	get_magnitude (and maybe for operators?) instead of having to implement them
-//	separately for each vector quantity.
-/// A two-dimensional vector (not to be confused with `Vec<T>`).
-/// Supports basic vector math.
-#[derive(PartialEq)]
-#[derive(Debug)]
-#[derive(Clone, Copy)]
-pub struct Vector2 {
-	x: f64,
-	y: f64,
+	fn atan2(self, x: Self) -> Self {
+		f64::atan2(self, x)
+	}
 }
 
-impl Vector2 {
-	pub fn new(x: f64, y:f64) -> Self {
-		Self {
-			x: x,
-			y: y,
-		}
+impl Scalar for f32 {
+	fn from_f64(value: f64) -> Self {
+		value as f32
 	}
 
-	pub fn x(&self) -> f64 {
-		self.x
+	fn to_f64(self) -> f64 {
+		self as f64
 	}
 
-	pub fn y(&self) -> f64 {
-		self.y
+	fn sqrt(self) -> Self {
+		f32::sqrt(self)
 	}
 
-	/// Gets the magnitude of this vector.
-	pub fn get_magnitude(self) -> f64 {
-		(self.x.powf(2.0) + self.y.powf(2.0)).sqrt()
+	fn abs(self) -> Self {
+		f32::abs(self)
 	}
 
-	/// Gets a unit vector pointing in the same direction as this vector.
-	pub fn get_unit_vector(self) -> Self {
-		let magnitude = self.get_magnitude();
-
-		Self {
-			x: self.x / magnitude,
-			y: self.y / magnitude,
-		}
+	fn sin(self) -> Self {
+		f32::sin(self)
 	}
-}
 
-// Scalar multiplication of a vector.
-impl ops::Mul<f64> for Vector2 {
-	type Output = Self;
+	fn cos(self) -> Self {
+		f32::cos(self)
+	}
 
-	fn mul(self, rhs: f64) -> Self::Output {
-		Self {
-			x: self.x * rhs,
-			y: self.y * rhs,
-		}
+	fn atan2(self, x: Self) -> Self {
+		f32::atan2(self, x)
 	}
 }
-impl ops::Mul<Vector2> for f64 {
-	type Output = Vector2;
 
-	fn mul(self, rhs: Vector2) -> Self::Output {
-		Vector2 {
-			x: rhs.x * self,
-			y: rhs.y * self,
-		}
+// Every physical quantity is a number (`Quantity`) or a vector (`QuantityN`)
+//	tagged with its dimension (see the `dimensions` module). Multiplying or
+//	dividing quantities works out the dimension of the result at compile time,
+//	so e.g. `Mass * Acceleration` is a `Force`, `Force * Length` is an
+//	`Energy`, and adding a `Time` to a `Mass` doesn't compile. The dimension is
+//	only a type parameter, so a `Time<f64>` is the same size as an `f64`.
+// The familiar names (`Time`, `Mass`, `Velocity`, ...) are aliases for
+//	particular dimensions.
+/// A scalar physical quantity: a number of type `S` with dimension `D`. Usually
+/// used through an alias such as `Time`, `Mass` or `Energy`.
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "persistence", serde(transparent))]
+pub struct Quantity<D, S = f64>(
+	S,
+	#[cfg_attr(feature = "persistence", serde(skip))]
+	marker::PhantomData<D>,
+);
+
+// These are implemented by hand because deriving them would require the
+//	dimension, which is never created, to implement them too.
+impl<D, S: Clone> Clone for Quantity<D, S> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone(), marker::PhantomData)
 	}
 }
 
-// Scalar division of a vector.
-impl ops::Div<f64> for Vector2 {
-	type Output = Self;
+impl<D, S: Copy> Copy for Quantity<D, S> {}
 
-	fn div(self, rhs: f64) -> Self::Output {
-		Self {
-			x: self.x / rhs,
-			y: self.y / rhs,
-		}
+impl<D, S: PartialEq> PartialEq for Quantity<D, S> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
 	}
 }
 
-// Vector addition.
-impl ops::Add for Vector2 {
-	type Output = Self;
-
-	fn add(self, rhs: Self) -> Self::Output {
-		Self {
-			x: self.x + rhs.x,
-			y: self.y + rhs.y,
-		}
+impl<D, S: PartialOrd> PartialOrd for Quantity<D, S> {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		self.0.partial_cmp(&other.0)
 	}
 }
 
-// Vector subtraction.
-impl ops::Sub for Vector2 {
-	type Output = Self;
-
-	fn sub(self, rhs: Self) -> Self::Output {
-		Self {
-			x: self.x - rhs.x,
-			y: self.y - rhs.y,
-		}
+impl<D, S: fmt::Debug> fmt::Debug for Quantity<D, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("Quantity").field(&self.0).finish()
 	}
 }
 
+impl<D: dimensions::Dimension, S: Scalar> Quantity<D, S> {
+	/// Creates a quantity of any dimension. The aliases (`Time::new`,
+	/// `Energy::new`, ...) are usually clearer.
+	pub fn from_number(number: S) -> Self {
+		Self(number, marker::PhantomData)
+	}
 
-/// Mass.
-#[derive(PartialEq)]
-#[derive(Debug)]
-#[derive(Clone, Copy)]
-pub struct Mass(f64);
+	/// Gets the raw value representing the number of units (e.g., seconds
+	/// for a `Time`).
+	pub fn get_number(&self) -> S {
+		self.0
+	}
 
-impl Mass {
-	pub fn new(m: f64) -> Self {
-		if m <= 0.0 {
-			panic!("Mass must be positive.");
-		}
+	/// Gets the square root of this quantity. Only quantities whose dimension
+	/// has a square root (e.g., `Area`) have one.
+	pub fn sqrt(self) -> Quantity<dimensions::SquareRoot<D>, S>
+	where
+		D: dimensions::HasSquareRoot,
+	{
+		Quantity(self.0.sqrt(), marker::PhantomData)
+	}
 
-		Self(m)
+	/// Gets 1 divided by this quantity (e.g., a `Frequency` from a `Time`).
+	pub fn recip(self) -> Quantity<typenum::Quot<dimensions::Dimensionless, D>, S>
+	where
+		dimensions::Dimensionless: ops::Div<D, Output: dimensions::Dimension>,
+	{
+		Quantity(S::from_f64(1.0) / self.0, marker::PhantomData)
 	}
 
-	pub fn get_number(&self) -> f64 {
-		self.0
+	/// Gets the number of `unit`s in this quantity (e.g., 1.5 for a `Length` of
+	/// 1500 m and a `unit` of "km"). See the `units` module for the units that
+	/// can be used. Like `Display`, this takes the quantity to be in SI units.
+	/// Returns an error if `unit` isn't recognized or is of a different
+	/// dimension.
+	pub fn get_number_in(&self, unit: &str) -> Result<f64, units::ParseQuantityError> {
+		units::convert::<D>(self.0.to_f64(), unit)
+	}
+
+	/// Creates a vector quantity with this quantity's dimension, pointing along
+	/// `direction`. E.g., a force with this magnitude when `direction` is a
+	/// unit vector.
+	pub fn along<V: Vector<Scalar = S>>(self, direction: V) -> QuantityN<D, V> {
+		QuantityN(direction * self.0, marker::PhantomData)
 	}
 }
 
-impl ops::Mul<Acceleration> for Mass {
-	type Output = Force;
+// Multiplication of a quantity by a coefficient.
+impl<D: dimensions::Dimension, S: Scalar> ops::Mul<S> for Quantity<D, S> {
+	type Output = Self;
 
-	fn mul(self, rhs: Acceleration) -> Self::Output {
-		Self::Output::new(
-			rhs.x() * self.0,
-			rhs.y() * self.0,
-		)
+	fn mul(self, rhs: S) -> Self::Output {
+		Self(self.0 * rhs, marker::PhantomData)
 	}
 }
 
-/// Position in space (displacement from the origin), displacement relative to
-/// some starting location, or distance from some arbitrary position.
-/// Wraps `Vector2` and provides functionality specific to displacement.
-#[derive(PartialEq)]
-#[derive(Debug)]
-#[derive(Clone, Copy)]
-pub struct Displacement(Vector2);
+// Division of a quantity by a coefficient.
+impl<D: dimensions::Dimension, S: Scalar> ops::Div<S> for Quantity<D, S> {
+	type Output = Self;
 
-impl Displacement {
-	pub fn new(x: f64, y: f64) -> Self {
-		Self(Vector2::new(x, y))
+	fn div(self, rhs: S) -> Self::Output {
+		Self(self.0 / rhs, marker::PhantomData)
 	}
+}
 
-	pub fn x(&self) -> f64 {
-		self.0.x
-	}
+// Multiplication of two quantities. The dimension of the result is the
+//	product of their dimensions.
+impl<D1, D2, S> ops::Mul<Quantity<D2, S>> for Quantity<D1, S>
+where
+	D1: dimensions::Dimension + ops::Mul<D2, Output: dimensions::Dimension>,
+	D2: dimensions::Dimension,
+	S: Scalar,
+{
+	type Output = Quantity<typenum::Prod<D1, D2>, S>;
 
-	pub fn y(&self) -> f64 {
-		self.0.y
+	fn mul(self, rhs: Quantity<D2, S>) -> Self::Output {
+		Quantity(self.0 * rhs.0, marker::PhantomData)
 	}
+}
 
-	pub fn get_vector(&self) -> Vector2 {
-		self.0
+// Division of two quantities. The dimension of the result is the quotient of
+//	their dimensions.
+impl<D1, D2, S> ops::Div<Quantity<D2, S>> for Quantity<D1, S>
+where
+	D1: dimensions::Dimension + ops::Div<D2, Output: dimensions::Dimension>,
+	D2: dimensions::Dimension,
+	S: Scalar,
+{
+	type Output = Quantity<typenum::Quot<D1, D2>, S>;
+
+	fn div(self, rhs: Quantity<D2, S>) -> Self::Output {
+		Quantity(self.0 / rhs.0, marker::PhantomData)
 	}
+}
 
-	pub fn get_magnitude(self) -> f64 {
-		self.0.get_magnitude()
+// Multiplication of a scalar quantity and a vector quantity (e.g.,
+//	`Time * Velocity`).
+impl<D1, D2, V> ops::Mul<QuantityN<D2, V>> for Quantity<D1, V::Scalar>
+where
+	D1: dimensions::Dimension + ops::Mul<D2, Output: dimensions::Dimension>,
+	D2: dimensions::Dimension,
+	V: Vector,
+{
+	type Output = QuantityN<typenum::Prod<D1, D2>, V>;
+
+	fn mul(self, rhs: QuantityN<D2, V>) -> Self::Output {
+		QuantityN(rhs.0 * self.0, marker::PhantomData)
 	}
 }
 
-impl ops::Add for Displacement {
+impl<D: dimensions::Dimension, S: Scalar> ops::Add for Quantity<D, S> {
 	type Output = Self;
 
 	fn add(self, rhs: Self) -> Self::Output {
-		Self(self.0 + rhs.0)
+		Self(self.0 + rhs.0, marker::PhantomData)
 	}
 }
 
-impl ops::AddAssign for Displacement {
+impl<D: dimensions::Dimension, S: Scalar> ops::AddAssign for Quantity<D, S> {
 	fn add_assign(&mut self, other: Self) {
 		*self = *self + other;
 	}
 }
 
-impl ops::Sub for Displacement {
+impl<D: dimensions::Dimension, S: Scalar> ops::Sub for Quantity<D, S> {
 	type Output = Self;
 
 	fn sub(self, rhs: Self) -> Self::Output {
-		Self(self.0 - rhs.0)
+		Self(self.0 - rhs.0, marker::PhantomData)
 	}
 }
 
-impl ops::SubAssign for Displacement {
+impl<D: dimensions::Dimension, S: Scalar> ops::SubAssign for Quantity<D, S> {
 	fn sub_assign(&mut self, other: Self) {
-		*self = *self + other;
+		*self = *self - other;
 	}
 }
 
-/// Velocity.
-/// Wraps `Vector2` and provides functionality specific to velocity.
+impl<D: dimensions::Dimension, S: Scalar> ops::Neg for Quantity<D, S> {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		Self(-self.0, marker::PhantomData)
+	}
+}
+
+impl<D: dimensions::Dimension, S: Scalar> iter::Sum for Quantity<D, S> {
+	fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+		iter.fold(Self::from_number(S::zero()), |total, q| total + q)
+	}
+}
+
+/// Writes the number followed by the symbol of its SI unit, e.g. "9.81 m/s²".
+/// Formatting options such as precision apply to the number. The number is
+/// always taken to be in SI units, so convert a quantity from a simulation
+/// with another unit system with `UnitSystem::to_si` first.
+impl<D: dimensions::Dimension, S: Scalar> fmt::Display for Quantity<D, S> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		fmt::Display::fmt(&self.0.to_f64(), f)?;
+		write_symbol::<D>(f)
+	}
+}
+
+/// Reads a number followed by an optional unit, e.g. "5 km" or "36 km/h", in
+/// SI units. A number without a unit is taken to be in SI units. See the
+/// `units` module, and use `UnitSystem::parse` to read a number in another
+/// unit system.
+impl<D: dimensions::Dimension, S: Scalar> str::FromStr for Quantity<D, S> {
+	type Err = units::ParseQuantityError;
+
+	fn from_str(text: &str) -> Result<Self, Self::Err> {
+		units::parse::<D>(text).map(|number| Self::from_number(S::from_f64(number)))
+	}
+}
+
+fn write_symbol<D: dimensions::Dimension>(f: &mut fmt::Formatter) -> fmt::Result {
+	let symbol = units::get_symbol::<D>();
+	if !symbol.is_empty() {
+		write!(f, " {}", symbol)?;
+	}
+	Ok(())
+}
+
+/// Represents a length of time. Could be though of as milliseconds, seconds,
+/// minutes, etc.
+pub type Time<S = f64> = Quantity<dimensions::Time, S>;
+
+impl<S: Scalar> Time<S> {
+	pub fn new(t: S) -> Self {
+		Self::from_number(t)
+	}
+}
+
+/// Operations shared by `Vector2` and `Vector3`. Vector quantities, fields and
+/// `Simulation` are generic over this trait, so the same code works in two and
+/// three dimensions.
+pub trait Vector:
+	Copy
+	+ PartialEq
+	+ fmt::Debug
+	+ Persistable
+	+ ops::Add<Output = Self>
+	+ ops::Sub<Output = Self>
+	+ ops::Neg<Output = Self>
+	+ ops::Mul<<Self as Vector>::Scalar, Output = Self>
+	+ ops::Div<<Self as Vector>::Scalar, Output = Self>
+	+ iter::Sum
+	+ 'static
+{
+	/// The number type of the vector's components.
+	type Scalar: Scalar;
+
+	/// The number of components in the vector.
+	const DIMENSIONS: usize;
+
+	/// Gets a vector with every component equal to zero.
+	fn zero() -> Self;
+
+	/// Gets a component of the vector. Index 0 is x, 1 is y and 2 is z.
+	///
+	/// # Panics
+	/// Panics if `index` is not less than `DIMENSIONS`.
+	fn get_component(&self, index: usize) -> Self::Scalar;
+
+	/// Creates a vector from its components, in the order x, y, z.
+	///
+	/// # Panics
+	/// Panics if the number of components is not `DIMENSIONS`.
+	fn from_components(components: &[Self::Scalar]) -> Self;
+
+	/// Gets the dot product of this vector and `other`.
+	fn dot(self, other: Self) -> Self::Scalar;
+
+	/// Gets the magnitude of this vector.
+	fn get_magnitude(self) -> Self::Scalar {
+		self.dot(self).sqrt()
+	}
+
+	/// Gets a unit vector pointing in the same direction as this vector.
+	fn get_unit_vector(self) -> Self {
+		self / self.get_magnitude()
+	}
+
+	/// Linearly interpolates between this vector and `other`. `t` = 0 gives
+	/// this vector and `t` = 1 gives `other`.
+	fn lerp(self, other: Self, t: Self::Scalar) -> Self {
+		self + (other - self) * t
+	}
+
+	/// Gets the part of this vector that points along `other`, which must not
+	/// be zero.
+	fn project_onto(self, other: Self) -> Self {
+		other * (self.dot(other) / other.dot(other))
+	}
+
+	/// Gets the angle between this vector and `other`, in radians, from 0 to
+	/// π. Neither vector may be zero.
+	fn angle_between(self, other: Self) -> Self::Scalar {
+		// Kahan's formula, which stays accurate for nearly parallel vectors,
+		//	unlike acos of the dot product.
+		let (a, b) = (self.get_unit_vector(), other.get_unit_vector());
+		Self::Scalar::from_f64(2.0) * (a - b).get_magnitude().atan2((a + b).get_magnitude())
+	}
+}
+
+// Vectors have to be serializable when the persistence feature is enabled, so
+//	that generic fields can be saved. This trait is a bound on `Vector` that
+//	only requires serde when it's available.
+#[cfg(feature = "persistence")]
+pub trait Persistable: serde::Serialize + serde::de::DeserializeOwned {}
+#[cfg(feature = "persistence")]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Persistable for T {}
+
+#[cfg(not(feature = "persistence"))]
+pub trait Persistable {}
+#[cfg(not(feature = "persistence"))]
+impl<T> Persistable for T {}
+
+/// A two-dimensional vector (not to be confused with `Vec<T>`).
+/// Supports basic vector math.
 #[derive(PartialEq)]
 #[derive(Debug)]
 #[derive(Clone, Copy)]
-pub struct Velocity(Vector2);
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector2<S = f64> {
+	x: S,
+	y: S,
+}
 
-impl Velocity {
-	pub fn new(x: f64, y: f64) -> Self {
-		Self(Vector2::new(x, y))
+impl<S: Scalar> Vector2<S> {
+	pub fn new(x: S, y:S) -> Self {
+		Self {
+			x: x,
+			y: y,
+		}
 	}
 
-	pub fn x(&self) -> f64 {
-		self.0.x
+	pub fn x(&self) -> S {
+		self.x
 	}
 
-	pub fn y(&self) -> f64 {
-		self.0.y
+	pub fn y(&self) -> S {
+		self.y
 	}
 
-	pub fn get_magnitude(self) -> f64 {
-		self.0.get_magnitude()
+	/// Gets the magnitude of this vector.
+	pub fn get_magnitude(self) -> S {
+		(self.x * self.x + self.y * self.y).sqrt()
+	}
+
+	/// Gets a unit vector pointing in the same direction as this vector.
+	pub fn get_unit_vector(self) -> Self {
+		let magnitude = self.get_magnitude();
+
+		Self {
+			x: self.x / magnitude,
+			y: self.y / magnitude,
+		}
+	}
+
+	/// Gets the two-dimensional cross product of this vector and `other`: the
+	/// z component of the cross product of the two vectors extended into three
+	/// dimensions. Positive when `other` is counterclockwise from this vector.
+	pub fn cross(self, other: Self) -> S {
+		self.x * other.y - self.y * other.x
+	}
+
+	/// Rotates this vector counterclockwise by `angle` radians.
+	pub fn rotate(self, angle: S) -> Self {
+		let (sin, cos) = (angle.sin(), angle.cos());
+
+		Self {
+			x: self.x * cos - self.y * sin,
+			y: self.x * sin + self.y * cos,
+		}
+	}
+
+	/// Gets the angle of this vector counterclockwise from the positive x
+	/// axis, in radians, from -π to π.
+	pub fn get_angle(self) -> S {
+		self.y.atan2(self.x)
+	}
+
+	/// Creates a vector from polar coordinates: its magnitude and its angle
+	/// counterclockwise from the positive x axis, in radians.
+	pub fn from_polar(magnitude: S, angle: S) -> Self {
+		Self {
+			x: magnitude * angle.cos(),
+			y: magnitude * angle.sin(),
+		}
+	}
+
+	/// Gets this vector's polar coordinates: its magnitude and its angle
+	/// counterclockwise from the positive x axis, in radians, from -π to π.
+	pub fn to_polar(self) -> (S, S) {
+		(self.get_magnitude(), self.get_angle())
+	}
+
+	/// Gets this vector rotated counterclockwise by a right angle. Exact, unlike
+	/// `rotate`, since it only swaps and negates components.
+	pub fn get_perpendicular(self) -> Self {
+		Self {
+			x: -self.y,
+			y: self.x,
+		}
 	}
 }
 
-// Multiplication of velocity by time.
-impl ops::Mul<Time> for Velocity {
-	type Output = Displacement;
+// Scalar multiplication of a vector.
+impl<S: Scalar> ops::Mul<S> for Vector2<S> {
+	type Output = Self;
 
-	fn mul(self, rhs: Time) -> Self::Output {
-		Displacement(self.0 * rhs.0)
+	fn mul(self, rhs: S) -> Self::Output {
+		Self {
+			x: self.x * rhs,
+			y: self.y * rhs,
+		}
 	}
 }
+impl ops::Mul<Vector2<f64>> for f64 {
+	type Output = Vector2<f64>;
 
-impl ops::Add for Velocity {
-	type Output = Velocity;
+	fn mul(self, rhs: Vector2<f64>) -> Self::Output {
+		rhs * self
+	}
+}
+impl ops::Mul<Vector2<f32>> for f32 {
+	type Output = Vector2<f32>;
 
-	fn add(self, rhs: Self) -> Self::Output {
-		Self(self.0 + rhs.0)
+	fn mul(self, rhs: Vector2<f32>) -> Self::Output {
+		rhs * self
 	}
 }
 
-impl ops::AddAssign for Velocity {
-	fn add_assign(&mut self, other: Self) {
-		*self = *self + other;
+// Scalar division of a vector.
+impl<S: Scalar> ops::Div<S> for Vector2<S> {
+	type Output = Self;
+
+	fn div(self, rhs: S) -> Self::Output {
+		Self {
+			x: self.x / rhs,
+			y: self.y / rhs,
+		}
 	}
 }
 
-impl ops::Sub for Velocity {
+// Vector addition.
+impl<S: Scalar> ops::Add for Vector2<S> {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		Self {
+			x: self.x + rhs.x,
+			y: self.y + rhs.y,
+		}
+	}
+}
+
+// Vector subtraction.
+impl<S: Scalar> ops::Sub for Vector2<S> {
 	type Output = Self;
 
 	fn sub(self, rhs: Self) -> Self::Output {
-		Self(self.0 - rhs.0)
+		Self {
+			x: self.x - rhs.x,
+			y: self.y - rhs.y,
+		}
 	}
 }
 
-impl ops::SubAssign for Velocity {
-	fn sub_assign(&mut self, other: Self) {
-		*self = *self - other;
+// Vector negation.
+impl<S: Scalar> ops::Neg for Vector2<S> {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		Self {
+			x: -self.x,
+			y: -self.y,
+		}
 	}
 }
 
-/// Acceleration.
-/// Wraps `Vector2` and provides functionality specific to acceleration.
+impl<S: Scalar> iter::Sum for Vector2<S> {
+	fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+		iter.fold(Vector::zero(), |total, v| total + v)
+	}
+}
+
+impl<S: Scalar> Vector for Vector2<S> {
+	type Scalar = S;
+
+	const DIMENSIONS: usize = 2;
+
+	fn zero() -> Self {
+		Self::new(S::zero(), S::zero())
+	}
+
+	fn get_component(&self, index: usize) -> S {
+		match index {
+			0 => self.x,
+			1 => self.y,
+			_ => panic!("Vector2 has no component {}", index),
+		}
+	}
+
+	fn from_components(components: &[S]) -> Self {
+		match components {
+			[x, y] => Self::new(*x, *y),
+			_ => panic!("Vector2 needs 2 components, not {}", components.len()),
+		}
+	}
+
+	fn dot(self, other: Self) -> S {
+		self.x * other.x + self.y * other.y
+	}
+
+	fn get_magnitude(self) -> S {
+		Vector2::get_magnitude(self)
+	}
+
+	fn get_unit_vector(self) -> Self {
+		Vector2::get_unit_vector(self)
+	}
+}
+
+/// A three-dimensional vector. Supports the same math as `Vector2`.
 #[derive(PartialEq)]
 #[derive(Debug)]
 #[derive(Clone, Copy)]
-pub struct Acceleration(Vector2);
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector3<S = f64> {
+	x: S,
+	y: S,
+	z: S,
+}
 
-impl Acceleration {
-	pub fn new(x: f64, y: f64) -> Self {
-		Self(Vector2::new(x, y))
+impl<S: Scalar> Vector3<S> {
+	pub fn new(x: S, y: S, z: S) -> Self {
+		Self {
+			x: x,
+			y: y,
+			z: z,
+		}
 	}
 
-	pub fn x(&self) -> f64 {
-		self.0.x
+	pub fn x(&self) -> S {
+		self.x
 	}
 
-	pub fn y(&self) -> f64 {
-		self.0.y
+	pub fn y(&self) -> S {
+		self.y
 	}
 
-	pub fn get_magnitude(self) -> f64 {
-		self.0.get_magnitude()
+	pub fn z(&self) -> S {
+		self.z
+	}
+
+	/// Gets the magnitude of this vector.
+	pub fn get_magnitude(self) -> S {
+		(self.x * self.x + self.y * self.y + self.z * self.z).sqrt()
+	}
+
+	/// Gets a unit vector pointing in the same direction as this vector.
+	pub fn get_unit_vector(self) -> Self {
+		let magnitude = self.get_magnitude();
+
+		Self {
+			x: self.x / magnitude,
+			y: self.y / magnitude,
+			z: self.z / magnitude,
+		}
+	}
+
+	/// Gets the cross product of this vector and `other`.
+	pub fn cross(self, other: Self) -> Self {
+		Self {
+			x: self.y * other.z - self.z * other.y,
+			y: self.z * other.x - self.x * other.z,
+			z: self.x * other.y - self.y * other.x,
+		}
 	}
 }
 
-// Scalar multiplication of acceleration.
-impl ops::Mul<f64> for Acceleration {
+// Vector negation.
+impl<S: Scalar> ops::Neg for Vector3<S> {
 	type Output = Self;
 
-	fn mul(self, rhs: f64) -> Self::Output {
-		Acceleration(self.0 * rhs)
+	fn neg(self) -> Self::Output {
+		Self {
+			x: -self.x,
+			y: -self.y,
+			z: -self.z,
+		}
 	}
 }
-impl ops::Mul<Acceleration> for f64 {
-	type Output = Acceleration;
 
-	fn mul(self, rhs: Acceleration) -> Self::Output {
-		Acceleration(rhs.0 * self)
+impl<S: Scalar> iter::Sum for Vector3<S> {
+	fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+		iter.fold(Vector::zero(), |total, v| total + v)
 	}
 }
 
-// Multiplication of acceleration by time.
-impl ops::Mul<Time> for Acceleration {
-	type Output = Velocity;
+impl<S: Scalar> Vector for Vector3<S> {
+	type Scalar = S;
 
-	fn mul(self, rhs: Time) -> Self::Output {
-		Velocity(self.0 * rhs.0)
+	const DIMENSIONS: usize = 3;
+
+	fn zero() -> Self {
+		Self::new(S::zero(), S::zero(), S::zero())
+	}
+
+	fn get_component(&self, index: usize) -> S {
+		match index {
+			0 => self.x,
+			1 => self.y,
+			2 => self.z,
+			_ => panic!("Vector3 has no component {}", index),
+		}
+	}
+
+	fn from_components(components: &[S]) -> Self {
+		match components {
+			[x, y, z] => Self::new(*x, *y, *z),
+			_ => panic!("Vector3 needs 3 components, not {}", components.len()),
+		}
+	}
+
+	fn dot(self, other: Self) -> S {
+		self.x * other.x + self.y * other.y + self.z * other.z
+	}
+
+	fn get_magnitude(self) -> S {
+		Vector3::get_magnitude(self)
+	}
+
+	fn get_unit_vector(self) -> Self {
+		Vector3::get_unit_vector(self)
 	}
 }
 
-impl ops::Add for Acceleration {
-	type Output = Acceleration;
+// Scalar multiplication of a vector.
+impl<S: Scalar> ops::Mul<S> for Vector3<S> {
+	type Output = Self;
+
+	fn mul(self, rhs: S) -> Self::Output {
+		Self {
+			x: self.x * rhs,
+			y: self.y * rhs,
+			z: self.z * rhs,
+		}
+	}
+}
+impl ops::Mul<Vector3<f64>> for f64 {
+	type Output = Vector3<f64>;
+
+	fn mul(self, rhs: Vector3<f64>) -> Self::Output {
+		rhs * self
+	}
+}
+impl ops::Mul<Vector3<f32>> for f32 {
+	type Output = Vector3<f32>;
+
+	fn mul(self, rhs: Vector3<f32>) -> Self::Output {
+		rhs * self
+	}
+}
+
+// Scalar division of a vector.
+impl<S: Scalar> ops::Div<S> for Vector3<S> {
+	type Output = Self;
+
+	fn div(self, rhs: S) -> Self::Output {
+		Self {
+			x: self.x / rhs,
+			y: self.y / rhs,
+			z: self.z / rhs,
+		}
+	}
+}
+
+// Vector addition.
+impl<S: Scalar> ops::Add for Vector3<S> {
+	type Output = Self;
 
 	fn add(self, rhs: Self) -> Self::Output {
-		Self(self.0 + rhs.0)
+		Self {
+			x: self.x + rhs.x,
+			y: self.y + rhs.y,
+			z: self.z + rhs.z,
+		}
 	}
 }
 
-impl ops::Sub for Acceleration {
+// Vector subtraction.
+impl<S: Scalar> ops::Sub for Vector3<S> {
 	type Output = Self;
 
 	fn sub(self, rhs: Self) -> Self::Output {
-		Self(self.0 - rhs.0)
+		Self {
+			x: self.x - rhs.x,
+			y: self.y - rhs.y,
+			z: self.z - rhs.z,
+		}
 	}
 }
 
-/// Force.
-/// Wraps `Vector2` and provides functionality specific to forces.
-#[derive(PartialEq)]
-#[derive(Debug)]
-#[derive(Clone, Copy)]
-pub struct Force(Vector2);
+/// Operations shared by the vector quantities (`Displacement`, `Velocity`,
+/// `Acceleration`, `Force` and `Momentum`) in any number of dimensions. Each
+/// quantity wraps a `Vector`, and the default methods work on that vector and
+/// wrap the result back up as the same quantity.
+///
+/// Along with these methods, every vector quantity supports addition,
+/// subtraction, negation, `Sum`, and multiplication and division by its
+/// scalar type.
+pub trait VectorQuantity:
+	Copy
+	+ ops::Add<Output = Self>
+	+ ops::Sub<Output = Self>
+	+ ops::Neg<Output = Self>
+	+ iter::Sum
+{
+	/// The vector the quantity wraps, which sets its dimensions and number
+	/// type.
+	type Vector: Vector;
+
+	fn from_vector(vector: Self::Vector) -> Self;
+
+	fn get_vector(&self) -> Self::Vector;
+
+	/// Gets a unit vector pointing in the same direction as this quantity.
+	fn get_direction(self) -> Self::Vector {
+		self.get_vector().get_unit_vector()
+	}
+
+	/// Gets the dot product of this quantity's vector and `vector`.
+	fn dot(self, vector: Self::Vector) -> <Self::Vector as Vector>::Scalar {
+		self.get_vector().dot(vector)
+	}
+
+	/// Linearly interpolates between this quantity and `other`. `t` = 0 gives
+	/// this quantity and `t` = 1 gives `other`.
+	fn lerp(self, other: Self, t: <Self::Vector as Vector>::Scalar) -> Self {
+		Self::from_vector(self.get_vector().lerp(other.get_vector(), t))
+	}
+
+	/// Gets the part of this quantity that points along `direction`, which
+	/// must not be zero. E.g., the part of a velocity along a surface normal.
+	fn project_onto(self, direction: Self::Vector) -> Self {
+		Self::from_vector(self.get_vector().project_onto(direction))
+	}
+
+	/// Gets the angle between this quantity and `direction`, in radians, from
+	/// 0 to π.
+	fn angle_between(self, direction: Self::Vector) -> <Self::Vector as Vector>::Scalar {
+		self.get_vector().angle_between(direction)
+	}
+
+	/// Gets the two-dimensional cross product of this quantity's vector and
+	/// `vector`. See `Vector2::cross`.
+	fn cross<S: Scalar>(self, vector: Vector2<S>) -> S
+	where
+		Self: VectorQuantity<Vector = Vector2<S>>,
+	{
+		self.get_vector().cross(vector)
+	}
+
+	/// Rotates this two-dimensional quantity counterclockwise by `angle`
+	/// radians.
+	fn rotate<S: Scalar>(self, angle: S) -> Self
+	where
+		Self: VectorQuantity<Vector = Vector2<S>>,
+	{
+		Self::from_vector(self.get_vector().rotate(angle))
+	}
+
+	/// Gets the angle of this two-dimensional quantity counterclockwise from
+	/// the positive x axis, in radians, from -π to π.
+	fn get_angle<S: Scalar>(self) -> S
+	where
+		Self: VectorQuantity<Vector = Vector2<S>>,
+	{
+		self.get_vector().get_angle()
+	}
+}
+
+// Scalar multiplication and division of quantities. These can't be generic
+//	over `V::Scalar`: the compiler can't rule out `V::Scalar` being a
+//	`Quantity`, so a generic implementation would overlap with multiplying a
+//	vector quantity by a scalar quantity (e.g., `Velocity * Time`). Instead,
+//	this macro implements them for one scalar type at a time. `fixed_point`
+//	uses it for `Fixed`.
+macro_rules! impl_scalar_operations {
+	($scalar:ty) => {
+		impl<D: $crate::dimensions::Dimension> std::ops::Mul<$crate::physical_quantities::Quantity<D, $scalar>>
+			for $scalar
+		{
+			type Output = $crate::physical_quantities::Quantity<D, $scalar>;
+
+			fn mul(self, rhs: $crate::physical_quantities::Quantity<D, $scalar>) -> Self::Output {
+				rhs * self
+			}
+		}
+
+		impl<D, V> std::ops::Mul<$scalar> for $crate::physical_quantities::QuantityN<D, V>
+		where
+			D: $crate::dimensions::Dimension,
+			V: $crate::physical_quantities::Vector<Scalar = $scalar>,
+		{
+			type Output = Self;
+
+			fn mul(self, rhs: $scalar) -> Self::Output {
+				Self::from_vector(self.get_vector() * rhs)
+			}
+		}
 
-impl Force {
-	pub fn new(x: f64, y: f64) -> Self {
-		Self(Vector2::new(x, y))
+		impl<D, V> std::ops::Mul<$crate::physical_quantities::QuantityN<D, V>> for $scalar
+		where
+			D: $crate::dimensions::Dimension,
+			V: $crate::physical_quantities::Vector<Scalar = $scalar>,
+		{
+			type Output = $crate::physical_quantities::QuantityN<D, V>;
+
+			fn mul(self, rhs: $crate::physical_quantities::QuantityN<D, V>) -> Self::Output {
+				rhs * self
+			}
+		}
+
+		impl<D, V> std::ops::Div<$scalar> for $crate::physical_quantities::QuantityN<D, V>
+		where
+			D: $crate::dimensions::Dimension,
+			V: $crate::physical_quantities::Vector<Scalar = $scalar>,
+		{
+			type Output = Self;
+
+			fn div(self, rhs: $scalar) -> Self::Output {
+				Self::from_vector(self.get_vector() / rhs)
+			}
+		}
+	};
+}
+#[cfg(feature = "fixed-point")]
+pub(crate) use impl_scalar_operations;
+
+impl_scalar_operations!(f64);
+impl_scalar_operations!(f32);
+
+/// Mass.
+pub type Mass<S = f64> = Quantity<dimensions::Mass, S>;
+
+impl<S: Scalar> Mass<S> {
+	pub fn new(m: S) -> Self {
+		if m <= S::zero() {
+			panic!("Mass must be positive.");
+		}
+
+		Self::from_number(m)
 	}
+}
+
+/// A vector physical quantity: a `Vector` with dimension `D`. The vector sets
+/// the number of spatial dimensions and the number type. Usually used through
+/// an alias such as `Displacement`, `Velocity` or `Force`.
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "persistence", serde(transparent))]
+pub struct QuantityN<D, V>(
+	V,
+	#[cfg_attr(feature = "persistence", serde(skip))]
+	marker::PhantomData<D>,
+);
+
+// Implemented by hand for the same reason as `Quantity`'s.
+impl<D, V: Clone> Clone for QuantityN<D, V> {
+	fn clone(&self) -> Self {
+		Self(self.0.clone(), marker::PhantomData)
+	}
+}
+
+impl<D, V: Copy> Copy for QuantityN<D, V> {}
+
+impl<D, V: PartialEq> PartialEq for QuantityN<D, V> {
+	fn eq(&self, other: &Self) -> bool {
+		self.0 == other.0
+	}
+}
+
+impl<D, V: fmt::Debug> fmt::Debug for QuantityN<D, V> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.debug_tuple("QuantityN").field(&self.0).finish()
+	}
+}
 
-	pub fn x(&self) -> f64 {
+impl<D: dimensions::Dimension, V: Vector> QuantityN<D, V> {
+	/// Creates a vector quantity from a vector of any dimension.
+	pub fn from_vector(vector: V) -> Self {
+		Self(vector, marker::PhantomData)
+	}
+
+	pub fn get_vector(&self) -> V {
+		self.0
+	}
+
+	pub fn get_magnitude(self) -> V::Scalar {
+		self.0.get_magnitude()
+	}
+
+	/// Gets the magnitude as a scalar quantity of the same dimension (e.g., a
+	/// speed from a velocity, or a distance from a displacement).
+	pub fn get_norm(self) -> Quantity<D, V::Scalar> {
+		Quantity(self.0.get_magnitude(), marker::PhantomData)
+	}
+
+	/// Gets the square of the magnitude. Cheaper and more precise than squaring
+	/// `get_norm`, since there's no square root.
+	pub fn get_norm_squared(self) -> Quantity<typenum::Prod<D, D>, V::Scalar>
+	where
+		D: ops::Mul<D, Output: dimensions::Dimension>,
+	{
+		Quantity(self.0.dot(self.0), marker::PhantomData)
+	}
+}
+
+impl<D: dimensions::Dimension, S: Scalar> QuantityN<D, Vector2<S>> {
+	pub fn new(x: S, y: S) -> Self {
+		Self::from_vector(Vector2::new(x, y))
+	}
+
+	/// Creates a quantity from polar coordinates. See `Vector2::from_polar`.
+	///
+	/// # Arguments
+	/// * `magnitude` - The quantity's magnitude (e.g., a speed for a
+	///		velocity).
+	/// * `angle` - The quantity's angle counterclockwise from the positive x
+	///		axis, in radians.
+	pub fn from_polar(magnitude: Quantity<D, S>, angle: S) -> Self {
+		Self::from_vector(Vector2::from_polar(magnitude.0, angle))
+	}
+
+	/// Gets this quantity's polar coordinates: its magnitude and its angle
+	/// counterclockwise from the positive x axis, in radians, from -π to π.
+	pub fn to_polar(self) -> (Quantity<D, S>, S) {
+		let (magnitude, angle) = self.0.to_polar();
+		(Quantity(magnitude, marker::PhantomData), angle)
+	}
+
+	/// Gets this quantity rotated counterclockwise by a right angle. See
+	/// `Vector2::get_perpendicular`.
+	pub fn get_perpendicular(self) -> Self {
+		Self::from_vector(self.0.get_perpendicular())
+	}
+
+	pub fn x(&self) -> S {
 		self.0.x
 	}
 
-	pub fn y(&self) -> f64 {
+	pub fn y(&self) -> S {
 		self.0.y
 	}
+}
+
+impl<D: dimensions::Dimension, S: Scalar> QuantityN<D, Vector3<S>> {
+	pub fn new(x: S, y: S, z: S) -> Self {
+		Self::from_vector(Vector3::new(x, y, z))
+	}
 
-	pub fn get_magnitude(self) -> f64 {
-		self.0.get_magnitude()
+	pub fn x(&self) -> S {
+		self.0.x
+	}
+
+	pub fn y(&self) -> S {
+		self.0.y
+	}
+
+	pub fn z(&self) -> S {
+		self.0.z
 	}
 }
 
-// Force divided by mass.
-impl ops::Div<Mass> for Force {
-	type Output = Acceleration;
+// Multiplication of a vector quantity by a scalar quantity (e.g.,
+//	`Velocity * Time`).
+impl<D1, D2, V> ops::Mul<Quantity<D2, V::Scalar>> for QuantityN<D1, V>
+where
+	D1: dimensions::Dimension + ops::Mul<D2, Output: dimensions::Dimension>,
+	D2: dimensions::Dimension,
+	V: Vector,
+{
+	type Output = QuantityN<typenum::Prod<D1, D2>, V>;
 
-	fn div(self, rhs: Mass) -> Self::Output {
-		Acceleration::new(
-			self.x() / rhs.0,
-			self.y() / rhs.0,
-		)
+	fn mul(self, rhs: Quantity<D2, V::Scalar>) -> Self::Output {
+		QuantityN(self.0 * rhs.0, marker::PhantomData)
 	}
 }
 
-impl ops::Add for Force {
-	type Output = Force;
+// Division of a vector quantity by a scalar quantity (e.g., `Force / Mass`).
+impl<D1, D2, V> ops::Div<Quantity<D2, V::Scalar>> for QuantityN<D1, V>
+where
+	D1: dimensions::Dimension + ops::Div<D2, Output: dimensions::Dimension>,
+	D2: dimensions::Dimension,
+	V: Vector,
+{
+	type Output = QuantityN<typenum::Quot<D1, D2>, V>;
+
+	fn div(self, rhs: Quantity<D2, V::Scalar>) -> Self::Output {
+		QuantityN(self.0 / rhs.0, marker::PhantomData)
+	}
+}
+
+impl<D: dimensions::Dimension, V: Vector> ops::Add for QuantityN<D, V> {
+	type Output = Self;
 
 	fn add(self, rhs: Self) -> Self::Output {
-		Self(self.0 + rhs.0)
+		Self(self.0 + rhs.0, marker::PhantomData)
+	}
+}
+
+impl<D: dimensions::Dimension, V: Vector> ops::AddAssign for QuantityN<D, V> {
+	fn add_assign(&mut self, other: Self) {
+		*self = *self + other;
 	}
 }
 
-impl ops::Sub for Force {
+impl<D: dimensions::Dimension, V: Vector> ops::Sub for QuantityN<D, V> {
 	type Output = Self;
 
 	fn sub(self, rhs: Self) -> Self::Output {
-		Self(self.0 - rhs.0)
+		Self(self.0 - rhs.0, marker::PhantomData)
+	}
+}
+
+impl<D: dimensions::Dimension, V: Vector> ops::SubAssign for QuantityN<D, V> {
+	fn sub_assign(&mut self, other: Self) {
+		*self = *self - other;
+	}
+}
+
+impl<D: dimensions::Dimension, V: Vector> ops::Neg for QuantityN<D, V> {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output {
+		Self(-self.0, marker::PhantomData)
+	}
+}
+
+impl<D: dimensions::Dimension, V: Vector> iter::Sum for QuantityN<D, V> {
+	fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+		Self::from_vector(iter.map(|q| q.0).sum())
+	}
+}
+
+/// Writes the components followed by the symbol of their SI unit, e.g.
+/// "(3, -4) m/s". Like `Quantity`, the components are always taken to be in
+/// SI units.
+impl<D: dimensions::Dimension, V: Vector> fmt::Display for QuantityN<D, V> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "(")?;
+		for i in 0..V::DIMENSIONS {
+			if i > 0 {
+				write!(f, ", ")?;
+			}
+			fmt::Display::fmt(&self.0.get_component(i).to_f64(), f)?;
+		}
+		write!(f, ")")?;
+		write_symbol::<D>(f)
+	}
+}
+
+impl<D: dimensions::Dimension, V: Vector> VectorQuantity for QuantityN<D, V> {
+	type Vector = V;
+
+	fn from_vector(vector: V) -> Self {
+		Self(vector, marker::PhantomData)
+	}
+
+	fn get_vector(&self) -> V {
+		self.0
+	}
+}
+
+/// Position in space (displacement from the origin), displacement relative to
+/// some starting location, or distance from some arbitrary position.
+pub type DisplacementN<V> = QuantityN<dimensions::Length, V>;
+/// A two-dimensional displacement.
+pub type Displacement<S = f64> = DisplacementN<Vector2<S>>;
+/// A three-dimensional displacement.
+pub type Displacement3<S = f64> = DisplacementN<Vector3<S>>;
+
+/// Velocity.
+pub type VelocityN<V> = QuantityN<dimensions::Velocity, V>;
+/// A two-dimensional velocity.
+pub type Velocity<S = f64> = VelocityN<Vector2<S>>;
+/// A three-dimensional velocity.
+pub type Velocity3<S = f64> = VelocityN<Vector3<S>>;
+
+/// Acceleration.
+pub type AccelerationN<V> = QuantityN<dimensions::Acceleration, V>;
+/// A two-dimensional acceleration.
+pub type Acceleration<S = f64> = AccelerationN<Vector2<S>>;
+/// A three-dimensional acceleration.
+pub type Acceleration3<S = f64> = AccelerationN<Vector3<S>>;
+
+/// Force.
+pub type ForceN<V> = QuantityN<dimensions::Force, V>;
+/// A two-dimensional force.
+pub type Force<S = f64> = ForceN<Vector2<S>>;
+/// A three-dimensional force.
+pub type Force3<S = f64> = ForceN<Vector3<S>>;
+
+/// Linear momentum.
+pub type MomentumN<V> = QuantityN<dimensions::Momentum, V>;
+/// A two-dimensional momentum.
+pub type Momentum<S = f64> = MomentumN<Vector2<S>>;
+/// A three-dimensional momentum.
+pub type Momentum3<S = f64> = MomentumN<Vector3<S>>;
+
+/// Impulse, the change in momentum caused by a force acting over time
+/// (`Force * Time`). The same type as `MomentumN`.
+pub type ImpulseN<V> = MomentumN<V>;
+/// A two-dimensional impulse.
+pub type Impulse<S = f64> = ImpulseN<Vector2<S>>;
+/// A three-dimensional impulse.
+pub type Impulse3<S = f64> = ImpulseN<Vector3<S>>;
+
+/// Energy (e.g., kinetic or potential energy). May be negative.
+pub type Energy<S = f64> = Quantity<dimensions::Energy, S>;
+
+impl<S: Scalar> Energy<S> {
+	pub fn new(e: S) -> Self {
+		Self::from_number(e)
+	}
+}
+
+/// Angular momentum. In two dimensions, this is the z component of the
+/// angular momentum vector, so positive values are counterclockwise.
+pub type AngularMomentum<S = f64> = Quantity<dimensions::AngularMomentum, S>;
+
+impl<S: Scalar> AngularMomentum<S> {
+	pub fn new(l: S) -> Self {
+		Self::from_number(l)
+	}
+}
+
+/// A distance, or the magnitude of a displacement.
+pub type Length<S = f64> = Quantity<dimensions::Length, S>;
+
+impl<S: Scalar> Length<S> {
+	pub fn new(l: S) -> Self {
+		Self::from_number(l)
+	}
+}
+
+/// The magnitude of a velocity.
+pub type Speed<S = f64> = Quantity<dimensions::Velocity, S>;
+
+impl<S: Scalar> Speed<S> {
+	pub fn new(v: S) -> Self {
+		Self::from_number(v)
+	}
+}
+
+/// Area.
+pub type Area<S = f64> = Quantity<dimensions::Area, S>;
+
+impl<S: Scalar> Area<S> {
+	pub fn new(a: S) -> Self {
+		Self::from_number(a)
+	}
+}
+
+/// Power, energy per unit time.
+pub type Power<S = f64> = Quantity<dimensions::Power, S>;
+
+impl<S: Scalar> Power<S> {
+	pub fn new(p: S) -> Self {
+		Self::from_number(p)
+	}
+}
+
+/// Pressure, force per unit area.
+pub type Pressure<S = f64> = Quantity<dimensions::Pressure, S>;
+
+impl<S: Scalar> Pressure<S> {
+	pub fn new(p: S) -> Self {
+		Self::from_number(p)
+	}
+}
+
+/// An angle, in radians. Positive angles are counterclockwise.
+pub type Angle<S = f64> = Quantity<dimensions::Dimensionless, S>;
+
+impl<S: Scalar> Angle<S> {
+	pub fn new(radians: S) -> Self {
+		Self::from_number(radians)
+	}
+}
+
+/// Angular velocity, in radians per unit time. Positive angular velocities are
+/// counterclockwise.
+pub type AngularVelocity<S = f64> = Quantity<dimensions::Frequency, S>;
+
+impl<S: Scalar> AngularVelocity<S> {
+	pub fn new(omega: S) -> Self {
+		Self::from_number(omega)
+	}
+}
+
+/// Angular acceleration, in radians per unit time squared.
+pub type AngularAcceleration<S = f64> = Quantity<dimensions::AngularAcceleration, S>;
+
+impl<S: Scalar> AngularAcceleration<S> {
+	pub fn new(alpha: S) -> Self {
+		Self::from_number(alpha)
+	}
+}
+
+/// Torque. Positive torques spin things counterclockwise. Torque has the same
+/// dimension as energy (a newton meter is a joule), so this is the same type
+/// as `Energy`, and `Torque::new` is `Energy::new`.
+pub type Torque<S = f64> = Energy<S>;
+
+/// Moment of inertia, a body's resistance to angular acceleration (the
+/// rotational equivalent of mass).
+pub type MomentOfInertia<S = f64> = Quantity<dimensions::MomentOfInertia, S>;
+
+impl<S: Scalar> MomentOfInertia<S> {
+	pub fn new(i: S) -> Self {
+		if i <= S::zero() {
+			panic!("Moment of inertia must be positive.");
+		}
+
+		Self::from_number(i)
+	}
+}
+
+/// A gravitational constant, "big 'G'." Its units (m³ / (kg s²) in SI) make
+/// `G * Mass * Mass / Area` a force.
+pub type GravitationalConstant<S = f64> = Quantity<dimensions::GravitationalConstant, S>;
+
+impl<S: Scalar> GravitationalConstant<S> {
+	pub fn new(g: S) -> Self {
+		Self::from_number(g)
 	}
 }
 
 /// A type representing a number of ticks.
-#[derive(PartialEq)]
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
 #[derive(Debug)]
 #[derive(Clone, Copy)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ticks(u64);
 
 impl Ticks {
@@ -0,0 +1,1229 @@
+use crate::{physical_quantities, simulation};
+use crate::physical_quantities::Scalar;
+use std::cmp;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::marker;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use uuid::Uuid;
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::simulation_objects;
+
+	fn frame(tick: u64, particles: Vec<ParticleRecord>) -> Frame {
+		Frame::new(
+			physical_quantities::Ticks::new(tick),
+			physical_quantities::Time::new(tick as f64 * 0.1),
+			particles,
+		)
+	}
+
+	fn particle(id: Uuid, x: f64, field_names: Vec<&str>) -> ParticleRecord {
+		ParticleRecord::new(
+			id,
+			physical_quantities::Mass::new(2.5),
+			physical_quantities::Displacement::new(x, -1.0 / 3.0),
+			physical_quantities::Velocity::new(0.1, 1.0e-300),
+			field_names.into_iter().map(String::from).collect(),
+		)
+	}
+
+	fn sample_frames() -> Vec<Frame> {
+		let a = Uuid::new_v4();
+		let b = Uuid::new_v4();
+		vec![
+			frame(0, vec![particle(a, 1.0, vec!["gravity", "odd, \"name\"; here\\"])]),
+			frame(5, vec![particle(a, 2.0, vec![]), particle(b, 3.0, vec!["gravity"])]),
+			frame(10, vec![particle(b, f64::MAX, vec!["gravity", "gravity"])]),
+		]
+	}
+
+	fn sample_frames_3d() -> Vec<Frame3> {
+		let id = Uuid::new_v4();
+		(0..3).map(|tick| Frame3::new(
+			physical_quantities::Ticks::new(tick),
+			physical_quantities::Time::new(tick as f64 * 0.1),
+			vec![ParticleRecord3::new(
+				id,
+				physical_quantities::Mass::new(2.5),
+				physical_quantities::Displacement3::new(tick as f64, -1.0 / 3.0, 7.0),
+				physical_quantities::Velocity3::new(0.1, 1.0e-300, -4.0),
+				vec![String::from("gravity")],
+			)],
+		)).collect()
+	}
+
+	fn write_all<V: physical_quantities::Vector>(
+		writer: &mut dyn FrameWriter<V>,
+		frames: &[FrameN<V>],
+	) {
+		for f in frames {
+			writer.write_frame(f).expect("Should write the frame.");
+		}
+		writer.flush().expect("Should flush.");
+	}
+
+	/********************* CSV ********************/
+
+	#[test]
+	fn csv_round_trips_frames() {
+		let frames = sample_frames();
+		let mut bytes = Vec::new();
+		write_all(&mut CsvWriter::new(&mut bytes), &frames);
+
+		let read: Vec<Frame> = CsvReader::new(bytes.as_slice())
+			.collect::<Result<_, _>>()
+			.expect("Should read the frames.");
+
+		assert_eq!(read, frames);
+	}
+
+	#[test]
+	fn csv_has_header_and_row_per_particle() {
+		let mut bytes = Vec::new();
+		write_all(&mut CsvWriter::new(&mut bytes), &sample_frames());
+		let text = String::from_utf8(bytes).unwrap();
+		let lines: Vec<&str> = text.lines().collect();
+
+		assert_eq!(lines[0], CSV_HEADER);
+		assert_eq!(lines.len(), 5);
+		assert!(lines[1].starts_with("0,0,"));
+	}
+
+	#[test]
+	fn csv_round_trips_three_dimensional_frames() {
+		let frames = sample_frames_3d();
+		let mut bytes = Vec::new();
+		write_all(&mut CsvWriter::new(&mut bytes), &frames);
+		let text = String::from_utf8(bytes).unwrap();
+		assert_eq!(
+			text.lines().next().unwrap(),
+			"tick,time,particle_id,mass,position_x,position_y,position_z,\
+				velocity_x,velocity_y,velocity_z,field_names",
+		);
+
+		let read: Vec<Frame3> = CsvReader3::new(text.as_bytes())
+			.collect::<Result<_, _>>()
+			.expect("Should read the frames.");
+		assert_eq!(read, frames);
+
+		// A two-dimensional reader can't read it.
+		let result: Result<Vec<Frame>, RecordingError> = CsvReader::new(text.as_bytes()).collect();
+		assert!(
+			matches!(result, Err(RecordingError::Format { line: Some(1), .. })),
+			"{:?}",
+			result,
+		);
+	}
+
+	#[test]
+	fn csv_reader_reports_line_of_bad_row() {
+		let text = format!("{}\n0,0,{},1,2,3,4,5,\n1,0.1,nope\n", CSV_HEADER, Uuid::nil());
+		let result: Result<Vec<Frame>, RecordingError> =
+			CsvReader::new(text.as_bytes()).collect();
+
+		assert!(
+			matches!(result, Err(RecordingError::Format { line: Some(3), .. })),
+			"{:?}",
+			result,
+		);
+	}
+
+	#[test]
+	fn csv_reader_rejects_non_positive_mass() {
+		let text = format!("{}\n0,0,{},0,2,3,4,5,\n", CSV_HEADER, Uuid::nil());
+		let result: Result<Vec<Frame>, RecordingError> =
+			CsvReader::new(text.as_bytes()).collect();
+
+		assert!(
+			matches!(result, Err(RecordingError::Format { line: Some(2), .. })),
+			"{:?}",
+			result,
+		);
+	}
+
+	/********************* Binary ********************/
+
+	#[test]
+	fn binary_round_trips_frames() {
+		let frames = sample_frames();
+		let mut bytes = Vec::new();
+		write_all(&mut BinaryWriter::new(&mut bytes), &frames);
+
+		let read: Vec<Frame> = BinaryReader::new(bytes.as_slice())
+			.collect::<Result<_, _>>()
+			.expect("Should read the frames.");
+
+		assert_eq!(read, frames);
+	}
+
+	#[test]
+	fn binary_round_trips_three_dimensional_frames() {
+		let frames = sample_frames_3d();
+		let mut bytes = Vec::new();
+		write_all(&mut BinaryWriter::new(&mut bytes), &frames);
+
+		let read: Vec<Frame3> = BinaryReader3::new(bytes.as_slice())
+			.collect::<Result<_, _>>()
+			.expect("Should read the frames.");
+		assert_eq!(read, frames);
+
+		// A two-dimensional reader can't read it.
+		let mut reader = BinaryReader::new(bytes.as_slice());
+		match reader.next() {
+			Some(Err(RecordingError::Format { message, .. })) =>
+				assert_eq!(message, "the recording has 3 dimensions, not 2"),
+			other => panic!("Unexpected result: {:?}", other),
+		}
+		assert!(reader.next().is_none());
+	}
+
+	#[test]
+	fn binary_reader_reads_version_1_recordings() {
+		let frames = sample_frames();
+		let mut bytes = Vec::new();
+		write_all(&mut BinaryWriter::new(&mut bytes), &frames);
+		// Version 1 recordings had no dimensions byte and were always 2D.
+		bytes[7] = 1;
+		bytes.remove(8);
+
+		let read: Vec<Frame> = BinaryReader::new(bytes.as_slice())
+			.collect::<Result<_, _>>()
+			.expect("Should read the frames.");
+		assert_eq!(read, frames);
+	}
+
+	#[test]
+	fn binary_is_smaller_than_csv() {
+		let ids: Vec<Uuid> = (0..20).map(|_| Uuid::new_v4()).collect();
+		let frames: Vec<Frame> = (0..50).map(|t| {
+			frame(t, ids.iter().map(|id| particle(*id, t as f64, vec!["gravity"])).collect())
+		}).collect();
+		let mut csv = Vec::new();
+		let mut binary = Vec::new();
+		write_all(&mut CsvWriter::new(&mut csv), &frames);
+		write_all(&mut BinaryWriter::new(&mut binary), &frames);
+
+		assert!(binary.len() < csv.len(), "{} >= {}", binary.len(), csv.len());
+	}
+
+	#[test]
+	fn binary_reader_rejects_other_data() {
+		let mut reader = BinaryReader::new("tick,time\n".as_bytes());
+
+		assert!(matches!(reader.next(), Some(Err(RecordingError::Format { .. }))));
+		assert!(reader.next().is_none());
+	}
+
+	#[test]
+	fn binary_reader_reports_truncated_data() {
+		let mut bytes = Vec::new();
+		write_all(&mut BinaryWriter::new(&mut bytes), &sample_frames());
+		bytes.truncate(bytes.len() - 3);
+		let result: Result<Vec<Frame>, RecordingError> =
+			BinaryReader::new(bytes.as_slice()).collect();
+
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn binary_reader_rejects_oversized_name() {
+		let mut bytes = BINARY_MAGIC.to_vec();
+		bytes.push(2);
+		bytes.push(BINARY_NAME);
+		bytes.extend(u32::MAX.to_le_bytes());
+		let mut reader = BinaryReader::new(bytes.as_slice());
+
+		match reader.next() {
+			Some(Err(RecordingError::Format { message, .. })) =>
+				assert!(message.contains("more than the maximum"), "{}", message),
+			other => panic!("Unexpected result: {:?}", other),
+		}
+	}
+
+	#[test]
+	fn binary_reader_reports_truncated_name() {
+		let mut bytes = BINARY_MAGIC.to_vec();
+		bytes.push(2);
+		bytes.push(BINARY_NAME);
+		bytes.extend(8u32.to_le_bytes());
+		bytes.extend(b"Coll");
+		let mut reader = BinaryReader::new(bytes.as_slice());
+
+		match reader.next() {
+			Some(Err(RecordingError::Format { message, .. })) =>
+				assert!(message.contains("ends in the middle of a record"), "{}", message),
+			other => panic!("Unexpected result: {:?}", other),
+		}
+	}
+
+	#[test]
+	fn binary_reader_rejects_non_positive_mass() {
+		let mut bytes = Vec::new();
+		write_all(&mut BinaryWriter::new(&mut bytes), &sample_frames()[..1]);
+		let mass = 2.5f64.to_le_bytes();
+		let start = bytes.windows(8).position(|w| w == mass).expect("Should find the mass.");
+		bytes[start..start + 8].copy_from_slice(&(-2.5f64).to_le_bytes());
+		let result: Result<Vec<Frame>, RecordingError> =
+			BinaryReader::new(bytes.as_slice()).collect();
+
+		assert!(matches!(result, Err(RecordingError::Format { .. })), "{:?}", result);
+	}
+
+	#[test]
+	fn binary_writer_rejects_too_many_field_names() {
+		let names = vec!["tag"; u16::MAX as usize + 1];
+		let frames = [frame(0, vec![particle(Uuid::new_v4(), 1.0, names)])];
+		let mut bytes = Vec::new();
+		let mut writer = BinaryWriter::new(&mut bytes);
+		let error = FrameWriter::write_frame(&mut writer, &frames[0])
+			.expect_err("The frame should not be written.");
+
+		assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+		assert!(bytes.is_empty());
+	}
+
+	/********************* Recorder ********************/
+
+	#[test]
+	fn recorder_records_at_interval() {
+		let path = std::env::temp_dir().join(format!("recording_{}.bin", Uuid::new_v4()));
+		let simulation = simulation::Simulation::new(
+			physical_quantities::Time::new(0.5),
+			None,
+			None,
+		);
+		let id = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(3.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(2.0, 0.0),
+			vec![Box::new(simulation_objects::MarkerField::new(String::from("tag")))],
+		);
+		let recorder_id = simulation.attach_recorder(
+			Recorder::binary_file(&path, physical_quantities::Ticks::new(3))
+				.expect("Should create the file."),
+		);
+		for _ in 0..7 {
+			simulation.step();
+		}
+		simulation.detach_recorder(recorder_id).finish().expect("Should finish.");
+
+		let frames: Vec<Frame> = open(&path)
+			.expect("Should open the recording.")
+			.collect::<Result<_, _>>()
+			.expect("Should read the frames.");
+		std::fs::remove_file(&path).expect("Should delete the recording.");
+
+		let ticks: Vec<u64> = frames.iter().map(|f| f.get_tick().get_number()).collect();
+		assert_eq!(ticks, [3, 6]);
+		assert_eq!(frames[1].get_time(), physical_quantities::Time::new(3.0));
+		let record = &frames[1].get_particles()[0];
+		assert_eq!(record.get_id(), id);
+		assert_eq!(record.get_mass(), physical_quantities::Mass::new(3.0));
+		// The particle moved another tick after the frame was recorded.
+		assert_eq!(record.get_position(), physical_quantities::Displacement::new(5.0, 0.0));
+		assert_eq!(simulation.get_position(id), physical_quantities::Displacement::new(6.0, 0.0));
+		assert_eq!(record.get_velocity(), physical_quantities::Velocity::new(2.0, 0.0));
+		assert_eq!(*record.get_field_names(), [String::from("tag")]);
+	}
+
+	#[test]
+	fn open_detects_csv() {
+		let path = std::env::temp_dir().join(format!("recording_{}.csv", Uuid::new_v4()));
+		let frames = sample_frames();
+		let mut recorder = Recorder::csv_file(&path, physical_quantities::Ticks::new(1))
+			.expect("Should create the file.");
+		for f in &frames {
+			recorder.write(f);
+		}
+		recorder.finish().expect("Should finish.");
+
+		let read: Vec<Frame> = open(&path)
+			.expect("Should open the recording.")
+			.collect::<Result<_, _>>()
+			.expect("Should read the frames.");
+		std::fs::remove_file(&path).expect("Should delete the recording.");
+
+		assert_eq!(read, frames);
+	}
+
+	#[test]
+	fn recorder_records_three_dimensional_simulation() {
+		let path = std::env::temp_dir().join(format!("recording_{}.csv", Uuid::new_v4()));
+		let simulation = simulation::Simulation3::new(
+			physical_quantities::Time::new(1.0),
+			None,
+			None,
+		);
+		simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement3::new(0.0, 0.0, 0.0),
+			physical_quantities::Velocity3::new(0.0, 0.0, 2.0),
+			Vec::new(),
+		);
+		let recorder_id = simulation.attach_recorder(
+			Recorder3::csv_file(&path, physical_quantities::Ticks::new(1))
+				.expect("Should create the file."),
+		);
+		simulation.step();
+		simulation.step();
+		simulation.detach_recorder(recorder_id).finish().expect("Should finish.");
+
+		let frames: Vec<Frame3> = open_n(&path)
+			.expect("Should open the recording.")
+			.collect::<Result<_, _>>()
+			.expect("Should read the frames.");
+		std::fs::remove_file(&path).expect("Should delete the recording.");
+
+		assert_eq!(frames.len(), 2);
+		assert_eq!(
+			frames[1].get_particles()[0].get_velocity(),
+			physical_quantities::Velocity3::new(0.0, 0.0, 2.0),
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "interval must be at least one tick")]
+	fn recorder_panics_on_zero_interval() {
+		Recorder::new(physical_quantities::Ticks::new(0), Box::new(CsvWriter::new(io::sink())));
+	}
+
+	#[test]
+	#[should_panic(expected = "Simulation.detach_recorder(): the provided recorder ID was not found")]
+	fn detach_recorder_panics_on_unknown_id() {
+		let simulation = simulation::Simulation::new(
+			physical_quantities::Time::new(1.0),
+			None,
+			None,
+		);
+		simulation.detach_recorder(Uuid::new_v4());
+	}
+}
+
+
+/// The header row of two-dimensional CSV recordings. Three-dimensional
+/// recordings also have `position_z` and `velocity_z` columns.
+pub const CSV_HEADER: &str =
+	"tick,time,particle_id,mass,position_x,position_y,velocity_x,velocity_y,field_names";
+
+// Identifies binary recordings. The last byte is the format version. Version 1
+//	recordings are always two-dimensional. Later versions follow the magic with
+//	a byte giving the number of dimensions.
+const BINARY_MAGIC: [u8; 8] = *b"PTRJREC\x02";
+// Binary record types.
+const BINARY_NAME: u8 = 1;
+const BINARY_PARTICLE: u8 = 2;
+const BINARY_FRAME: u8 = 3;
+// The longest field name, in bytes, that binary recordings can hold. Limits
+//	how much a corrupt recording can make the reader allocate.
+const MAX_NAME_LENGTH: usize = 64 * 1024;
+// The most particles the binary reader allocates space for before reading
+//	them, for the same reason.
+const MAX_PREALLOCATED_PARTICLES: usize = 1024;
+
+/// Something that went wrong while writing or reading a recording.
+#[derive(Debug)]
+pub enum RecordingError {
+	Io(io::Error),
+	/// The recording is malformed. `line` is set for CSV recordings.
+	Format {
+		line: Option<usize>,
+		message: String,
+	},
+}
+
+impl fmt::Display for RecordingError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			RecordingError::Io(e) => write!(f, "{}", e),
+			RecordingError::Format { line: Some(line), message } =>
+				write!(f, "line {}: {}", line, message),
+			RecordingError::Format { line: None, message } => write!(f, "{}", message),
+		}
+	}
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<io::Error> for RecordingError {
+	fn from(e: io::Error) -> Self {
+		RecordingError::Io(e)
+	}
+}
+
+/// The state of a single particle at the time a frame was recorded. Use
+/// `ParticleRecord` in two dimensions and `ParticleRecord3` in three.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParticleRecordN<V: physical_quantities::Vector> {
+	id: Uuid,
+	mass: physical_quantities::Mass<V::Scalar>,
+	position: physical_quantities::DisplacementN<V>,
+	velocity: physical_quantities::VelocityN<V>,
+	field_names: Vec<String>,
+}
+
+/// The state of a particle in a two-dimensional simulation.
+pub type ParticleRecord = ParticleRecordN<physical_quantities::Vector2>;
+/// The state of a particle in a three-dimensional simulation.
+pub type ParticleRecord3 = ParticleRecordN<physical_quantities::Vector3>;
+
+impl<V: physical_quantities::Vector> ParticleRecordN<V> {
+	pub fn new(
+		id: Uuid,
+		mass: physical_quantities::Mass<V::Scalar>,
+		position: physical_quantities::DisplacementN<V>,
+		velocity: physical_quantities::VelocityN<V>,
+		field_names: Vec<String>,
+	) -> Self {
+		Self {
+			id: id,
+			mass: mass,
+			position: position,
+			velocity: velocity,
+			field_names: field_names,
+		}
+	}
+
+	pub fn get_id(&self) -> Uuid {
+		self.id
+	}
+
+	pub fn get_mass(&self) -> physical_quantities::Mass<V::Scalar> {
+		self.mass
+	}
+
+	pub fn get_position(&self) -> physical_quantities::DisplacementN<V> {
+		self.position
+	}
+
+	pub fn get_velocity(&self) -> physical_quantities::VelocityN<V> {
+		self.velocity
+	}
+
+	/// Gets the names of the particle's fields, in the order in which they are
+	/// attached.
+	pub fn get_field_names(&self) -> &Vec<String> {
+		&self.field_names
+	}
+}
+
+/// The state of every particle in a simulation after a given tick. Use `Frame`
+/// in two dimensions and `Frame3` in three.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameN<V: physical_quantities::Vector> {
+	tick: physical_quantities::Ticks,
+	time: physical_quantities::Time<V::Scalar>,
+	particles: Vec<ParticleRecordN<V>>,
+}
+
+/// A frame recorded from a two-dimensional simulation.
+pub type Frame = FrameN<physical_quantities::Vector2>;
+/// A frame recorded from a three-dimensional simulation.
+pub type Frame3 = FrameN<physical_quantities::Vector3>;
+
+impl<V: physical_quantities::Vector> FrameN<V> {
+	pub fn new(
+		tick: physical_quantities::Ticks,
+		time: physical_quantities::Time<V::Scalar>,
+		particles: Vec<ParticleRecordN<V>>,
+	) -> Self {
+		Self {
+			tick: tick,
+			time: time,
+			particles: particles,
+		}
+	}
+
+	/// Records the current state of every particle in the simulation, sorted
+	/// by particle ID.
+	pub fn capture(simulation: &simulation::SimulationN<V>) -> Self {
+		let particles = simulation.get_particle_ids().into_iter().map(|id| {
+			ParticleRecordN::new(
+				id,
+				simulation.get_mass(id),
+				simulation.get_position(id),
+				simulation.get_velocity(id),
+				simulation.get_field_info(id).iter().map(|i| i.get_name().clone()).collect(),
+			)
+		}).collect();
+
+		Self::new(simulation.get_elapsed_ticks(), simulation.get_elapsed_time(), particles)
+	}
+
+	/// Gets the number of ticks that had elapsed when the frame was recorded.
+	pub fn get_tick(&self) -> physical_quantities::Ticks {
+		self.tick
+	}
+
+	/// Gets the simulated time that had elapsed when the frame was recorded.
+	pub fn get_time(&self) -> physical_quantities::Time<V::Scalar> {
+		self.time
+	}
+
+	pub fn get_particles(&self) -> &Vec<ParticleRecordN<V>> {
+		&self.particles
+	}
+}
+
+/// Writes frames in some format. See `CsvWriter` and `BinaryWriter`. The type
+/// parameter is the vector type of the frames and defaults to `Vector2`.
+pub trait FrameWriter<V: physical_quantities::Vector = physical_quantities::Vector2> {
+	fn write_frame(&mut self, frame: &FrameN<V>) -> io::Result<()>;
+
+	/// Writes anything that's buffered. Called by `Recorder::finish`.
+	fn flush(&mut self) -> io::Result<()>;
+}
+
+// Gets the header row of CSV recordings with the given number of dimensions.
+fn csv_header(dimensions: usize) -> String {
+	let axes = &["x", "y", "z"][..dimensions];
+	["tick", "time", "particle_id", "mass"].into_iter().map(String::from)
+		.chain(axes.iter().map(|axis| format!("position_{}", axis)))
+		.chain(axes.iter().map(|axis| format!("velocity_{}", axis)))
+		.chain([String::from("field_names")])
+		.collect::<Vec<_>>()
+		.join(",")
+}
+
+// Quotes a CSV cell if it needs it.
+fn escape_csv(cell: &str) -> String {
+	if cell.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", cell.replace('"', "\"\""))
+	} else {
+		String::from(cell)
+	}
+}
+
+// Splits a CSV line into cells, handling quoted cells.
+fn split_csv(line: &str) -> Result<Vec<String>, String> {
+	let mut cells = Vec::new();
+	let mut cell = String::new();
+	let mut chars = line.chars().peekable();
+	let mut in_quotes = false;
+
+	while let Some(c) = chars.next() {
+		match (c, in_quotes) {
+			('"', true) if chars.peek() == Some(&'"') => {
+				chars.next();
+				cell.push('"');
+			},
+			('"', true) => in_quotes = false,
+			('"', false) if cell.is_empty() => in_quotes = true,
+			(',', false) => cells.push(std::mem::take(&mut cell)),
+			(c, _) => cell.push(c),
+		}
+	}
+	if in_quotes {
+		return Err(String::from("unterminated quoted cell"));
+	}
+	cells.push(cell);
+
+	Ok(cells)
+}
+
+/// Writes frames as CSV, with one row per particle per frame and a column per
+/// component of each vector. Numbers are written as `f64`. Field names are
+/// separated by semicolons, with any semicolons or backslashes in names
+/// escaped by a backslash. Frames with no particles have no rows, so they are
+/// not read back.
+pub struct CsvWriter<W: Write> {
+	writer: W,
+	wrote_header: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+	pub fn new(writer: W) -> Self {
+		Self {
+			writer: writer,
+			wrote_header: false,
+		}
+	}
+
+	fn write_header(&mut self, dimensions: usize) -> io::Result<()> {
+		if !self.wrote_header {
+			writeln!(self.writer, "{}", csv_header(dimensions))?;
+			self.wrote_header = true;
+		}
+		Ok(())
+	}
+}
+
+impl<V: physical_quantities::Vector, W: Write> FrameWriter<V> for CsvWriter<W> {
+	fn write_frame(&mut self, frame: &FrameN<V>) -> io::Result<()> {
+		self.write_header(V::DIMENSIONS)?;
+
+		for particle in &frame.particles {
+			let field_names = particle.field_names.iter()
+				.map(|name| name.replace('\\', "\\\\").replace(';', "\\;"))
+				.collect::<Vec<_>>()
+				.join(";");
+			let mut cells = vec![
+				frame.tick.get_number().to_string(),
+				frame.time.get_number().to_f64().to_string(),
+				particle.id.to_string(),
+				particle.mass.get_number().to_f64().to_string(),
+			];
+			for vector in [particle.position.get_vector(), particle.velocity.get_vector()] {
+				for i in 0..V::DIMENSIONS {
+					cells.push(vector.get_component(i).to_f64().to_string());
+				}
+			}
+			cells.push(escape_csv(&field_names));
+			writeln!(self.writer, "{}", cells.join(","))?;
+		}
+
+		Ok(())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.write_header(V::DIMENSIONS)?;
+		self.writer.flush()
+	}
+}
+
+/// Reads frames written by `CsvWriter`. Consecutive rows with the same tick
+/// make up a frame. Use `CsvReader` in two dimensions and `CsvReader3` in
+/// three. Reading a recording with a different number of dimensions fails on
+/// its first row.
+pub struct CsvReaderN<R: BufRead, V: physical_quantities::Vector> {
+	lines: io::Lines<R>,
+	line_number: usize,
+	// A row that was read but belongs to the next frame.
+	pending: Option<FrameN<V>>,
+	failed: bool,
+}
+
+/// Reads a two-dimensional CSV recording.
+pub type CsvReader<R> = CsvReaderN<R, physical_quantities::Vector2>;
+/// Reads a three-dimensional CSV recording.
+pub type CsvReader3<R> = CsvReaderN<R, physical_quantities::Vector3>;
+
+impl<R: BufRead, V: physical_quantities::Vector> CsvReaderN<R, V> {
+	pub fn new(reader: R) -> Self {
+		Self {
+			lines: reader.lines(),
+			line_number: 0,
+			pending: None,
+			failed: false,
+		}
+	}
+
+	// Reads the next row as a frame containing a single particle. Returns None
+	//	at the end of the data.
+	fn read_row(&mut self) -> Option<Result<FrameN<V>, RecordingError>> {
+		loop {
+			let line = match self.lines.next()? {
+				Ok(line) => line,
+				Err(e) => return Some(Err(e.into())),
+			};
+			self.line_number += 1;
+			if self.line_number == 1 && line == csv_header(V::DIMENSIONS) || line.is_empty() {
+				continue;
+			}
+			return Some(Self::parse_row(&line).map_err(|message| RecordingError::Format {
+				line: Some(self.line_number),
+				message: message,
+			}));
+		}
+	}
+
+	fn parse_row(line: &str) -> Result<FrameN<V>, String> {
+		let cells = split_csv(line)?;
+		// The tick, time, ID, mass and field names, plus the position and
+		//	velocity components.
+		let cell_count = 5 + 2 * V::DIMENSIONS;
+		if cells.len() != cell_count {
+			return Err(format!("expected {} cells, found {}", cell_count, cells.len()));
+		}
+		let number = |i: usize| cells[i].parse::<f64>()
+			.map(V::Scalar::from_f64)
+			.map_err(|_| format!("expected a number, found \"{}\"", cells[i]));
+		let vector = |start: usize| (start..start + V::DIMENSIONS)
+			.map(number)
+			.collect::<Result<Vec<_>, _>>()
+			.map(|components| V::from_components(&components));
+		let field_names_cell = &cells[cell_count - 1];
+		let mass = number(3)?;
+		if mass.partial_cmp(&V::Scalar::zero()) != Some(cmp::Ordering::Greater) {
+			return Err(format!("mass must be positive, found \"{}\"", cells[3]));
+		}
+
+		let tick = cells[0].parse::<u64>()
+			.map_err(|_| format!("expected a tick, found \"{}\"", cells[0]))?;
+		let id = Uuid::parse_str(&cells[2])
+			.map_err(|_| format!("expected a particle ID, found \"{}\"", cells[2]))?;
+
+		let mut field_names = Vec::new();
+		if !field_names_cell.is_empty() {
+			let mut name = String::new();
+			let mut chars = field_names_cell.chars();
+			while let Some(c) = chars.next() {
+				match c {
+					'\\' => name.extend(chars.next()),
+					';' => field_names.push(std::mem::take(&mut name)),
+					c => name.push(c),
+				}
+			}
+			field_names.push(name);
+		}
+
+		Ok(FrameN::new(
+			physical_quantities::Ticks::new(tick),
+			physical_quantities::Time::new(number(1)?),
+			vec![ParticleRecordN::new(
+				id,
+				physical_quantities::Mass::new(mass),
+				physical_quantities::DisplacementN::from_vector(vector(4)?),
+				physical_quantities::VelocityN::from_vector(vector(4 + V::DIMENSIONS)?),
+				field_names,
+			)],
+		))
+	}
+}
+
+impl<R: BufRead, V: physical_quantities::Vector> Iterator for CsvReaderN<R, V> {
+	type Item = Result<FrameN<V>, RecordingError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.failed {
+			return None;
+		}
+
+		let mut frame = match self.pending.take() {
+			Some(frame) => frame,
+			None => match self.read_row()? {
+				Ok(frame) => frame,
+				Err(e) => {
+					self.failed = true;
+					return Some(Err(e));
+				},
+			},
+		};
+
+		loop {
+			match self.read_row() {
+				None => return Some(Ok(frame)),
+				Some(Err(e)) => {
+					self.failed = true;
+					return Some(Err(e));
+				},
+				Some(Ok(row)) if row.tick == frame.tick => frame.particles.extend(row.particles),
+				Some(Ok(row)) => {
+					self.pending = Some(row);
+					return Some(Ok(frame));
+				},
+			}
+		}
+	}
+}
+
+/// Writes frames in a compact, little-endian binary format suitable for long
+/// runs. Numbers are written as `f64`. Particle IDs and field names are
+/// written once and then referred to by index.
+pub struct BinaryWriter<W: Write> {
+	writer: W,
+	wrote_header: bool,
+	name_indexes: HashMap<String, u32>,
+	particle_indexes: HashMap<Uuid, u32>,
+}
+
+impl<W: Write> BinaryWriter<W> {
+	pub fn new(writer: W) -> Self {
+		Self {
+			writer: writer,
+			wrote_header: false,
+			name_indexes: HashMap::new(),
+			particle_indexes: HashMap::new(),
+		}
+	}
+
+	fn write_header(&mut self, dimensions: usize) -> io::Result<()> {
+		if !self.wrote_header {
+			self.writer.write_all(&BINARY_MAGIC)?;
+			self.writer.write_all(&[dimensions as u8])?;
+			self.wrote_header = true;
+		}
+		Ok(())
+	}
+}
+
+impl<V: physical_quantities::Vector, W: Write> FrameWriter<V> for BinaryWriter<W> {
+	fn write_frame(&mut self, frame: &FrameN<V>) -> io::Result<()> {
+		// Check everything before writing, so an error doesn't leave part of a
+		//	frame in the recording.
+		let invalid_input = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+		let particle_count = u32::try_from(frame.particles.len())
+			.map_err(|_| invalid_input(format!("too many particles: {}", frame.particles.len())))?;
+		for particle in &frame.particles {
+			if u16::try_from(particle.field_names.len()).is_err() {
+				return Err(invalid_input(format!(
+					"particle {} has {} field names, more than the maximum of {}",
+					particle.id,
+					particle.field_names.len(),
+					u16::MAX,
+				)));
+			}
+			if let Some(name) = particle.field_names.iter().find(|n| n.len() > MAX_NAME_LENGTH) {
+				return Err(invalid_input(format!(
+					"a field name is {} bytes long, more than the maximum of {}",
+					name.len(),
+					MAX_NAME_LENGTH,
+				)));
+			}
+		}
+
+		self.write_header(V::DIMENSIONS)?;
+
+		// Define any particles and names that haven't been seen yet.
+		for particle in &frame.particles {
+			if !self.particle_indexes.contains_key(&particle.id) {
+				self.writer.write_all(&[BINARY_PARTICLE])?;
+				self.writer.write_all(particle.id.as_bytes())?;
+				let index = self.particle_indexes.len() as u32;
+				self.particle_indexes.insert(particle.id, index);
+			}
+			for name in &particle.field_names {
+				if !self.name_indexes.contains_key(name) {
+					self.writer.write_all(&[BINARY_NAME])?;
+					self.writer.write_all(&(name.len() as u32).to_le_bytes())?;
+					self.writer.write_all(name.as_bytes())?;
+					let index = self.name_indexes.len() as u32;
+					self.name_indexes.insert(name.clone(), index);
+				}
+			}
+		}
+
+		self.writer.write_all(&[BINARY_FRAME])?;
+		self.writer.write_all(&frame.tick.get_number().to_le_bytes())?;
+		self.writer.write_all(&frame.time.get_number().to_f64().to_le_bytes())?;
+		self.writer.write_all(&particle_count.to_le_bytes())?;
+		for particle in &frame.particles {
+			self.writer.write_all(&self.particle_indexes[&particle.id].to_le_bytes())?;
+			self.writer.write_all(&particle.mass.get_number().to_f64().to_le_bytes())?;
+			for vector in [particle.position.get_vector(), particle.velocity.get_vector()] {
+				for i in 0..V::DIMENSIONS {
+					self.writer.write_all(&vector.get_component(i).to_f64().to_le_bytes())?;
+				}
+			}
+			// Checked above.
+			self.writer.write_all(&(particle.field_names.len() as u16).to_le_bytes())?;
+			for name in &particle.field_names {
+				self.writer.write_all(&self.name_indexes[name].to_le_bytes())?;
+			}
+		}
+
+		Ok(())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		self.write_header(V::DIMENSIONS)?;
+		self.writer.flush()
+	}
+}
+
+/// Reads frames written by `BinaryWriter`. Use `BinaryReader` in two
+/// dimensions and `BinaryReader3` in three. Reading a recording with a
+/// different number of dimensions fails.
+pub struct BinaryReaderN<R: Read, V: physical_quantities::Vector> {
+	reader: R,
+	read_header: bool,
+	names: Vec<String>,
+	particle_ids: Vec<Uuid>,
+	failed: bool,
+	vector_type: marker::PhantomData<V>,
+}
+
+/// Reads a two-dimensional binary recording.
+pub type BinaryReader<R> = BinaryReaderN<R, physical_quantities::Vector2>;
+/// Reads a three-dimensional binary recording.
+pub type BinaryReader3<R> = BinaryReaderN<R, physical_quantities::Vector3>;
+
+impl<R: Read, V: physical_quantities::Vector> BinaryReaderN<R, V> {
+	pub fn new(reader: R) -> Self {
+		Self {
+			reader: reader,
+			read_header: false,
+			names: Vec::new(),
+			particle_ids: Vec::new(),
+			failed: false,
+			vector_type: marker::PhantomData,
+		}
+	}
+
+	fn read_array<const N: usize>(&mut self) -> Result<[u8; N], RecordingError> {
+		let mut bytes = [0; N];
+		self.read_exact(&mut bytes)?;
+		Ok(bytes)
+	}
+
+	fn read_exact(&mut self, bytes: &mut [u8]) -> Result<(), RecordingError> {
+		self.reader.read_exact(bytes).map_err(|e| match e.kind() {
+			io::ErrorKind::UnexpectedEof => RecordingError::Format {
+				line: None,
+				message: String::from("the recording ends in the middle of a record"),
+			},
+			_ => e.into(),
+		})
+	}
+
+	fn read_u32(&mut self) -> Result<u32, RecordingError> {
+		Ok(u32::from_le_bytes(self.read_array()?))
+	}
+
+	fn read_scalar(&mut self) -> Result<V::Scalar, RecordingError> {
+		Ok(V::Scalar::from_f64(f64::from_le_bytes(self.read_array()?)))
+	}
+
+	fn read_vector(&mut self) -> Result<V, RecordingError> {
+		let components = (0..V::DIMENSIONS)
+			.map(|_| self.read_scalar())
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(V::from_components(&components))
+	}
+
+	fn read_index<'a, T>(table: &'a [T], index: u32, what: &str) -> Result<&'a T, RecordingError> {
+		table.get(index as usize).ok_or_else(|| RecordingError::Format {
+			line: None,
+			message: format!("undefined {} index: {}", what, index),
+		})
+	}
+
+	// Reads records until a frame is complete. Returns None at the end of the
+	//	data.
+	fn read_frame(&mut self) -> Result<Option<FrameN<V>>, RecordingError> {
+		if !self.read_header {
+			let magic = self.read_array::<8>()?;
+			let dimensions = match (magic[..7] == BINARY_MAGIC[..7], magic[7]) {
+				(true, 1) => 2,
+				(true, 2) => self.read_array::<1>()?[0] as usize,
+				_ => return Err(RecordingError::Format {
+					line: None,
+					message: String::from("not a binary recording, or an unsupported version"),
+				}),
+			};
+			if dimensions != V::DIMENSIONS {
+				return Err(RecordingError::Format {
+					line: None,
+					message: format!(
+						"the recording has {} dimensions, not {}",
+						dimensions,
+						V::DIMENSIONS,
+					),
+				});
+			}
+			self.read_header = true;
+		}
+
+		loop {
+			let mut record_type = [0];
+			if self.reader.read(&mut record_type)? == 0 {
+				return Ok(None);
+			}
+
+			match record_type[0] {
+				BINARY_NAME => {
+					let length = self.read_u32()? as usize;
+					if length > MAX_NAME_LENGTH {
+						return Err(RecordingError::Format {
+							line: None,
+							message: format!(
+								"a field name is {} bytes long, more than the maximum of {}",
+								length,
+								MAX_NAME_LENGTH,
+							),
+						});
+					}
+					let mut bytes = vec![0; length];
+					self.read_exact(&mut bytes)?;
+					let name = String::from_utf8(bytes).map_err(|_| RecordingError::Format {
+						line: None,
+						message: String::from("a field name is not valid UTF-8"),
+					})?;
+					self.names.push(name);
+				},
+				BINARY_PARTICLE => {
+					let id = Uuid::from_bytes(self.read_array()?);
+					self.particle_ids.push(id);
+				},
+				BINARY_FRAME => {
+					let tick = u64::from_le_bytes(self.read_array()?);
+					let time = self.read_scalar()?;
+					let count = self.read_u32()?;
+					let mut particles =
+						Vec::with_capacity((count as usize).min(MAX_PREALLOCATED_PARTICLES));
+					for _ in 0..count {
+						let index = self.read_u32()?;
+						let id = *Self::read_index(&self.particle_ids, index, "particle")?;
+						let mass = self.read_scalar()?;
+						if mass.partial_cmp(&V::Scalar::zero()) != Some(cmp::Ordering::Greater) {
+							return Err(RecordingError::Format {
+								line: None,
+								message: format!("mass must be positive, found {:?}", mass),
+							});
+						}
+						let position = physical_quantities::DisplacementN::from_vector(self.read_vector()?);
+						let velocity = physical_quantities::VelocityN::from_vector(self.read_vector()?);
+						let name_count = u16::from_le_bytes(self.read_array()?);
+						let mut field_names = Vec::with_capacity(name_count as usize);
+						for _ in 0..name_count {
+							let index = self.read_u32()?;
+							field_names.push(Self::read_index(&self.names, index, "name")?.clone());
+						}
+						particles.push(ParticleRecordN::new(
+							id,
+							physical_quantities::Mass::new(mass),
+							position,
+							velocity,
+							field_names,
+						));
+					}
+					return Ok(Some(FrameN::new(
+						physical_quantities::Ticks::new(tick),
+						physical_quantities::Time::new(time),
+						particles,
+					)));
+				},
+				other => return Err(RecordingError::Format {
+					line: None,
+					message: format!("unknown record type: {}", other),
+				}),
+			}
+		}
+	}
+}
+
+impl<R: Read, V: physical_quantities::Vector> Iterator for BinaryReaderN<R, V> {
+	type Item = Result<FrameN<V>, RecordingError>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.failed {
+			return None;
+		}
+
+		let result = self.read_frame();
+		self.failed = result.is_err();
+		result.transpose()
+	}
+}
+
+/// Opens a two-dimensional recording written by `CsvWriter` or `BinaryWriter`,
+/// detecting the format from its contents, and returns an iterator over its
+/// frames. See `open_n` for other vector types.
+pub fn open<P: AsRef<Path>>(
+	path: P,
+) -> Result<Box<dyn Iterator<Item = Result<Frame, RecordingError>>>, RecordingError> {
+	open_n(path)
+}
+
+/// An iterator over the frames of a recording, as returned by `open_n`.
+pub type FrameIterator<V> = Box<dyn Iterator<Item = Result<FrameN<V>, RecordingError>>>;
+
+/// Opens a recording like `open`, with frames of any vector type, e.g.,
+/// `open_n::<Vector3, _>(path)` for a three-dimensional recording.
+pub fn open_n<V: physical_quantities::Vector, P: AsRef<Path>>(
+	path: P,
+) -> Result<FrameIterator<V>, RecordingError> {
+	let mut reader = BufReader::new(File::open(path)?);
+	let is_binary = reader.fill_buf()?.starts_with(&BINARY_MAGIC[..7]);
+
+	if is_binary {
+		Ok(Box::new(BinaryReaderN::new(reader)))
+	} else {
+		Ok(Box::new(CsvReaderN::new(reader)))
+	}
+}
+
+/// Records frames from a simulation every `interval` ticks. Attach it with
+/// `Simulation::attach_recorder`. A frame is recorded at the end of each tick
+/// after which the number of elapsed ticks is a multiple of the interval. Use
+/// `Recorder` in two dimensions and `Recorder3` in three.
+pub struct RecorderN<V: physical_quantities::Vector> {
+	interval: physical_quantities::Ticks,
+	writer: Box<dyn FrameWriter<V>>,
+	// The first error encountered while writing. Nothing more is written once
+	//	an error occurs.
+	error: Option<io::Error>,
+}
+
+/// Records frames from a two-dimensional simulation.
+pub type Recorder = RecorderN<physical_quantities::Vector2>;
+/// Records frames from a three-dimensional simulation.
+pub type Recorder3 = RecorderN<physical_quantities::Vector3>;
+
+impl<V: physical_quantities::Vector> RecorderN<V> {
+	/// Creates an instance of `Recorder`.
+	///
+	/// # Arguments
+	/// * `interval` - The number of ticks between frames.
+	/// * `writer` - Writes the frames in some format.
+	///
+	/// # Panics
+	/// Panics if `interval` is zero.
+	pub fn new(interval: physical_quantities::Ticks, writer: Box<dyn FrameWriter<V>>) -> Self {
+		if interval.get_number() == 0 {
+			panic!("interval must be at least one tick");
+		}
+
+		Self {
+			interval: interval,
+			writer: writer,
+			error: None,
+		}
+	}
+
+	pub fn get_interval(&self) -> physical_quantities::Ticks {
+		self.interval
+	}
+
+	/// Writes a frame, regardless of the interval. Useful for recording the
+	/// initial state of a simulation.
+	pub fn write(&mut self, frame: &FrameN<V>) {
+		if self.error.is_none()
+			&& let Err(e) = self.writer.write_frame(frame) {
+			self.error = Some(e);
+		}
+	}
+
+	// Called by the simulation at the end of each tick.
+	pub(crate) fn on_tick(&mut self, simulation: &simulation::SimulationN<V>) {
+		let tick = simulation.get_elapsed_ticks().get_number();
+		if tick.is_multiple_of(self.interval.get_number()) {
+			self.write(&FrameN::capture(simulation));
+		}
+	}
+
+	/// Flushes the writer. Returns the first error encountered while
+	/// recording, if any.
+	pub fn finish(mut self) -> Result<(), RecordingError> {
+		if let Some(e) = self.error.take() {
+			return Err(e.into());
+		}
+		Ok(self.writer.flush()?)
+	}
+}
+
+impl<V: physical_quantities::Vector> RecorderN<V> {
+	/// Creates a `Recorder` that writes CSV to a new file at `path`.
+	pub fn csv_file<P: AsRef<Path>>(
+		path: P,
+		interval: physical_quantities::Ticks,
+	) -> io::Result<Self> {
+		let file = BufWriter::new(File::create(path)?);
+		Ok(Self::new(interval, Box::new(CsvWriter::new(file))))
+	}
+
+	/// Creates a `Recorder` that writes the binary format to a new file at
+	/// `path`.
+	pub fn binary_file<P: AsRef<Path>>(
+		path: P,
+		interval: physical_quantities::Ticks,
+	) -> io::Result<Self> {
+		let file = BufWriter::new(File::create(path)?);
+		Ok(Self::new(interval, Box::new(BinaryWriter::new(file))))
+	}
+}
@@ -0,0 +1,1338 @@
+use crate::{dimensions, persistence, physical_quantities, simulation, simulation_objects, units, utilities};
+use serde::Deserialize;
+use serde::de::{self, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing;
+	use serde::Serialize;
+	use std::collections::HashMap;
+	use uuid::Uuid;
+
+	// A user-defined field, constructed from parameters in a scene file.
+	#[derive(Serialize, Deserialize)]
+	struct SpinField {
+		strength: f64,
+		name: String,
+	}
+
+	impl simulation_objects::Field for SpinField {
+		fn effect(
+			&self,
+			_simulation: &simulation::Simulation,
+			_position: physical_quantities::Displacement,
+			_triggered_by: HashMap<Uuid, Vec<Option<simulation_objects::FieldInfo>>>,
+			_field_owner_id: Uuid,
+		) {
+		}
+
+		fn get_radius(&self) -> f64 {
+			self.strength
+		}
+
+		fn affects_self(&self) -> bool {
+			false
+		}
+
+		fn affects_others(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_fields(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_particles(&self) -> bool {
+			false
+		}
+
+		fn get_name(&self) -> &String {
+			&self.name
+		}
+	}
+
+	fn load(text: &str) -> Result<simulation::Simulation, SceneError> {
+		let mut registry = persistence::Registry::new();
+		registry.register_field::<SpinField>("SpinField");
+		let simulation = load_str(text, &registry, None)?;
+		// Add the particles.
+		simulation.step();
+		Ok(simulation)
+	}
+
+	fn expect_error(text: &str) -> SceneError {
+		match load(text) {
+			Ok(_) => panic!("The scene should not have loaded."),
+			Err(e) => e,
+		}
+	}
+
+	/********************* Loading ********************/
+
+	#[test]
+	fn load_str_creates_simulation_with_units() {
+		let simulation = load(r#"
+			Scene(
+				tick_duration: "10 ms",
+				simulation_speed: 2.0,
+				particles: [
+					(
+						mass: "500 g",
+						position: ("1 km", -3.0),
+						velocity: ("36 km/h", "0 m/s"),
+						fields: [
+							SimpleSelfGravity(acceleration: (0.0, "-9.81 m/s^2")),
+							Collider(radius: "50 cm", coefficient_of_restitution: 0.5, layers: [1, 2]),
+							UniversalGravitation(
+								radius: "1 AU",
+								softening_length: "1 m",
+								attracts_layers: [1],
+								name: "gravity",
+							),
+							Custom(type_tag: "SpinField", params: (strength: 3.0, name: "spin")),
+						],
+					),
+				],
+			)
+		"#).expect("The scene should load.");
+
+		assert_eq!(simulation.get_tick_duration(), physical_quantities::Time::new(0.01));
+		let ids = simulation.get_particle_ids();
+		assert_eq!(ids.len(), 1);
+		assert_eq!(simulation.get_mass(ids[0]), physical_quantities::Mass::new(0.5));
+		assert_eq!(
+			simulation.get_position(ids[0]),
+			physical_quantities::Displacement::new(1000.0, -3.0),
+		);
+
+		let info = simulation.get_field_info(ids[0]);
+		let names: Vec<&String> = info.iter().map(|i| i.get_name()).collect();
+		assert_eq!(names, ["SimpleSelfGravityField", "Collider", "gravity", "spin"]);
+		assert_eq!(info[1].get_radius(), 0.5);
+		assert_eq!(
+			info[1].get_layers(),
+			simulation_objects::Layers::layer(1) | simulation_objects::Layers::layer(2),
+		);
+		assert_eq!(info[2].get_radius(), 1.495978707e11);
+		assert_eq!(info[3].get_radius(), 3.0);
+	}
+
+	#[test]
+	fn load_str_sets_initial_velocity() {
+		let simulation = load(r#"
+			(
+				tick_duration: "1 s",
+				particles: [(mass: 1.0, position: (0.0, 0.0), velocity: ("36 km/h", 0.0))],
+			)
+		"#).expect("The scene should load.");
+		let id = simulation.get_particle_ids()[0];
+
+		assert_eq!(
+			simulation.get_velocity(id),
+			physical_quantities::Velocity::new(10.0, 0.0),
+		);
+	}
+
+	#[test]
+	fn grid_generator_creates_rows_and_columns() {
+		let simulation = load(r#"
+			(
+				tick_duration: "1 s",
+				generators: [
+					Grid(
+						origin: (10.0, 20.0),
+						rows: 3,
+						columns: 4,
+						spacing: ("1 m", "-2 m"),
+						mass: "1 kg",
+						fields: [Marker(name: "grid")],
+					),
+				],
+			)
+		"#).expect("The scene should load.");
+
+		let ids = simulation.get_particle_ids();
+		assert_eq!(ids.len(), 12);
+		let mut positions: Vec<(f64, f64)> = ids.iter()
+			.map(|id| simulation.get_position(*id))
+			.map(|p| (p.x(), p.y()))
+			.collect();
+		positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+		assert_eq!(positions[0], (10.0, 16.0));
+		assert_eq!(positions[11], (13.0, 20.0));
+		for id in ids {
+			assert_eq!(simulation.get_field_info(id)[0].get_name(), "grid");
+		}
+	}
+
+	#[test]
+	fn ring_generator_creates_particles_on_circle() {
+		let simulation = load(r#"
+			(
+				tick_duration: "1 s",
+				generators: [
+					Ring(
+						center: ("1 m", "1 m"),
+						radius: "2 m",
+						count: 8,
+						mass: "2 kg",
+						tangential_speed: "3 m/s",
+					),
+				],
+			)
+		"#).expect("The scene should load.");
+
+		let ids = simulation.get_particle_ids();
+		assert_eq!(ids.len(), 8);
+		for id in ids {
+			let position = simulation.get_position(id);
+			let velocity = simulation.get_velocity(id);
+			let (dx, dy) = (position.x() - 1.0, position.y() - 1.0);
+			assert!(((dx * dx + dy * dy).sqrt() - 2.0).abs() < 1.0e-12);
+			assert!((velocity.get_magnitude() - 3.0).abs() < 1.0e-12);
+			// Counterclockwise and perpendicular to the radius.
+			assert!((dx * velocity.x() + dy * velocity.y()).abs() < 1.0e-12);
+			assert!(dx * velocity.y() - dy * velocity.x() > 0.0);
+		}
+	}
+
+	#[test]
+	fn load_str_creates_world_fields() {
+		let simulation = load(r#"
+			(
+				tick_duration: "1 s",
+				world_fields: [
+					(
+						region: Rectangle(min: (-1.0, -1.0), max: ("1 m", "1 m")),
+						field: UniformForce(force: ("2 N", 0.0)),
+					),
+				],
+				particles: [(mass: "2 kg", position: (0.0, 0.0))],
+			)
+		"#).expect("The scene should load.");
+		let id = simulation.get_particle_ids()[0];
+		simulation.step();
+
+		assert_eq!(
+			simulation.get_velocity(id),
+			physical_quantities::Velocity::new(1.0, 0.0),
+		);
+	}
+
+	#[test]
+	fn load_str_reads_gravitational_constant_with_units() {
+		let simulation = load(r#"
+			(
+				tick_duration: "1 s",
+				particles: [
+					(
+						mass: "1 kg",
+						position: (0.0, 0.0),
+						fields: [
+							UniversalGravitation(
+								radius: "10 m",
+								gravitational_constant: "2e-9 km³/(kg s²)",
+							),
+						],
+					),
+					(mass: "1 kg", position: ("1 m", 0.0)),
+				],
+			)
+		"#).expect("The scene should load.");
+		let other_id = simulation.get_particle_ids().into_iter()
+			.find(|&id| simulation.get_field_info(id).is_empty())
+			.expect("One particle should have no fields.");
+		simulation.step();
+
+		crate::assert_approx_eq!(
+			simulation.get_velocity(other_id),
+			physical_quantities::Velocity::new(-2.0, 0.0),
+			testing::Tolerance::Absolute(1e-12),
+		);
+	}
+
+	#[test]
+	fn load_str_converts_values_to_unit_system() {
+		let simulation = load(r#"
+			(
+				unit_system: Astronomical,
+				tick_duration: "1 d",
+				particles: [
+					(
+						mass: 1.0,
+						position: ("1 AU", 0.0),
+						velocity: (0.0, 6.0),
+						fields: [UniversalGravitation(radius: "2 AU")],
+					),
+				],
+			)
+		"#).expect("The scene should load.");
+		let id = simulation.get_particle_ids()[0];
+
+		assert_eq!(simulation.get_unit_system(), units::UnitSystem::ASTRONOMICAL);
+		crate::assert_approx_eq!(
+			simulation.get_tick_duration(),
+			physical_quantities::Time::new(86400.0 / 3.15576e7),
+			testing::Tolerance::Relative(1e-12),
+		);
+		assert_eq!(simulation.get_mass(id), physical_quantities::Mass::new(1.0));
+		crate::assert_approx_eq!(
+			simulation.get_position(id),
+			physical_quantities::Displacement::new(1.0, 0.0),
+			testing::Tolerance::Absolute(1e-12),
+		);
+		assert_eq!(simulation.get_velocity(id), physical_quantities::Velocity::new(0.0, 6.0));
+		crate::assert_approx_eq!(
+			simulation.get_field_info(id)[0].get_radius(),
+			2.0,
+			testing::Tolerance::Absolute(1e-12),
+		);
+	}
+
+	#[test]
+	fn load_str_uses_custom_unit_system() {
+		let simulation = load(r#"
+			(
+				unit_system: Custom(length: 1000.0, mass: 1.0, time: 3600.0),
+				tick_duration: 1.0,
+				particles: [(mass: "1 kg", position: ("2 km", "500 m"), velocity: ("36 km/h", 0.0))],
+			)
+		"#).expect("The scene should load.");
+		let id = simulation.get_particle_ids()[0];
+
+		assert_eq!(simulation.get_unit_system(), units::UnitSystem::new(1000.0, 1.0, 3600.0));
+		assert_eq!(simulation.get_tick_duration(), physical_quantities::Time::new(1.0));
+		assert_eq!(simulation.get_position(id), physical_quantities::Displacement::new(2.0, 0.5));
+		crate::assert_approx_eq!(
+			simulation.get_velocity(id),
+			physical_quantities::Velocity::new(36.0, 0.0),
+			testing::Tolerance::Relative(1e-12),
+		);
+	}
+
+	/********************* Errors ********************/
+
+	#[test]
+	fn non_positive_custom_unit_is_an_error() {
+		let error = expect_error(
+"(
+	unit_system: Custom(length: 0.0, mass: 1.0, time: 1.0),
+	tick_duration: 1.0,
+)");
+
+		assert_eq!(error.get_line(), Some(2));
+		assert!(error.get_message().contains("units must be positive"), "{}", error);
+	}
+
+	#[test]
+	fn wrong_dimension_reports_location() {
+		let error = expect_error(
+"(
+	tick_duration: \"1 s\",
+	particles: [
+		(mass: \"5 m\", position: (0.0, 0.0)),
+	],
+)");
+
+		assert_eq!(error.get_line(), Some(4));
+		assert!(error.get_column().is_some());
+		assert!(
+			error.get_message().contains("\"5 m\" is a length, but a mass is expected"),
+			"{}",
+			error,
+		);
+	}
+
+	#[test]
+	fn unknown_unit_reports_location() {
+		let error = expect_error(
+"(
+	tick_duration: \"1 fortnight\",
+)");
+
+		assert_eq!(error.get_line(), Some(2));
+		assert!(error.get_message().contains("unknown unit \"fortnight\""), "{}", error);
+	}
+
+	#[test]
+	fn non_positive_mass_is_an_error() {
+		let error = expect_error(
+"(tick_duration: \"1 s\", particles: [(mass: 0.0, position: (0.0, 0.0))])");
+
+		assert_eq!(error.get_line(), Some(1));
+		assert!(error.get_message().contains("mass must be positive"), "{}", error);
+	}
+
+	#[test]
+	fn syntax_error_reports_location() {
+		let error = expect_error(
+"(
+	tick_duration: \"1 s\",
+	particles: [(mass: 1.0 position: (0.0, 0.0))],
+)");
+
+		assert_eq!(error.get_line(), Some(3));
+		assert!(error.get_column().is_some());
+	}
+
+	#[test]
+	fn unregistered_custom_field_is_an_error() {
+		let error = expect_error(
+"(
+	tick_duration: \"1 s\",
+	particles: [(mass: 1.0, position: (0.0, 0.0), fields: [Custom(type_tag: \"Nope\")])],
+)");
+
+		assert_eq!(error.get_line(), Some(3));
+		assert!(error.get_column().is_some());
+		assert!(error.get_message().contains("particle 0, field 0"), "{}", error);
+		assert!(error.get_message().contains("Nope"), "{}", error);
+	}
+
+	#[test]
+	fn bad_layer_index_reports_location() {
+		let error = expect_error(
+"(
+	tick_duration: \"1 s\",
+	particles: [
+		(mass: 1.0, position: (0.0, 0.0)),
+		(
+			mass: 1.0,
+			position: (1.0, 0.0),
+			fields: [
+				Collider(radius: 1.0, coefficient_of_restitution: 1.0, layers: [0]),
+				Collider(radius: 1.0, coefficient_of_restitution: 1.0, layers: [32]),
+			],
+		),
+	],
+)");
+
+		assert_eq!(error.get_line(), Some(10));
+		assert_eq!(error.get_column(), Some(5));
+		assert!(error.get_message().contains("particle 1, field 1"), "{}", error);
+		assert!(error.get_message().contains("layer index must be less than 32"), "{}", error);
+	}
+
+	#[test]
+	fn negative_softening_length_reports_location() {
+		let error = expect_error(
+"(
+	tick_duration: \"1 s\",
+	generators: [
+		Ring(
+			count: 3,
+			center: (0.0, 0.0),
+			radius: 1.0,
+			mass: 1.0,
+			fields: [UniversalGravitation(radius: 1.0, softening_length: \"-1 m\")],
+		),
+	],
+)");
+
+		assert_eq!(error.get_line(), Some(9));
+		assert!(error.get_message().contains("generator 0, field 0"), "{}", error);
+		assert!(error.get_message().contains("softening_length must not be negative"), "{}", error);
+	}
+
+	#[test]
+	fn bad_world_field_reports_location() {
+		let error = expect_error(
+"(
+	tick_duration: \"1 s\",
+	world_fields: [
+		(
+			region: Everywhere,
+			field: Custom(type_tag: \"Nope\"),
+		),
+	],
+)");
+
+		assert_eq!(error.get_line(), Some(6));
+		assert!(error.get_message().contains("world field 0"), "{}", error);
+	}
+
+	#[test]
+	fn load_file_reports_file_name() {
+		let path = std::env::temp_dir().join(format!("scene_{}.ron", Uuid::new_v4()));
+		std::fs::write(&path, "(tick_duration: \"1 kg\")").expect("Should write the scene.");
+		let error = match load_file(&path, &persistence::Registry::new(), None) {
+			Ok(_) => panic!("The scene should not have loaded."),
+			Err(e) => e,
+		};
+		std::fs::remove_file(&path).expect("Should delete the scene.");
+
+		assert_eq!(error.get_file(), Some(path.as_path()));
+		assert!(error.to_string().starts_with(&format!("{}:1:", path.display())), "{}", error);
+	}
+}
+
+
+/// Something that went wrong while loading a scene.
+#[derive(Debug)]
+pub struct SceneError {
+	file: Option<PathBuf>,
+	line: Option<usize>,
+	column: Option<usize>,
+	message: String,
+}
+
+impl SceneError {
+	fn new(message: String) -> Self {
+		Self {
+			file: None,
+			line: None,
+			column: None,
+			message: message,
+		}
+	}
+
+	// Sets where in the scene's text the error occurred.
+	fn at(mut self, location: Option<(usize, usize)>) -> Self {
+		if let Some((line, column)) = location {
+			self.line = Some(line);
+			self.column = Some(column);
+		}
+		self
+	}
+
+	/// Gets the path of the scene file, if the scene was loaded from a file.
+	pub fn get_file(&self) -> Option<&Path> {
+		self.file.as_deref()
+	}
+
+	/// Gets the line (starting at 1) at which the error occurred, if known.
+	pub fn get_line(&self) -> Option<usize> {
+		self.line
+	}
+
+	/// Gets the column (starting at 1) at which the error occurred, if known.
+	pub fn get_column(&self) -> Option<usize> {
+		self.column
+	}
+
+	pub fn get_message(&self) -> &String {
+		&self.message
+	}
+}
+
+impl fmt::Display for SceneError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(file) = &self.file {
+			write!(f, "{}:", file.display())?;
+		}
+		if let Some(line) = self.line {
+			write!(f, "{}:", line)?;
+			if let Some(column) = self.column {
+				write!(f, "{}:", column)?;
+			}
+		}
+		if self.file.is_some() || self.line.is_some() {
+			write!(f, " ")?;
+		}
+		write!(f, "{}", self.message)
+	}
+}
+
+impl std::error::Error for SceneError {}
+
+impl From<ron::error::SpannedError> for SceneError {
+	fn from(e: ron::error::SpannedError) -> Self {
+		Self {
+			file: None,
+			line: Some(e.span.start.line),
+			column: Some(e.span.start.col),
+			message: e.code.to_string(),
+		}
+	}
+}
+
+// A physical dimension that scene files can give values for. Values may be
+//	written as plain numbers, in the scene's unit system, or as strings with a
+//	unit, such as "5 km". See the `units` module for the units that can be
+//	used.
+trait Dimension {
+	type Dimension: dimensions::Dimension;
+	const NAME: &'static str;
+
+	fn validate(_value: f64) -> Result<(), String> {
+		Ok(())
+	}
+}
+
+struct Length;
+struct MassDimension;
+struct TimeDimension;
+struct Speed;
+struct AccelerationDimension;
+struct ForceDimension;
+struct GravitationalConstantDimension;
+
+impl Dimension for Length {
+	type Dimension = dimensions::Length;
+	const NAME: &'static str = "length";
+}
+
+impl Dimension for MassDimension {
+	type Dimension = dimensions::Mass;
+	const NAME: &'static str = "mass";
+
+	fn validate(value: f64) -> Result<(), String> {
+		if value > 0.0 {
+			Ok(())
+		} else {
+			Err(format!("mass must be positive, found {}", value))
+		}
+	}
+}
+
+impl Dimension for TimeDimension {
+	type Dimension = dimensions::Time;
+	const NAME: &'static str = "time";
+
+	// Times in scene files are tick durations.
+	fn validate(value: f64) -> Result<(), String> {
+		if value > 0.0 {
+			Ok(())
+		} else {
+			Err(format!("tick duration must be positive, found {}", value))
+		}
+	}
+}
+
+impl Dimension for Speed {
+	type Dimension = dimensions::Velocity;
+	const NAME: &'static str = "speed";
+}
+
+impl Dimension for AccelerationDimension {
+	type Dimension = dimensions::Acceleration;
+	const NAME: &'static str = "acceleration";
+}
+
+impl Dimension for ForceDimension {
+	type Dimension = dimensions::Force;
+	const NAME: &'static str = "force";
+}
+
+impl Dimension for GravitationalConstantDimension {
+	type Dimension = dimensions::GravitationalConstant;
+	const NAME: &'static str = "gravitational constant";
+}
+
+// A value of some dimension. The scene's unit system isn't known while it's
+//	being read, so values with a unit are kept in SI units until the scene is
+//	built.
+struct Measure<D> {
+	value: f64,
+	has_unit: bool,
+	dimension: PhantomData<D>,
+}
+
+impl<D: Dimension> Measure<D> {
+	// Gets the value in `unit_system`'s units. As with `UnitSystem::parse`, a
+	//	plain number is already in those units.
+	fn get_number(&self, unit_system: &units::UnitSystem) -> f64 {
+		if self.has_unit {
+			unit_system.from_si::<D::Dimension>(self.value)
+		} else {
+			self.value
+		}
+	}
+}
+
+impl<'de, D: Dimension> Deserialize<'de> for Measure<D> {
+	fn deserialize<De: Deserializer<'de>>(deserializer: De) -> Result<Self, De::Error> {
+		struct MeasureVisitor<D>(PhantomData<D>);
+
+		// Reads the value and whether it has a unit.
+		impl<'de, D: Dimension> Visitor<'de> for MeasureVisitor<D> {
+			type Value = (f64, bool);
+
+			fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+				let symbol = units::get_symbol::<D::Dimension>();
+				write!(f, "a {} as a number or a string such as \"1 {}\"", D::NAME, symbol)
+			}
+
+			fn visit_f64<E: de::Error>(self, value: f64) -> Result<(f64, bool), E> {
+				Ok((value, false))
+			}
+
+			fn visit_i64<E: de::Error>(self, value: i64) -> Result<(f64, bool), E> {
+				Ok((value as f64, false))
+			}
+
+			fn visit_u64<E: de::Error>(self, value: u64) -> Result<(f64, bool), E> {
+				Ok((value as f64, false))
+			}
+
+			fn visit_str<E: de::Error>(self, value: &str) -> Result<(f64, bool), E> {
+				if let Ok(number) = value.trim().parse::<f64>() {
+					return Ok((number, false));
+				}
+				units::parse::<D::Dimension>(value).map(|number| (number, true)).map_err(E::custom)
+			}
+		}
+
+		let (value, has_unit) = deserializer.deserialize_any(MeasureVisitor::<D>(PhantomData))?;
+		// Unit systems only scale values by positive factors, so this holds in
+		//	any of them.
+		D::validate(value).map_err(de::Error::custom)?;
+
+		Ok(Measure {
+			value: value,
+			has_unit: has_unit,
+			dimension: PhantomData,
+		})
+	}
+}
+
+// An (x, y) pair of values of some dimension.
+#[derive(Deserialize)]
+#[serde(bound = "D: Dimension")]
+struct Vector<D>(Measure<D>, Measure<D>);
+
+impl<D: Dimension> Vector<D> {
+	fn x(&self, unit_system: &units::UnitSystem) -> f64 {
+		self.0.get_number(unit_system)
+	}
+
+	fn y(&self, unit_system: &units::UnitSystem) -> f64 {
+		self.1.get_number(unit_system)
+	}
+}
+
+// A simulation speed, which must be positive.
+#[derive(Deserialize)]
+#[serde(try_from = "f64")]
+struct SimulationSpeed(f64);
+
+impl TryFrom<f64> for SimulationSpeed {
+	type Error = String;
+
+	fn try_from(value: f64) -> Result<Self, String> {
+		if value > 0.0 {
+			Ok(SimulationSpeed(value))
+		} else {
+			Err(format!("simulation speed must be positive, found {}", value))
+		}
+	}
+}
+
+// The units that plain numbers in a scene are in. Custom units are given in
+//	meters, kilograms and seconds, as with `UnitSystem::new`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+enum UnitSystemSpec {
+	SI,
+	Astronomical,
+	Molecular,
+	Custom {
+		length: f64,
+		mass: f64,
+		time: f64,
+	},
+}
+
+#[derive(Deserialize)]
+#[serde(try_from = "UnitSystemSpec")]
+struct SceneUnitSystem(units::UnitSystem);
+
+impl TryFrom<UnitSystemSpec> for SceneUnitSystem {
+	type Error = String;
+
+	fn try_from(spec: UnitSystemSpec) -> Result<Self, String> {
+		Ok(SceneUnitSystem(match spec {
+			UnitSystemSpec::SI => units::UnitSystem::SI,
+			UnitSystemSpec::Astronomical => units::UnitSystem::ASTRONOMICAL,
+			UnitSystemSpec::Molecular => units::UnitSystem::MOLECULAR,
+			UnitSystemSpec::Custom { length, mass, time } => {
+				if !(length > 0.0 && mass > 0.0 && time > 0.0) {
+					return Err(format!(
+						"units must be positive, found length {}, mass {} and time {}",
+						length,
+						mass,
+						time,
+					));
+				}
+				units::UnitSystem::new(length, mass, time)
+			},
+		}))
+	}
+}
+
+impl Default for SceneUnitSystem {
+	fn default() -> Self {
+		SceneUnitSystem(units::UnitSystem::SI)
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+enum FieldSpec {
+	SimpleSelfGravity {
+		acceleration: Vector<AccelerationDimension>,
+		#[serde(default)]
+		name: Option<String>,
+	},
+	UniversalGravitation {
+		radius: Measure<Length>,
+		#[serde(default)]
+		gravitational_constant: Option<Measure<GravitationalConstantDimension>>,
+		#[serde(default)]
+		softening_length: Option<Measure<Length>>,
+		#[serde(default)]
+		attracts_only: Option<String>,
+		#[serde(default)]
+		attracts_layers: Option<Vec<u32>>,
+		#[serde(default)]
+		name: Option<String>,
+	},
+	Collider {
+		radius: Measure<Length>,
+		coefficient_of_restitution: f64,
+		#[serde(default)]
+		layers: Option<Vec<u32>>,
+		#[serde(default)]
+		name: Option<String>,
+	},
+	UniformForce {
+		force: Vector<ForceDimension>,
+		#[serde(default)]
+		name: Option<String>,
+	},
+	LinearDrag {
+		drag_coefficient: f64,
+		#[serde(default)]
+		name: Option<String>,
+	},
+	Marker {
+		name: String,
+	},
+	/// A field type registered with the `persistence::Registry` passed to the
+	/// loader. `params` are passed to the registered loader.
+	Custom {
+		type_tag: String,
+		#[serde(default)]
+		params: serde_json::Value,
+	},
+}
+
+impl FieldSpec {
+	fn build(
+		&self,
+		registry: &persistence::Registry,
+		unit_system: &units::UnitSystem,
+	) -> Result<Box<dyn simulation_objects::Field>, String> {
+		let field: Box<dyn simulation_objects::Field> = match self {
+			FieldSpec::SimpleSelfGravity { acceleration, name } =>
+				Box::new(simulation_objects::SimpleSelfGravityField::new(
+					physical_quantities::Acceleration::new(
+						acceleration.x(unit_system),
+						acceleration.y(unit_system),
+					),
+					name.clone(),
+				)),
+			FieldSpec::UniversalGravitation {
+				radius,
+				gravitational_constant,
+				softening_length,
+				attracts_only,
+				attracts_layers,
+				name,
+			} => {
+				let softening_length = softening_length.as_ref().map(|l| l.get_number(unit_system));
+				if softening_length.is_some_and(|l| l < 0.0) {
+					return Err(String::from("softening_length must not be negative"));
+				}
+				let attracts_layers = match attracts_layers {
+					Some(indices) => Some(build_layers(indices)?),
+					None => None,
+				};
+				Box::new(simulation_objects::UniversalGravitationField::new(
+					radius.get_number(unit_system),
+					gravitational_constant.as_ref().map(|g| g.get_number(unit_system)),
+					softening_length,
+					attracts_only.clone(),
+					attracts_layers,
+					name.clone(),
+				))
+			},
+			FieldSpec::Collider { radius, coefficient_of_restitution, layers, name } => {
+				let layers = match layers {
+					Some(indices) => Some(build_layers(indices)?),
+					None => None,
+				};
+				Box::new(simulation_objects::Collider::new(
+					radius.get_number(unit_system),
+					*coefficient_of_restitution,
+					layers,
+					name.clone(),
+				))
+			},
+			FieldSpec::UniformForce { force, name } =>
+				Box::new(simulation_objects::UniformForceField::new(
+					physical_quantities::Force::new(force.x(unit_system), force.y(unit_system)),
+					name.clone(),
+				)),
+			FieldSpec::LinearDrag { drag_coefficient, name } =>
+				Box::new(simulation_objects::LinearDragField::new(
+					*drag_coefficient,
+					name.clone(),
+				)),
+			FieldSpec::Marker { name } =>
+				Box::new(simulation_objects::MarkerField::new(name.clone())),
+			FieldSpec::Custom { type_tag, params } => {
+				let saved = persistence::SavedObject::new(type_tag, params);
+				return registry.load_field(saved).map_err(|e| e.to_string());
+			},
+		};
+
+		Ok(field)
+	}
+}
+
+// Converts a list of layer indices to a set of layers.
+fn build_layers(indices: &[u32]) -> Result<simulation_objects::Layers, String> {
+	let mut set = simulation_objects::Layers::NONE;
+	for index in indices {
+		if *index >= simulation_objects::Layers::COUNT {
+			return Err(format!(
+				"layer index must be less than {}: {}",
+				simulation_objects::Layers::COUNT,
+				index,
+			));
+		}
+		set = set | simulation_objects::Layers::layer(*index);
+	}
+
+	Ok(set)
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParticleSpec {
+	mass: Measure<MassDimension>,
+	position: Vector<Length>,
+	#[serde(default)]
+	velocity: Option<Vector<Speed>>,
+	#[serde(default)]
+	fields: Vec<FieldSpec>,
+}
+
+// Creates many particles at once.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+enum GeneratorSpec {
+	/// `rows` * `columns` particles. The particle in row r and column c is at
+	/// `origin + (c * spacing.x, r * spacing.y)`.
+	Grid {
+		origin: Vector<Length>,
+		rows: u32,
+		columns: u32,
+		spacing: Vector<Length>,
+		mass: Measure<MassDimension>,
+		#[serde(default)]
+		velocity: Option<Vector<Speed>>,
+		#[serde(default)]
+		fields: Vec<FieldSpec>,
+	},
+	/// `count` particles evenly spaced around a circle, optionally moving
+	/// counterclockwise around it.
+	Ring {
+		center: Vector<Length>,
+		radius: Measure<Length>,
+		count: u32,
+		mass: Measure<MassDimension>,
+		#[serde(default)]
+		tangential_speed: Option<Measure<Speed>>,
+		/// The angle of the first particle, in radians, counterclockwise from
+		/// the +x axis.
+		#[serde(default)]
+		start_angle: f64,
+		#[serde(default)]
+		fields: Vec<FieldSpec>,
+	},
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+enum RegionSpec {
+	Everywhere,
+	Circle {
+		center: Vector<Length>,
+		radius: Measure<Length>,
+	},
+	Rectangle {
+		min: Vector<Length>,
+		max: Vector<Length>,
+	},
+	Polygon {
+		vertices: Vec<Vector<Length>>,
+	},
+}
+
+impl RegionSpec {
+	fn build(&self, unit_system: &units::UnitSystem) -> simulation_objects::Region {
+		let to_displacement = |v: &Vector<Length>| {
+			physical_quantities::Displacement::new(v.x(unit_system), v.y(unit_system))
+		};
+
+		match self {
+			RegionSpec::Everywhere => simulation_objects::Region::Everywhere,
+			RegionSpec::Circle { center, radius } => simulation_objects::Region::Circle {
+				center: to_displacement(center),
+				radius: radius.get_number(unit_system),
+			},
+			RegionSpec::Rectangle { min, max } => simulation_objects::Region::Rectangle {
+				min: to_displacement(min),
+				max: to_displacement(max),
+			},
+			RegionSpec::Polygon { vertices } => simulation_objects::Region::Polygon {
+				vertices: vertices.iter().map(to_displacement).collect(),
+			},
+		}
+	}
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WorldFieldSpec {
+	region: RegionSpec,
+	field: FieldSpec,
+}
+
+/// A declarative description of a simulation, usually loaded from a RON file
+/// with `load_file`. For example:
+/// ```text
+/// Scene(
+///     tick_duration: "10 ms",
+///     simulation_speed: 1.0,
+///     particles: [
+///         (
+///             mass: "5.97e24 kg",
+///             position: (0.0, 0.0),
+///             fields: [UniversalGravitation(radius: "1 AU", name: "gravity")],
+///         ),
+///     ],
+///     generators: [
+///         Ring(center: (0.0, 0.0), radius: "7000 km", count: 12, mass: "1 t",
+///             tangential_speed: "7.5 km/s", fields: [Marker(name: "gravity")]),
+///         Grid(origin: (0.0, 0.0), rows: 2, columns: 3, spacing: ("1 m", "1 m"),
+///             mass: "1 kg", fields: [Collider(radius: "40 cm",
+///             coefficient_of_restitution: 0.9)]),
+///     ],
+///     world_fields: [
+///         (region: Everywhere, field: LinearDrag(drag_coefficient: 0.01)),
+///     ],
+/// )
+/// ```
+/// Physical values may be plain numbers or strings with units (e.g., "5 km",
+/// "36 km/h", "500 g"); see the `units` module. Plain numbers are in the
+/// scene's `unit_system` and values with units are converted to it. The unit
+/// system is `SI` unless the scene gives `Astronomical`, `Molecular` or
+/// `Custom(length: 1000.0, mass: 1.0, time: 3600.0)` (in meters, kilograms and
+/// seconds); the simulation uses it too. Built-in fields are
+/// `SimpleSelfGravity`, `UniversalGravitation`, `Collider`, `UniformForce`,
+/// `LinearDrag`, and `Marker`. Other field types can be registered with a
+/// `persistence::Registry` and created with
+/// `Custom(type_tag: "MyField", params: (...))`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+	#[serde(default)]
+	unit_system: SceneUnitSystem,
+	tick_duration: Measure<TimeDimension>,
+	#[serde(default)]
+	simulation_speed: Option<SimulationSpeed>,
+	#[serde(default)]
+	particles: Vec<ParticleSpec>,
+	#[serde(default)]
+	generators: Vec<GeneratorSpec>,
+	#[serde(default)]
+	world_fields: Vec<WorldFieldSpec>,
+	// The text the scene was parsed from, for locating errors found while
+	//	building it.
+	#[serde(skip)]
+	source: String,
+}
+
+impl Scene {
+	/// Parses a scene from RON text.
+	pub fn parse(text: &str) -> Result<Scene, SceneError> {
+		let mut scene: Scene = ron_options().from_str(text)?;
+		scene.source = String::from(text);
+		Ok(scene)
+	}
+
+	// Finds the line and column at which the item at `path` starts.
+	fn locate(&self, path: &[PathStep]) -> Option<(usize, usize)> {
+		match ron_options().from_str_seed(&self.source, Locator { path: path }) {
+			Err(e) if e.code == ron::Error::Message(String::from(Locator::FOUND)) =>
+				Some((e.span.start.line, e.span.start.col)),
+			_ => None,
+		}
+	}
+
+	pub fn get_unit_system(&self) -> units::UnitSystem {
+		self.unit_system.0
+	}
+
+	/// Gets the tick duration, in the scene's unit system.
+	pub fn get_tick_duration(&self) -> physical_quantities::Time {
+		physical_quantities::Time::new(self.tick_duration.get_number(&self.get_unit_system()))
+	}
+
+	pub fn get_simulation_speed(&self) -> Option<f64> {
+		self.simulation_speed.as_ref().map(|s| s.0)
+	}
+
+	/// Creates a simulation containing everything in the scene. As with
+	/// `Simulation::create_particle`, particles and world fields are added
+	/// during the first tick.
+	///
+	/// # Arguments
+	/// * `registry` - Used to create `Custom` fields.
+	/// * `on_tick` - The function to call on each tick. See `Simulation::new`.
+	pub fn build(
+		&self,
+		registry: &persistence::Registry,
+		on_tick: Option<fn(&simulation::Simulation)>,
+	) -> Result<simulation::Simulation, SceneError> {
+		let unit_system = self.get_unit_system();
+		let simulation = simulation::Simulation::new(
+			self.get_tick_duration(),
+			self.get_simulation_speed(),
+			on_tick,
+		);
+		simulation.set_unit_system(unit_system);
+
+		// `path` leads to the item that has the fields.
+		let build_fields = |fields: &Vec<FieldSpec>, context: &str, path: &[PathStep]| {
+			fields.iter().enumerate()
+				.map(|(i, field)| field.build(registry, &unit_system).map_err(|message| {
+					let field_path = [path, &[PathStep::Key("fields"), PathStep::Index(i)]].concat();
+					SceneError::new(format!("{}, field {}: {}", context, i, message))
+						.at(self.locate(&field_path))
+				}))
+				.collect::<Result<Vec<_>, _>>()
+		};
+
+		for (i, particle) in self.particles.iter().enumerate() {
+			let velocity = match &particle.velocity {
+				Some(v) => physical_quantities::Velocity::new(v.x(&unit_system), v.y(&unit_system)),
+				None => physical_quantities::Velocity::new(0.0, 0.0),
+			};
+			simulation.create_particle_with_velocity(
+				physical_quantities::Mass::new(particle.mass.get_number(&unit_system)),
+				physical_quantities::Displacement::new(
+					particle.position.x(&unit_system),
+					particle.position.y(&unit_system),
+				),
+				velocity,
+				build_fields(
+					&particle.fields,
+					&format!("particle {}", i),
+					&[PathStep::Key("particles"), PathStep::Index(i)],
+				)?,
+			);
+		}
+
+		for (i, generator) in self.generators.iter().enumerate() {
+			let context = format!("generator {}", i);
+			let path = [PathStep::Key("generators"), PathStep::Index(i)];
+			match generator {
+				GeneratorSpec::Grid { origin, rows, columns, spacing, mass, velocity, fields } => {
+					let velocity = match velocity {
+						Some(v) => physical_quantities::Velocity::new(v.x(&unit_system), v.y(&unit_system)),
+						None => physical_quantities::Velocity::new(0.0, 0.0),
+					};
+					let spacing = physical_quantities::Displacement::new(
+						spacing.x(&unit_system),
+						spacing.y(&unit_system),
+					);
+					let origin = physical_quantities::Displacement::new(
+						origin.x(&unit_system),
+						origin.y(&unit_system),
+					);
+					for position in utilities::layout_grid(origin, *rows as usize, *columns as usize, spacing) {
+						simulation.create_particle_with_velocity(
+							physical_quantities::Mass::new(mass.get_number(&unit_system)),
+							position,
+							velocity,
+							build_fields(fields, &context, &path)?,
+						);
+					}
+				},
+				GeneratorSpec::Ring {
+					center,
+					radius,
+					count,
+					mass,
+					tangential_speed,
+					start_angle,
+					fields,
+				} => {
+					let speed = physical_quantities::Speed::new(
+						tangential_speed.as_ref().map_or(0.0, |s| s.get_number(&unit_system)),
+					);
+					let center = physical_quantities::Displacement::new(
+						center.x(&unit_system),
+						center.y(&unit_system),
+					);
+					let ring = utilities::layout_ring(
+						center,
+						physical_quantities::Length::new(radius.get_number(&unit_system)),
+						*count as usize,
+						*start_angle,
+						speed,
+					);
+					for (position, velocity) in ring {
+						simulation.create_particle_with_velocity(
+							physical_quantities::Mass::new(mass.get_number(&unit_system)),
+							position,
+							velocity,
+							build_fields(fields, &context, &path)?,
+						);
+					}
+				},
+			}
+		}
+
+		for (i, world_field) in self.world_fields.iter().enumerate() {
+			let field = world_field.field.build(registry, &unit_system).map_err(|message| {
+				SceneError::new(format!("world field {}: {}", i, message)).at(self.locate(&[
+					PathStep::Key("world_fields"),
+					PathStep::Index(i),
+					PathStep::Key("field"),
+				]))
+			})?;
+			simulation.create_world_field(world_field.region.build(&unit_system), field);
+		}
+
+		Ok(simulation)
+	}
+}
+
+fn ron_options() -> ron::Options {
+	ron::Options::default().with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+}
+
+// A step along the path from the top of a scene to one of its items.
+#[derive(Clone, Copy)]
+enum PathStep {
+	Key(&'static str),
+	Index(usize),
+}
+
+// Reads a scene's text down to the item at `path`, then stops with an error so
+//	that ron reports the item's position. ron only reports the positions of
+//	errors, so this is how errors found while building a scene are located.
+struct Locator<'a> {
+	path: &'a [PathStep],
+}
+
+impl Locator<'_> {
+	const FOUND: &'static str = "found the item";
+
+	fn found<E: de::Error>() -> E {
+		E::custom(Self::FOUND)
+	}
+
+	fn not_found<E: de::Error>() -> E {
+		E::custom("no item at the path")
+	}
+}
+
+impl<'de> DeserializeSeed<'de> for Locator<'_> {
+	type Value = ();
+
+	fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+		deserializer.deserialize_any(self)
+	}
+}
+
+impl<'de> Visitor<'de> for Locator<'_> {
+	type Value = ();
+
+	fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "a struct or list")
+	}
+
+	fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+		let (key, rest) = match self.path.split_first() {
+			None => return Err(Self::found()),
+			Some((PathStep::Key(key), rest)) => (key, rest),
+			Some((PathStep::Index(_), _)) => return Err(Self::not_found()),
+		};
+		while let Some(name) = map.next_key::<String>()? {
+			if name == *key {
+				return map.next_value_seed(Locator { path: rest });
+			}
+			map.next_value::<de::IgnoredAny>()?;
+		}
+
+		Err(Self::not_found())
+	}
+
+	fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+		let (index, rest) = match self.path.split_first() {
+			None => return Err(Self::found()),
+			Some((PathStep::Index(index), rest)) => (*index, rest),
+			Some((PathStep::Key(_), _)) => return Err(Self::not_found()),
+		};
+		for _ in 0..index {
+			seq.next_element::<de::IgnoredAny>()?.ok_or_else(Self::not_found)?;
+		}
+
+		if rest.is_empty() {
+			// Stop before the element is read, so that its start is reported
+			//	rather than somewhere inside it.
+			seq.next_element_seed(Found)?;
+			return Err(Self::not_found());
+		}
+		seq.next_element_seed(Locator { path: rest })?.ok_or_else(Self::not_found)
+	}
+}
+
+// Stops a `Locator` just before the item it's looking for.
+struct Found;
+
+impl<'de> DeserializeSeed<'de> for Found {
+	type Value = ();
+
+	fn deserialize<D: Deserializer<'de>>(self, _deserializer: D) -> Result<(), D::Error> {
+		Err(Locator::found())
+	}
+}
+
+/// Parses a scene from RON text and builds a simulation from it. See `Scene`.
+pub fn load_str(
+	text: &str,
+	registry: &persistence::Registry,
+	on_tick: Option<fn(&simulation::Simulation)>,
+) -> Result<simulation::Simulation, SceneError> {
+	Scene::parse(text)?.build(registry, on_tick)
+}
+
+/// Reads a scene from a RON file and builds a simulation from it. Errors
+/// include the path of the file. See `Scene`.
+pub fn load_file<P: AsRef<Path>>(
+	path: P,
+	registry: &persistence::Registry,
+	on_tick: Option<fn(&simulation::Simulation)>,
+) -> Result<simulation::Simulation, SceneError> {
+	let path = path.as_ref();
+	let with_file = |mut error: SceneError| {
+		error.file = Some(path.to_path_buf());
+		error
+	};
+
+	let text = std::fs::read_to_string(path)
+		.map_err(|e| with_file(SceneError::new(e.to_string())))?;
+	load_str(&text, registry, on_tick).map_err(with_file)
+}
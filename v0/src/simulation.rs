@@ -1,6 +1,11 @@
-use crate::{physical_quantities, simulation_objects, utilities};
-use std::collections::HashMap;
+use crate::{events, physical_quantities, recording, simulation_objects, units, utilities};
+#[cfg(feature = "persistence")]
+use crate::persistence;
+use crate::physical_quantities::Scalar;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::cell::RefCell;
+use std::thread;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
@@ -8,7 +13,11 @@ use uuid::Uuid;
 #[cfg(test)]
 mod tests {
 	use std::collections::HashSet;
+	use std::rc::Rc;
     use super::*;
+	#[cfg(feature = "fixed-point")]
+	use crate::fixed_point;
+	use crate::testing;
 
 	/********************* Simulation ********************/
 
@@ -46,7 +55,7 @@ mod tests {
 			None,
 		);
 		assert_eq!(
-			simulation.tick_duration,
+			*simulation.tick_duration.borrow(),
 			physical_quantities::Time::new(1.0),
 			"Incorrect tick_duration.",
 		);
@@ -56,7 +65,7 @@ mod tests {
 			"Incorrect elapsed_ticks."
 		);
 		assert!(
-			simulation.simulation_speed.is_none(),
+			simulation.simulation_speed.borrow().is_none(),
 			"Incorrect simulation_speed."
 		);
 		assert!(
@@ -74,7 +83,7 @@ mod tests {
 			Some(dummy_function),
 		);
 		assert_eq!(
-			simulation.tick_duration,
+			*simulation.tick_duration.borrow(),
 			physical_quantities::Time::new(1.0),
 			"Incorrect tick_duration."
 		);
@@ -84,7 +93,7 @@ mod tests {
 			"Incorrect elapsed_ticks."
 		);
 		assert_eq!(
-			simulation.simulation_speed.expect("Should have simulation speed."),
+			simulation.simulation_speed.borrow().expect("Should have simulation speed."),
 			1.0,
 			"Incorrect simulation_speed."
 		);
@@ -207,6 +216,52 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn simulation_creates_particle_with_velocity() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		let particle_id = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(1.0, 1.0),
+			physical_quantities::Velocity::new(2.0, -4.0),
+			Vec::new(),
+		);
+
+		// The particle doesn't move during the tick in which it's added.
+		simulation.step();
+		assert_eq!(
+			physical_quantities::Displacement::new(1.0, 1.0),
+			simulation.get_position(particle_id),
+		);
+		assert_eq!(
+			physical_quantities::Velocity::new(2.0, -4.0),
+			simulation.get_velocity(particle_id),
+		);
+
+		simulation.step();
+		assert_eq!(
+			physical_quantities::Displacement::new(2.0, -1.0),
+			simulation.get_position(particle_id),
+		);
+	}
+
+	#[test]
+	fn simulation_gets_particle_ids() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let mut ids = Vec::new();
+		for _ in 0..5 {
+			ids.push(simulation.create_particle(
+				physical_quantities::Mass::new(1.0),
+				physical_quantities::Displacement::new(0.0, 0.0),
+				Vec::new(),
+			));
+		}
+		assert!(simulation.get_particle_ids().is_empty());
+
+		simulation.step();
+		ids.sort();
+		assert_eq!(simulation.get_particle_ids(), ids);
+	}
+
 	#[test]
 	fn simulation_deletes_particle() {
 		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
@@ -2013,7 +2068,7 @@ mod tests {
 	//	elastic collision.
 	#[test]
 	fn functional_collision() {
-		let permissible_error = 0.0;
+		let permissible_error = 1e-12;
 		let force = physical_quantities::Force::new(1000.0, 0.0);
 		let mass_a = physical_quantities::Mass::new(1.0);
 		let mass_b = physical_quantities::Mass::new(2.0);
@@ -2026,7 +2081,7 @@ mod tests {
 		let final_velocity_b;
 		let original_total_energy;
 		let final_total_energy;
-		let simulation = Simulation::new(tick_duration, None, None);
+		let simulation: Simulation = Simulation::new(tick_duration, None, None);
 		let particle_id_a = simulation.create_particle(
 			mass_a,
 			physical_quantities::Displacement::new(0.0, 0.0),
@@ -2034,6 +2089,7 @@ mod tests {
 				5.0,
 				1.0,
 				None,
+				None,
 			))),
 		);
 		let particle_id_b = simulation.create_particle(
@@ -2043,6 +2099,7 @@ mod tests {
 				5.0,
 				1.0,
 				None,
+				None,
 			))),
 		);
 		
@@ -2057,7 +2114,7 @@ mod tests {
 		let distance_to_collision =
 			utilities::measure_distance(
 				simulation.get_position(particle_id_a),
-				simulation.get_position(particle_id_a),
+				simulation.get_position(particle_id_b),
 			);
 		let relative_velocity = (original_velocity_a - original_velocity_b);
 		let closing_speed = relative_velocity.get_magnitude();
@@ -2160,8 +2217,8 @@ mod tests {
 		// Verify that the total kinetic energy of the system is as expected.
 		assert!(
 			numbers_are_almost_equal(
-				12.0,
-				1.0,
+				original_total_energy,
+				final_total_energy,
 				permissible_error,
 			),
 			"Error in total energy greater than permissible error of {:?}.\n\
@@ -2191,7 +2248,7 @@ mod tests {
 	//	collision happen in the x-dimension).
 	#[test]
 	fn functional_collision_enclosed_collider() {
-		let permissible_error = 0.0;
+		let permissible_error = 1e-12;
 		let force = physical_quantities::Force::new(0.0, 1000.0);
 		let mass_a = physical_quantities::Mass::new(1.0);
 		let mass_b = physical_quantities::Mass::new(2.0);
@@ -2204,7 +2261,7 @@ mod tests {
 		let final_velocity_b;
 		let original_total_energy;
 		let final_total_energy;
-		let simulation = Simulation::new(tick_duration, None, None);
+		let simulation: Simulation = Simulation::new(tick_duration, None, None);
 		let particle_id_a = simulation.create_particle(
 			mass_a,
 			physical_quantities::Displacement::new(0.0, 0.0),
@@ -2212,6 +2269,7 @@ mod tests {
 				20.0,
 				1.0,
 				None,
+				None,
 			))),
 		);
 		let particle_id_b = simulation.create_particle(
@@ -2221,6 +2279,7 @@ mod tests {
 				2.5,
 				1.0,
 				None,
+				None,
 			))),
 		);
 		
@@ -2235,7 +2294,7 @@ mod tests {
 		let distance_to_collision =
 			utilities::measure_distance(
 				simulation.get_position(particle_id_a),
-				simulation.get_position(particle_id_a),
+				simulation.get_position(particle_id_b),
 			);
 		let relative_velocity = (original_velocity_a - original_velocity_b);
 		let closing_speed = relative_velocity.get_magnitude();
@@ -2338,8 +2397,8 @@ mod tests {
 		// Verify that the total kinetic energy of the system is as expected.
 		assert!(
 			numbers_are_almost_equal(
-				12.0,
-				1.0,
+				original_total_energy,
+				final_total_energy,
 				permissible_error,
 			),
 			"Error in total energy greater than permissible error of {:?}.\n\
@@ -2377,7 +2436,7 @@ mod tests {
 		let final_velocity_b;
 		let original_total_energy;
 		let final_total_energy;
-		let simulation = Simulation::new(tick_duration, None, None);
+		let simulation: Simulation = Simulation::new(tick_duration, None, None);
 		let particle_id_a = simulation.create_particle(
 			mass_a,
 			physical_quantities::Displacement::new(0.0, 0.0),
@@ -2385,6 +2444,7 @@ mod tests {
 				5.0,
 				1.0,
 				None,
+				None,
 			))),
 		);
 		let particle_id_b = simulation.create_particle(
@@ -2394,6 +2454,7 @@ mod tests {
 				5.0,
 				1.0,
 				None,
+				None,
 			))),
 		);
 		
@@ -2459,6 +2520,107 @@ mod tests {
 		);
 	}
 
+	// Creates two particles whose colliders have a coefficient of restitution
+	//	of 0 and launches one at the other. After a perfectly inelastic
+	//	collision, both particles should move together with the velocity of
+	//	their center of mass.
+	#[test]
+	fn functional_collision_inelastic() {
+		let simulation: Simulation = Simulation::new(physical_quantities::Time::new(0.001), None, None);
+		let particle_id_a = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(1.0, 0.0),
+			vec!(Box::new(simulation_objects::Collider::new(5.0, 0.0, None, None))),
+		);
+		let particle_id_b = simulation.create_particle(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(15.0, 0.0),
+			vec!(Box::new(simulation_objects::Collider::new(5.0, 0.0, None, None))),
+		);
+
+		// The colliders touch after 5 seconds. Run for a while longer.
+		while simulation.get_elapsed_time() < physical_quantities::Time::new(7.0) {
+			simulation.step();
+		}
+
+		let expected_velocity = physical_quantities::Velocity::new(1.0 / 3.0, 0.0);
+		assert!(
+			velocities_are_almost_equal(
+				simulation.get_velocity(particle_id_a),
+				expected_velocity,
+				1e-12,
+			),
+			"Particle A's velocity was wrong after the collision: {:?}",
+			simulation.get_velocity(particle_id_a),
+		);
+		assert!(
+			velocities_are_almost_equal(
+				simulation.get_velocity(particle_id_b),
+				expected_velocity,
+				1e-12,
+			),
+			"Particle B's velocity was wrong after the collision: {:?}",
+			simulation.get_velocity(particle_id_b),
+		);
+	}
+
+	// Creates two particles of equal mass with perfectly elastic colliders and
+	//	launches one so that it strikes the other off center. Momentum and
+	//	kinetic energy should be conserved, and the particles should leave the
+	//	collision at right angles to each other.
+	#[test]
+	fn functional_collision_oblique() {
+		let mass = physical_quantities::Mass::new(1.0);
+		let original_velocity = physical_quantities::Velocity::new(1.0, 0.0);
+		let simulation: Simulation = Simulation::new(physical_quantities::Time::new(0.001), None, None);
+		let particle_id_a = simulation.create_particle_with_velocity(
+			mass,
+			physical_quantities::Displacement::new(0.0, 0.0),
+			original_velocity,
+			vec!(Box::new(simulation_objects::Collider::new(5.0, 1.0, None, None))),
+		);
+		let particle_id_b = simulation.create_particle(
+			mass,
+			physical_quantities::Displacement::new(15.0, 5.0),
+			vec!(Box::new(simulation_objects::Collider::new(5.0, 1.0, None, None))),
+		);
+
+		while simulation.get_elapsed_time() < physical_quantities::Time::new(10.0) {
+			simulation.step();
+		}
+
+		let final_velocity_a = simulation.get_velocity(particle_id_a);
+		let final_velocity_b = simulation.get_velocity(particle_id_b);
+		assert!(final_velocity_b.y() > 0.0, "The particles didn't collide: {:?}", final_velocity_b);
+		assert!(
+			velocities_are_almost_equal(final_velocity_a + final_velocity_b, original_velocity, 1e-12),
+			"Momentum was not conserved: {:?} + {:?}",
+			final_velocity_a,
+			final_velocity_b,
+		);
+		assert!(
+			numbers_are_almost_equal(
+				final_velocity_a.get_magnitude().powi(2) + final_velocity_b.get_magnitude().powi(2),
+				original_velocity.get_magnitude().powi(2),
+				1e-12,
+			),
+			"Kinetic energy was not conserved: {:?} + {:?}",
+			final_velocity_a,
+			final_velocity_b,
+		);
+		assert!(
+			numbers_are_almost_equal(
+				final_velocity_a.x() * final_velocity_b.x() + final_velocity_a.y() * final_velocity_b.y(),
+				0.0,
+				1e-12,
+			),
+			"The particles didn't leave at right angles: {:?}, {:?}",
+			final_velocity_a,
+			final_velocity_b,
+		);
+	}
+
 	// Verifies that two particles that are already moving away from each other
 	//	will not collide, even if their colliders overlap (as may often be the
 	//	case for a few ticks after a collision).
@@ -2472,172 +2634,3062 @@ mod tests {
 	}
 	*/
 
-	// TODO: Should probably implement a test for the universal gravitation
-	// field.
-}
+	/********************* Simulation: events ********************/
 
+	#[test]
+	fn simulation_emits_particle_created_and_deleted_events() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let particle_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
 
-/// Owns all of the data that constitutes a physics simulation. Provides methods
-/// for interacting with and running the simulation.
-pub struct Simulation {
-	// The number of simulated seconds that elapse in a single tick.
-	//	This is effectively the resolution of the simulation.
-	tick_duration: physical_quantities::Time,
-	// A collection that owns all particles in the simulation.
-	particles: RefCell<HashMap<Uuid, simulation_objects::Particle>>,
-	// The number of ticks that have passed so far.
-	elapsed_ticks: RefCell<physical_quantities::Ticks>,
-	// Speed at which the simulation will run, resources permitting. Units are
-	//	(simulated seconds) / (real world seconds). If None, run as fast as
-	//	possible.
-	simulation_speed: Option<f64>,
-	// A function called on each tick. Allows user-defined logic to be driven
-	//	by the simulation.
-	on_tick: Option<fn(&Simulation)>,
-	// Holds forces, keyed by particle_id, to simulate when appropriate.
-	applied_forces: RefCell<HashMap<Uuid, Vec<physical_quantities::Force>>>,
-	// Stores the IDs of particles to delete when appropriate.
-	particle_ids_to_delete: RefCell<Vec<Uuid>>,
-	// Stores particles to add to the simulation when appropriate.
-	particles_to_add: RefCell<Vec<simulation_objects::Particle>>,
-	// If true, the simulation should be paused. If false, the simulation should
-	//	be running.
-	is_paused: RefCell<bool>,
-	// Keeps track of when the last tick started, in real world time. This
-	//	allows start() and step_synchronized() to call tick at the appropriate
-	//	rate.
-	previous_tick_instant: RefCell<Instant>,
-	// How long start() and step_synchronize should wait between calls to tick.
-	tick_wait_duration: Duration,
-}
+		// Nothing happens until a tick occurs.
+		assert!(simulation.drain_events().is_empty());
 
-impl Simulation {
-	fn tick(&self) {
-		*self.previous_tick_instant.borrow_mut() = Instant::now();
+		simulation.step();
 
-		// Call the on_tick fn pointer, if it exists.
-		match self.on_tick {
-			Some(f) => f(self),
-			None => (),
+		let events = simulation.drain_events();
+		assert_eq!(events.len(), 1);
+		assert_eq!(events[0].get_tick(), physical_quantities::Ticks::new(0));
+		match events[0].get_kind() {
+			events::EventKind::ParticleCreated { particle_id: id } =>
+				assert_eq!(*id, particle_id),
+			other => panic!("Unexpected event: {:?}", other),
 		}
 
-		// For each field, find all particles affected by that field and apply
-		//	the field's effect to each of those particles.
-		// TODO (for v1): There are algorithms and data structures to more
-		//	efficiently find things based on spatial relationships (e.g.,
-		//	distance). Do some research and optimize this so searching for
-		//	nearby particles isn't so inefficient.
-		// Vec of (field owner, field, affected particles).
-		for field_owner in self.particles.borrow().values() {
-			for field in field_owner.get_fields().iter() {
-				let owner_id = field_owner.get_id();
-				let mut triggered_by = HashMap::new();
+		// Draining removes the events.
+		assert!(simulation.drain_events().is_empty());
 
-				// Add the field owner if the field affects it.
-				if field.affects_self() {
-					// The field owner's fields should never trigger each other.
-					// Insert an empty Vec of fields.
-					triggered_by.insert(owner_id, vec![None]);
-				}
+		simulation.delete_particle(particle_id);
+		simulation.step();
 
-				// Add all particles, other than the field owner, that are
-				//	affected by the field, if this field affects particles other
-				//	than the particle to which it's attached.
-				if field.affects_others() {
-					for particle in self.particles.borrow().values() {
-						let particle_id = particle.get_id();
-						// Skip the field owner.
-						if owner_id != particle_id {
+		let events = simulation.drain_events();
+		assert_eq!(events.len(), 1);
+		assert_eq!(events[0].get_tick(), physical_quantities::Ticks::new(1));
+		match events[0].get_kind() {
+			events::EventKind::ParticleDeleted { particle_id: id } =>
+				assert_eq!(*id, particle_id),
+			other => panic!("Unexpected event: {:?}", other),
+		}
+	}
 
-							// If the particle is within this field and this
-							//	field affects particles within it.
-							if field.triggers_on_particles()
-								&& utilities::is_within_radius(
-									particle.get_position(),
-									field.get_radius(),
-									field_owner.get_position(),
-									true,
-								) {
-								// If this field is trigered by a particle,
-								//	insert the particle with a vector containing
-								//	None to denote that it wasn't added by field
-								//	overlap.
-								triggered_by.insert(particle_id, vec![None]);
-							}
+	// Verifies that a particle deleted by two fields on the same tick is only
+	//	reported as deleted once.
+	#[test]
+	fn simulation_emits_one_deleted_event_per_particle() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let field = DeletionField {
+			radius: 10.0,
+			affects_self: false,
+			affects_others: true,
+			triggers_on_fields: false,
+			triggers_on_particles: true,
+			name: String::from("The Destructor"),
+		};
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec!(Box::new(field.clone()), Box::new(field.clone())),
+		);
+		let victim = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(1.0, 0.0),
+			Vec::new(),
+		);
 
-							// Check whether this field affects the owners of
-							//	overlapping fields and whether the particle has
-							//	fields that overlap with this field. Record any
-							//	overlapping fields to send to the effect method.
-							if field.triggers_on_fields() {
-								for info in particle.get_field_info() {
-									if utilities::is_within_radius(
-										particle.get_position(),
-										field.get_radius() + info.get_radius(),
-										field_owner.get_position(),
- 										// TODO: It probably makes more sense for
-										//	this to be false. I.e., consider
-										//	fields that just touch to be
-										//	overlapping.
-										true,
-									) {
-										if let Some(v) = triggered_by.get_mut(&particle_id) {
-												v.push(Some(info));
-										} else {
-											triggered_by.insert(
-												particle_id,
-												vec![Some(info)],
-											);
-										}
-									}
-								}
-							}
-						}
-					}
-				}
+		simulation.step();
+		simulation.drain_events();
+		simulation.step();
 
-				// Add this Field's effects to the lists of actions to take.
-				field.effect(
-					self,
-					field_owner.get_position(),
-					triggered_by,
-					field_owner.get_id(),
-				);
-			}
-		}
+		let deletions = simulation.drain_events()
+			.into_iter()
+			.filter(|e| matches!(
+				e.get_kind(),
+				events::EventKind::ParticleDeleted { particle_id } if *particle_id == victim
+			))
+			.count();
+		assert_eq!(deletions, 1);
+	}
 
-		// Delete any particles that were staged for deletion. Doing this before
-		//	applying forces avoids having to do calculations for particles that
-		//	are being deleted anyway.
-		for particle_id in &*self.particle_ids_to_delete.borrow() {
-			self.particles.borrow_mut().remove(&particle_id);
-		}
-		self.particle_ids_to_delete.borrow_mut().clear();
+	#[test]
+	fn simulation_emits_trigger_enter_and_exit_events() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let sensor = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec!(Box::new(DoubleTriggerPanicField {
+				radius: 10.0,
+				affects_self: true,
+				affects_others: true,
+				triggers_on_fields: false,
+				triggers_on_particles: true,
+				name: String::from("sensor"),
+			})),
+		);
+		// Starts outside the sensor and moves through it at 5 units per tick.
+		let mover = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(-15.0, 0.0),
+			Vec::new(),
+		);
 
-		// For each particle, calculate and apply any change in velocity or
-		//	position that should occur during this tick.
-		for (particle_id, particle) in self.particles.borrow_mut().iter_mut() {
-			match self.applied_forces.borrow().get(particle_id) {
-				Some(forces) => particle.accelerate(forces, self.tick_duration),
-				None => particle.coast(self.tick_duration),
+		simulation.step();
+		simulation.apply_force(mover, physical_quantities::Force::new(10.0, 0.0));
+		// Accelerates to 10 units per tick while moving 5 units.
+		simulation.step();
+		simulation.drain_events();
+
+		let mut enter_ticks = Vec::new();
+		let mut exit_ticks = Vec::new();
+		for _ in 0..5 {
+			simulation.step();
+			for event in simulation.drain_events() {
+				match event.get_kind() {
+					events::EventKind::TriggerEnter { field_owner_id, field_name, particle_id } => {
+						assert_eq!(*field_owner_id, sensor);
+						assert_eq!(field_name, "sensor");
+						assert_eq!(*particle_id, mover);
+						enter_ticks.push(event.get_tick());
+					},
+					events::EventKind::TriggerExit { field_owner_id, field_name, particle_id } => {
+						assert_eq!(*field_owner_id, sensor);
+						assert_eq!(field_name, "sensor");
+						assert_eq!(*particle_id, mover);
+						exit_ticks.push(event.get_tick());
+					},
+					other => panic!("Unexpected event: {:?}", other),
+				}
 			}
 		}
-		self.applied_forces.borrow_mut().clear();
 
-		// Add any newly created particles to the simulation. Doing this after
-		//	applying changes in velocity and position avoids iterating through
-		//	particles that can't have changes during this tick anyway.
-		for particle in self.particles_to_add.borrow_mut().drain(..) {
-			let v = self.particles.borrow_mut().insert(particle.get_id(), particle);
-			// If v is Some, it means we already had a particle with this
-			//	particle's ID. This should not happen.
+		// The mover is at x = -10 during tick 2 (on the boundary, which doesn't
+		//	count), x = 0 during tick 3, and x = 10 during tick 4.
+		assert_eq!(enter_ticks, vec!(physical_quantities::Ticks::new(3)));
+		assert_eq!(exit_ticks, vec!(physical_quantities::Ticks::new(4)));
+	}
+
+	struct EmitterField {
+		name: String,
+	}
+
+	impl simulation_objects::Field for EmitterField {
+		fn effect(
+			&self,
+			simulation: &Simulation,
+			_position: physical_quantities::Displacement,
+			_triggered_by: HashMap<Uuid, Vec<Option<simulation_objects::FieldInfo>>>,
+			field_owner_id: Uuid,
+		) {
+			simulation.emit_event(
+				field_owner_id,
+				String::from("ping"),
+				Box::new(simulation.get_elapsed_ticks().get_number()),
+			);
+		}
+
+		fn get_radius(&self) -> f64 {
+			0.0
+		}
+
+		fn affects_self(&self) -> bool {
+			true
+		}
+
+		fn affects_others(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_fields(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_particles(&self) -> bool {
+			false
+		}
+
+		fn get_name(&self) -> &String {
+			&self.name
+		}
+	}
+
+	#[test]
+	fn simulation_field_emits_custom_event() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let emitter = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec!(Box::new(EmitterField { name: String::from("emitter") })),
+		);
+
+		simulation.step();
+		simulation.drain_events();
+		simulation.step();
+
+		let events = simulation.drain_events();
+		assert_eq!(events.len(), 1);
+		match events.into_iter().next().expect("Should have an event.").into_kind() {
+			events::EventKind::Custom { source_id, name, payload } => {
+				assert_eq!(source_id, emitter);
+				assert_eq!(name, "ping");
+				assert_eq!(*payload.downcast::<u64>().expect("Should be a u64."), 1);
+			},
+			other => panic!("Unexpected event: {:?}", other),
+		}
+	}
+
+	struct RecordingSubscriber {
+		seen: Rc<RefCell<Vec<String>>>,
+	}
+
+	impl events::EventSubscriber for RecordingSubscriber {
+		fn on_event(&mut self, simulation: &Simulation, event: &events::Event) {
+			assert_eq!(event.get_tick().get_number() + 1, simulation.get_elapsed_ticks().get_number());
+			self.seen.borrow_mut().push(format!("{:?}", event.get_kind()));
+		}
+	}
+
+	#[test]
+	fn simulation_passes_events_to_subscribers() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let seen_1 = Rc::new(RefCell::new(Vec::new()));
+		let seen_2 = Rc::new(RefCell::new(Vec::new()));
+		simulation.subscribe(Box::new(RecordingSubscriber { seen: Rc::clone(&seen_1) }));
+		simulation.subscribe(Box::new(RecordingSubscriber { seen: Rc::clone(&seen_2) }));
+
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		simulation.emit_event(Uuid::nil(), String::from("hello"), Box::new(()));
+		simulation.step();
+
+		assert_eq!(seen_1.borrow().len(), 2);
+		assert_eq!(*seen_1.borrow(), *seen_2.borrow());
+		// Subscribers don't consume events.
+		assert_eq!(simulation.drain_events().len(), 2);
+	}
+
+	// Verifies that two colliders approaching head-on exchange velocities in a
+	//	perfectly elastic collision between equal masses and that the collision
+	//	is reported once.
+	#[test]
+	fn simulation_emits_collision_resolved_event() {
+		let tick_duration = physical_quantities::Time::new(0.01);
+		let simulation = Simulation::new(tick_duration, None, None);
+		let particle_id_a = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec!(Box::new(simulation_objects::Collider::new(1.0, 1.0, None, None))),
+		);
+		let particle_id_b = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(3.0, 0.0),
+			vec!(Box::new(simulation_objects::Collider::new(1.0, 1.0, None, None))),
+		);
+		simulation.step();
+		// Get particle a moving at 1 unit per second.
+		simulation.apply_force(particle_id_a, physical_quantities::Force::new(100.0, 0.0));
+		simulation.step();
+		simulation.drain_events();
+
+		let mut collisions = 0;
+		while simulation.get_elapsed_time() < physical_quantities::Time::new(3.0) {
+			simulation.step();
+			for event in simulation.drain_events() {
+				if let events::EventKind::CollisionResolved { particle_id_a: a, particle_id_b: b }
+					= event.get_kind()
+				{
+					assert!(
+						(*a == particle_id_a && *b == particle_id_b)
+						|| (*a == particle_id_b && *b == particle_id_a)
+					);
+					collisions += 1;
+				}
+			}
+		}
+
+		assert_eq!(collisions, 1);
+		assert!(
+			velocities_are_almost_equal(
+				physical_quantities::Velocity::new(0.0, 0.0),
+				simulation.get_velocity(particle_id_a),
+				1e-9,
+			),
+			"Particle a should have stopped: {:?}",
+			simulation.get_velocity(particle_id_a),
+		);
+		assert!(
+			velocities_are_almost_equal(
+				physical_quantities::Velocity::new(1.0, 0.0),
+				simulation.get_velocity(particle_id_b),
+				1e-9,
+			),
+			"Particle b should have a's original velocity: {:?}",
+			simulation.get_velocity(particle_id_b),
+		);
+	}
+
+	/********************* Simulation: world fields ********************/
+
+	#[test]
+	fn simulation_world_field_everywhere_affects_all_particles() {
+		let tick_duration = physical_quantities::Time::new(1.0);
+		let simulation = Simulation::new(tick_duration, None, None);
+		let gravity = physical_quantities::Acceleration::new(0.0, -10.0);
+		simulation.create_world_field(
+			simulation_objects::Region::Everywhere,
+			Box::new(simulation_objects::SimpleSelfGravityField::new(gravity, None)),
+		);
+		let light = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		let heavy = simulation.create_particle(
+			physical_quantities::Mass::new(100.0),
+			physical_quantities::Displacement::new(1.0e6, 0.0),
+			Vec::new(),
+		);
+
+		// Add the particles and the world field.
+		simulation.step();
+		simulation.step();
+
+		// Both particles fall at the same rate, regardless of mass.
+		let expected_velocity = gravity * tick_duration;
+		assert_eq!(expected_velocity, simulation.get_velocity(light));
+		assert_eq!(expected_velocity, simulation.get_velocity(heavy));
+	}
+
+	#[test]
+	fn simulation_world_field_region_only_affects_particles_inside() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let wind = physical_quantities::Force::new(1.0, 0.0);
+		let field_id = simulation.create_world_field(
+			simulation_objects::Region::Rectangle {
+				min: physical_quantities::Displacement::new(-10.0, -10.0),
+				max: physical_quantities::Displacement::new(10.0, 10.0),
+			},
+			Box::new(simulation_objects::UniformForceField::new(wind, None)),
+		);
+		let inside = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		let outside = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 20.0),
+			Vec::new(),
+		);
+
+		simulation.step();
+		simulation.drain_events();
+		simulation.step();
+
+		assert_eq!(
+			physical_quantities::Velocity::new(1.0, 0.0),
+			simulation.get_velocity(inside),
+		);
+		assert_eq!(
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation.get_velocity(outside),
+		);
+
+		// World fields report particles entering them.
+		let events = simulation.drain_events();
+		assert_eq!(events.len(), 1);
+		match events[0].get_kind() {
+			events::EventKind::TriggerEnter { field_owner_id, field_name, particle_id } => {
+				assert_eq!(*field_owner_id, field_id);
+				assert_eq!(field_name, "UniformForceField");
+				assert_eq!(*particle_id, inside);
+			},
+			other => panic!("Unexpected event: {:?}", other),
+		}
+	}
+
+	#[test]
+	fn simulation_deletes_world_field() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let field_id = simulation.create_world_field(
+			simulation_objects::Region::Everywhere,
+			Box::new(simulation_objects::UniformForceField::new(
+				physical_quantities::Force::new(1.0, 0.0),
+				None,
+			)),
+		);
+		let particle_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		simulation.step();
+
+		// Like a deleted particle's fields, the world field still acts during
+		//	the tick in which it is removed.
+		simulation.delete_world_field(field_id);
+		simulation.step();
+		assert_eq!(
+			physical_quantities::Velocity::new(1.0, 0.0),
+			simulation.get_velocity(particle_id),
+		);
+
+		// After that, it no longer pushes the particle.
+		simulation.step();
+		assert_eq!(
+			physical_quantities::Velocity::new(1.0, 0.0),
+			simulation.get_velocity(particle_id),
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "the provided world field ID was not found: ")]
+	fn simulation_delete_world_field_panics_on_missing_id() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.delete_world_field(Uuid::new_v4());
+	}
+
+	#[test]
+	fn simulation_world_drag_field_slows_particles() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		simulation.create_world_field(
+			simulation_objects::Region::Circle {
+				center: physical_quantities::Displacement::new(0.0, 0.0),
+				radius: 100.0,
+			},
+			Box::new(simulation_objects::LinearDragField::new(1.0, None)),
+		);
+		let particle_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		simulation.step();
+		// Launch the particle at 4 units per second.
+		simulation.apply_force(particle_id, physical_quantities::Force::new(8.0, 0.0));
+		simulation.step();
+		assert_eq!(
+			physical_quantities::Velocity::new(4.0, 0.0),
+			simulation.get_velocity(particle_id),
+		);
+
+		// F = -b * v = -4, a = F / m = -4, Δv = a * t = -2.
+		simulation.step();
+		assert_eq!(
+			physical_quantities::Velocity::new(2.0, 0.0),
+			simulation.get_velocity(particle_id),
+		);
+	}
+
+	/********************* Simulation: pair interactions ********************/
+
+	// Pushes particle a in the +x direction (and, therefore, particle b in the
+	//	-x direction) and counts how many times it's evaluated.
+	struct CountingInteraction {
+		cutoff: f64,
+		count: Rc<RefCell<usize>>,
+	}
+
+	impl simulation_objects::PairInteraction for CountingInteraction {
+		fn force(
+			&self,
+			_simulation: &Simulation,
+			_particle_id_a: Uuid,
+			_position_a: physical_quantities::Displacement,
+			_particle_id_b: Uuid,
+			_position_b: physical_quantities::Displacement,
+		) -> Option<physical_quantities::Force> {
+			*self.count.borrow_mut() += 1;
+			Some(physical_quantities::Force::new(1.0, 0.0))
+		}
+
+		fn get_cutoff(&self) -> f64 {
+			self.cutoff
+		}
+	}
+
+	fn marker(name: &str) -> Vec<Box<dyn simulation_objects::Field>> {
+		vec![Box::new(simulation_objects::MarkerField::new(String::from(name)))]
+	}
+
+	#[test]
+	fn simulation_pair_interaction_applies_equal_and_opposite_forces_once_per_pair() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let count = Rc::new(RefCell::new(0));
+		simulation.create_pair_interaction(
+			String::from("thing"),
+			String::from("thing"),
+			Box::new(CountingInteraction { cutoff: f64::INFINITY, count: Rc::clone(&count) }),
+		);
+		let particle_id_1 = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			marker("thing"),
+		);
+		let particle_id_2 = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(1.0, 0.0),
+			marker("thing"),
+		);
+
+		simulation.step();
+		assert_eq!(*count.borrow(), 0);
+		simulation.step();
+		assert_eq!(*count.borrow(), 1);
+
+		let velocity_1 = simulation.get_velocity(particle_id_1);
+		let velocity_2 = simulation.get_velocity(particle_id_2);
+		assert_eq!(velocity_1.get_magnitude(), 1.0);
+		assert_eq!(velocity_1.x(), -velocity_2.x());
+		assert_eq!(velocity_1.y(), -velocity_2.y());
+	}
+
+	#[test]
+	fn simulation_pair_interaction_matches_field_names_in_either_order() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let count = Rc::new(RefCell::new(0));
+		simulation.create_pair_interaction(
+			String::from("a"),
+			String::from("b"),
+			Box::new(CountingInteraction { cutoff: f64::INFINITY, count: Rc::clone(&count) }),
+		);
+		let mut a_ids = Vec::new();
+		let mut b_ids = Vec::new();
+		// Create enough particles that "a" particles will sort both before and
+		//	after "b" particles.
+		for i in 0..5 {
+			a_ids.push(simulation.create_particle(
+				physical_quantities::Mass::new(1.0),
+				physical_quantities::Displacement::new(i as f64, 0.0),
+				marker("a"),
+			));
+		}
+		b_ids.push(simulation.create_particle(
+			physical_quantities::Mass::new(5.0),
+			physical_quantities::Displacement::new(0.0, 1.0),
+			marker("b"),
+		));
+		let unmarked_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 2.0),
+			marker("c"),
+		);
+
+		simulation.step();
+		simulation.step();
+
+		// Only the "a"-"b" pairs interact. "a"-"a" pairs do not.
+		assert_eq!(*count.borrow(), 5);
+		for id in a_ids {
+			assert_eq!(
+				physical_quantities::Velocity::new(1.0, 0.0),
+				simulation.get_velocity(id),
+			);
+		}
+		assert_eq!(
+			physical_quantities::Velocity::new(-1.0, 0.0),
+			simulation.get_velocity(b_ids[0]),
+		);
+		assert_eq!(
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation.get_velocity(unmarked_id),
+		);
+	}
+
+	#[test]
+	fn simulation_pair_interaction_ignores_pairs_beyond_cutoff() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let count = Rc::new(RefCell::new(0));
+		simulation.create_pair_interaction(
+			String::from("thing"),
+			String::from("thing"),
+			Box::new(CountingInteraction { cutoff: 5.0, count: Rc::clone(&count) }),
+		);
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			marker("thing"),
+		);
+		// Exactly at the cutoff.
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 5.0),
+			marker("thing"),
+		);
+		// Just inside the cutoff of the first particle, but outside the cutoff
+		//	of the second.
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, -4.9),
+			marker("thing"),
+		);
+
+		simulation.step();
+		simulation.step();
+
+		assert_eq!(*count.borrow(), 1);
+	}
+
+	#[test]
+	fn simulation_deletes_pair_interaction() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let count = Rc::new(RefCell::new(0));
+		let interaction_id = simulation.create_pair_interaction(
+			String::from("thing"),
+			String::from("thing"),
+			Box::new(CountingInteraction { cutoff: f64::INFINITY, count: Rc::clone(&count) }),
+		);
+		for i in 0..2 {
+			simulation.create_particle(
+				physical_quantities::Mass::new(1.0),
+				physical_quantities::Displacement::new(i as f64, 0.0),
+				marker("thing"),
+			);
+		}
+		simulation.step();
+
+		// Like a world field, the interaction still acts during the tick in
+		//	which it is removed.
+		simulation.delete_pair_interaction(interaction_id);
+		simulation.step();
+		assert_eq!(*count.borrow(), 1);
+		simulation.step();
+		assert_eq!(*count.borrow(), 1);
+	}
+
+	#[test]
+	#[should_panic(expected = "the provided pair interaction ID was not found: ")]
+	fn simulation_delete_pair_interaction_panics_on_missing_id() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.delete_pair_interaction(Uuid::new_v4());
+	}
+
+	// Verifies that UniversalGravitationField only pulls other particles
+	//	toward its owner.
+	#[test]
+	fn simulation_universal_gravitation_field_pulls_others_toward_owner() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let owner_id = simulation.create_particle(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec![Box::new(simulation_objects::UniversalGravitationField::new(
+				10.0,
+				Some(1.0),
+				None,
+				None,
+				None,
+				None,
+			))],
+		);
+		let other_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(2.0, 0.0),
+			Vec::new(),
+		);
+
+		simulation.step();
+		simulation.step();
+
+		// F = G * m_1 * m_2 / r^2 = 1 * 2 * 1 / 4 = 0.5, toward the owner.
+		assert_eq!(
+			physical_quantities::Velocity::new(-0.5, 0.0),
+			simulation.get_velocity(other_id),
+		);
+		assert_eq!(
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation.get_velocity(owner_id),
+		);
+	}
+
+	// Verifies that symmetric gravity conserves momentum, even for particles
+	//	with very different masses.
+	#[test]
+	fn simulation_pair_gravitation_conserves_momentum() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.01), None, None);
+		simulation.create_pair_interaction(
+			String::from("gravity"),
+			String::from("gravity"),
+			Box::new(simulation_objects::UniversalGravitation::new(f64::INFINITY, Some(1.0), None)),
+		);
+		let light_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			marker("gravity"),
+		);
+		let heavy_id = simulation.create_particle(
+			physical_quantities::Mass::new(30.0),
+			physical_quantities::Displacement::new(3.0, 4.0),
+			marker("gravity"),
+		);
+
+		for _ in 0..100 {
+			simulation.step();
+		}
+
+		let light_velocity = simulation.get_velocity(light_id);
+		let heavy_velocity = simulation.get_velocity(heavy_id);
+		// The particles were pulled toward each other.
+		assert!(light_velocity.x() > 0.0 && light_velocity.y() > 0.0);
+		assert!(heavy_velocity.x() < 0.0 && heavy_velocity.y() < 0.0);
+		// Total momentum is still zero.
+		assert!(numbers_are_almost_equal(
+			light_velocity.x() + 30.0 * heavy_velocity.x(),
+			0.0,
+			1.0e-12,
+		));
+		assert!(numbers_are_almost_equal(
+			light_velocity.y() + 30.0 * heavy_velocity.y(),
+			0.0,
+			1.0e-12,
+		));
+	}
+
+	// Verifies that a UniversalGravitationField with a filter only attracts
+	//	particles with a field of the given name.
+	#[test]
+	fn simulation_universal_gravitation_field_attracts_only_named_particles() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec![Box::new(simulation_objects::UniversalGravitationField::new(
+				10.0,
+				Some(1.0),
+				None,
+				Some(String::from("gravity")),
+				None,
+				Some(String::from("gravity")),
+			))],
+		);
+		let attracted_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(2.0, 0.0),
+			marker("gravity"),
+		);
+		let ignored_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(-2.0, 0.0),
+			marker("floor"),
+		);
+
+		simulation.step();
+		simulation.step();
+
+		assert_eq!(
+			physical_quantities::Velocity::new(-0.5, 0.0),
+			simulation.get_velocity(attracted_id),
+		);
+		assert_eq!(
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation.get_velocity(ignored_id),
+		);
+	}
+
+	// Verifies that a UniversalGravitationField with a layer filter only
+	//	attracts particles with a field on one of the given layers.
+	#[test]
+	fn simulation_universal_gravitation_field_attracts_only_tagged_particles() {
+		let massive = simulation_objects::Layers::layer(3);
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec![Box::new(simulation_objects::UniversalGravitationField::new(
+				10.0,
+				Some(1.0),
+				None,
+				None,
+				Some(massive),
+				None,
+			))],
+		);
+		let attracted_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(2.0, 0.0),
+			vec![Box::new(simulation_objects::Collider::new(0.1, 1.0, Some(massive), None))],
+		);
+		let ignored_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(-2.0, 0.0),
+			vec![Box::new(simulation_objects::Collider::new(0.1, 1.0, None, None))],
+		);
+
+		simulation.step();
+		simulation.step();
+
+		assert_eq!(
+			physical_quantities::Velocity::new(-0.5, 0.0),
+			simulation.get_velocity(attracted_id),
+		);
+		assert_eq!(
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation.get_velocity(ignored_id),
+		);
+	}
+
+	// Verifies that softening keeps the gravitational force bounded at small
+	//	separations and has little effect at large separations.
+	#[test]
+	fn universal_gravitation_softening_bounds_force() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let particle_id_a = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		let particle_id_b = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		simulation.step();
+
+		let softening_length = 1.0;
+		let softened = simulation_objects::UniversalGravitation::new(
+			f64::INFINITY,
+			Some(1.0),
+			Some(softening_length),
+		);
+		let unsoftened = simulation_objects::UniversalGravitation::new(
+			f64::INFINITY,
+			Some(1.0),
+			None,
+		);
+		let origin = physical_quantities::Displacement::new(0.0, 0.0);
+		let force_at = |interaction: &simulation_objects::UniversalGravitation, r: f64| {
+			simulation_objects::PairInteraction::force(
+				interaction,
+				&simulation,
+				particle_id_a,
+				origin,
+				particle_id_b,
+				physical_quantities::Displacement::new(r, 0.0),
+			).expect("Should produce a force.").x()
+		};
+
+		// The softened force never exceeds its peak of
+		//	G * m_1 * m_2 * 2 / (3 * sqrt(3) * ε^2), however close the
+		//	particles get.
+		let peak = 2.0 / (3.0 * 3.0_f64.sqrt() * softening_length.powf(2.0));
+		for r in [1.0e-9, 1.0e-3, 0.1, 0.5, 1.0 / 2.0_f64.sqrt(), 1.0, 2.0] {
+			assert!(force_at(&softened, r) <= peak + 1.0e-12);
+		}
+		assert!(force_at(&unsoftened, 1.0e-3) > 1.0e5);
+
+		// Far away, softening barely matters.
+		let unsoftened_far = force_at(&unsoftened, 1000.0);
+		assert!(numbers_are_almost_equal(
+			force_at(&softened, 1000.0),
+			unsoftened_far,
+			unsoftened_far * 1.0e-5,
+		));
+	}
+
+	#[test]
+	#[should_panic(expected = "softening_length must not be negative")]
+	fn universal_gravitation_new_panics_on_negative_softening_length() {
+		simulation_objects::UniversalGravitation::new(1.0, None, Some(-1.0));
+	}
+
+	/********************* Simulation: layers ********************/
+
+	// Deletes everything that triggers it. With affects_others set to false,
+	//	it's just a way to put a particle on some layers.
+	struct LayeredDeletionField {
+		affects_others: bool,
+		triggers_on_fields: bool,
+		layers: simulation_objects::Layers,
+		name: String,
+	}
+
+	impl LayeredDeletionField {
+		fn new(
+			affects_others: bool,
+			triggers_on_fields: bool,
+			layers: simulation_objects::Layers,
+		) -> Self {
+			LayeredDeletionField {
+				affects_others: affects_others,
+				triggers_on_fields: triggers_on_fields,
+				layers: layers,
+				name: String::from("LayeredDeletionField"),
+			}
+		}
+	}
+
+	impl simulation_objects::Field for LayeredDeletionField {
+		fn effect(
+			&self,
+			simulation: &Simulation,
+			_position: physical_quantities::Displacement,
+			triggered_by: HashMap<Uuid, Vec<Option<simulation_objects::FieldInfo>>>,
+			_field_owner_id: Uuid,
+		) {
+			for p in triggered_by.keys() {
+				simulation.delete_particle(*p);
+			}
+		}
+
+		fn get_radius(&self) -> f64 {
+			10.0
+		}
+
+		fn affects_self(&self) -> bool {
+			false
+		}
+
+		fn affects_others(&self) -> bool {
+			self.affects_others
+		}
+
+		fn triggers_on_fields(&self) -> bool {
+			self.triggers_on_fields
+		}
+
+		fn triggers_on_particles(&self) -> bool {
+			!self.triggers_on_fields
+		}
+
+		fn get_name(&self) -> &String {
+			&self.name
+		}
+
+		fn get_layers(&self) -> simulation_objects::Layers {
+			self.layers
+		}
+	}
+
+	#[test]
+	fn simulation_layer_interaction_defaults_to_all_layers() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		assert_eq!(simulation.get_layer_mask(0), simulation_objects::Layers::ALL);
+		assert_eq!(simulation.get_layer_mask(31), simulation_objects::Layers::ALL);
+
+		simulation.set_layer_interaction(3, 4, false);
+		assert!(!simulation.get_layer_mask(3).contains(4));
+		assert!(simulation.get_layer_mask(3).contains(3));
+		// Interactions are one-directional.
+		assert!(simulation.get_layer_mask(4).contains(3));
+
+		simulation.set_layer_interaction(3, 4, true);
+		assert_eq!(simulation.get_layer_mask(3), simulation_objects::Layers::ALL);
+
+		simulation.set_layer_mask(5, simulation_objects::Layers::layer(5));
+		assert_eq!(simulation.get_layer_mask(5), simulation_objects::Layers::layer(5));
+	}
+
+	#[test]
+	#[should_panic(expected = "Layer index must be less than 32: 40")]
+	fn simulation_set_layer_interaction_panics_on_invalid_layer() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.set_layer_interaction(1, 40, false);
+	}
+
+	// Verifies that particles on layers that can't trigger a field aren't
+	//	passed to its effect.
+	#[test]
+	fn simulation_layers_filter_particles_triggering_field() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.set_layer_interaction(1, 2, false);
+		let origin = physical_quantities::Displacement::new(0.0, 0.0);
+		let nearby = physical_quantities::Displacement::new(1.0, 0.0);
+		let deleter_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			origin,
+			vec!(Box::new(LayeredDeletionField::new(true, false, simulation_objects::Layers::layer(1)))),
+		);
+		let layer_2_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			nearby,
+			vec!(Box::new(LayeredDeletionField::new(false, false, simulation_objects::Layers::layer(2)))),
+		);
+		let layer_3_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			nearby,
+			vec!(Box::new(LayeredDeletionField::new(false, false, simulation_objects::Layers::layer(3)))),
+		);
+		// Particles without fields are on the default layer.
+		let default_layer_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			nearby,
+			Vec::new(),
+		);
+
+		simulation.step();
+		simulation.step();
+
+		let particles = simulation.particles.borrow();
+		assert!(particles.contains_key(&deleter_id));
+		assert!(particles.contains_key(&layer_2_id));
+		assert!(!particles.contains_key(&layer_3_id));
+		assert!(!particles.contains_key(&default_layer_id));
+	}
+
+	// Verifies that, for fields that trigger on fields, only overlapping
+	//	fields on layers that can trigger the field count.
+	#[test]
+	fn simulation_layers_filter_fields_triggering_field() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.set_layer_interaction(1, 2, false);
+		let origin = physical_quantities::Displacement::new(0.0, 0.0);
+		let nearby = physical_quantities::Displacement::new(1.0, 0.0);
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			origin,
+			vec!(Box::new(LayeredDeletionField::new(true, true, simulation_objects::Layers::layer(1)))),
+		);
+		let layer_2_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			nearby,
+			vec!(Box::new(LayeredDeletionField::new(false, false, simulation_objects::Layers::layer(2)))),
+		);
+		let both_layers_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			nearby,
+			vec!(
+				Box::new(LayeredDeletionField::new(false, false, simulation_objects::Layers::layer(2))),
+				Box::new(LayeredDeletionField::new(false, false, simulation_objects::Layers::layer(3))),
+			),
+		);
+
+		simulation.step();
+		simulation.step();
+
+		let particles = simulation.particles.borrow();
+		assert!(particles.contains_key(&layer_2_id));
+		assert!(!particles.contains_key(&both_layers_id));
+	}
+
+	// Verifies that colliders on layers that don't interact pass through each
+	//	other.
+	#[test]
+	fn simulation_colliders_on_non_interacting_layers_do_not_collide() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.01), None, None);
+		simulation.set_layer_interaction(1, 1, false);
+		let layers = Some(simulation_objects::Layers::layer(1));
+		let particle_id_a = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec!(Box::new(simulation_objects::Collider::new(1.0, 1.0, layers, None))),
+		);
+		let particle_id_b = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(3.0, 0.0),
+			vec!(Box::new(simulation_objects::Collider::new(1.0, 1.0, layers, None))),
+		);
+		simulation.step();
+		simulation.apply_force(particle_id_a, physical_quantities::Force::new(100.0, 0.0));
+		while simulation.get_elapsed_time() < physical_quantities::Time::new(3.0) {
+			simulation.step();
+		}
+
+		for event in simulation.drain_events() {
+			if let events::EventKind::CollisionResolved { .. } = event.get_kind() {
+				panic!("The colliders should not have collided.");
+			}
+		}
+		assert!(velocities_are_almost_equal(
+			physical_quantities::Velocity::new(1.0, 0.0),
+			simulation.get_velocity(particle_id_a),
+			1e-9,
+		));
+		assert_eq!(
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation.get_velocity(particle_id_b),
+		);
+	}
+
+	// Verifies that world fields only affect particles on layers that can
+	//	trigger them.
+	#[test]
+	fn simulation_layers_filter_particles_in_world_field() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.set_layer_mask(0, simulation_objects::Layers::layer(0));
+		simulation.create_world_field(
+			simulation_objects::Region::Everywhere,
+			Box::new(simulation_objects::UniformForceField::new(
+				physical_quantities::Force::new(1.0, 0.0),
+				None,
+			)),
+		);
+		let default_layer_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		let layer_4_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec!(Box::new(LayeredDeletionField::new(false, false, simulation_objects::Layers::layer(4)))),
+		);
+
+		simulation.step();
+		simulation.step();
+
+		assert_eq!(
+			physical_quantities::Velocity::new(1.0, 0.0),
+			simulation.get_velocity(default_layer_id),
+		);
+		assert_eq!(
+			physical_quantities::Velocity::new(0.0, 0.0),
+			simulation.get_velocity(layer_4_id),
+		);
+	}
+
+	/********************* Simulation: history ********************/
+
+	// Drops a new particle into the simulation every 7 ticks, so rewinding
+	//	has to recreate particles.
+	fn history_on_tick(simulation: &Simulation) {
+		let ticks = simulation.get_elapsed_ticks().get_number();
+		if ticks % 7 == 0 {
+			simulation.create_particle_with_velocity(
+				physical_quantities::Mass::new(1.0),
+				physical_quantities::Displacement::new(ticks as f64 * 0.1, 3.0),
+				physical_quantities::Velocity::new(0.0, -1.0),
+				vec![
+					Box::new(simulation_objects::Collider::new(0.5, 0.8, None, None)),
+					Box::new(simulation_objects::UniversalGravitationField::new(
+						10.0,
+						Some(1.0),
+						Some(0.1),
+						None,
+						None,
+						None,
+					)),
+				],
+			);
+		}
+	}
+
+	// Positions and velocities of every particle, keyed by ID.
+	fn history_state(simulation: &Simulation) -> Vec<(Uuid, f64, f64, f64, f64)> {
+		simulation.get_particle_ids().into_iter().map(|id| {
+			let p = simulation.get_position(id);
+			let v = simulation.get_velocity(id);
+			(id, p.x(), p.y(), v.x(), v.y())
+		}).collect()
+	}
+
+	fn history_simulation() -> Simulation {
+		let simulation = Simulation::new(
+			physical_quantities::Time::new(0.05),
+			None,
+			Some(history_on_tick),
+		);
+		simulation.create_world_field(
+			simulation_objects::Region::Everywhere,
+			Box::new(simulation_objects::LinearDragField::new(0.1, None)),
+		);
+		simulation
+	}
+
+	#[test]
+	fn rewind_to_resimulates_exactly() {
+		let simulation = history_simulation();
+		simulation.enable_history(physical_quantities::Ticks::new(10), usize::MAX);
+		let mut states = vec![history_state(&simulation)];
+		for _ in 0..60 {
+			simulation.step();
+			states.push(history_state(&simulation));
+		}
+
+		simulation.rewind_to(physical_quantities::Ticks::new(23));
+
+		assert_eq!(simulation.get_elapsed_ticks(), physical_quantities::Ticks::new(23));
+		assert_eq!(history_state(&simulation), states[23]);
+		for tick in 24..=60 {
+			simulation.step();
+			assert_eq!(history_state(&simulation), states[tick], "tick {}", tick);
+		}
+	}
+
+	#[test]
+	fn rewind_to_restores_wait_duration_across_tick_duration_change() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), Some(1.0), None);
+		simulation.enable_history(physical_quantities::Ticks::new(1), usize::MAX);
+		simulation.step();
+		simulation.step();
+		simulation.set_tick_duration(physical_quantities::Time::new(0.1));
+		simulation.step();
+		assert_eq!(*simulation.tick_wait_duration.borrow(), Duration::from_millis(100));
+
+		simulation.rewind_to(physical_quantities::Ticks::new(1));
+
+		assert_eq!(simulation.get_tick_duration(), physical_quantities::Time::new(0.5));
+		assert_eq!(*simulation.tick_wait_duration.borrow(), Duration::from_millis(500));
+	}
+
+	#[test]
+	fn rewind_to_can_rewind_repeatedly() {
+		let simulation = history_simulation();
+		simulation.enable_history(physical_quantities::Ticks::new(4), usize::MAX);
+		let mut states = vec![history_state(&simulation)];
+		for _ in 0..30 {
+			simulation.step();
+			states.push(history_state(&simulation));
+		}
+
+		simulation.rewind_to(physical_quantities::Ticks::new(17));
+		simulation.rewind_to(physical_quantities::Ticks::new(9));
+		assert_eq!(history_state(&simulation), states[9]);
+		simulation.rewind_to(physical_quantities::Ticks::new(9));
+		assert_eq!(history_state(&simulation), states[9]);
+		simulation.rewind_to(physical_quantities::Ticks::new(0));
+		assert_eq!(history_state(&simulation), states[0]);
+	}
+
+	#[test]
+	fn rewind_to_does_not_repeat_events() {
+		let simulation = history_simulation();
+		simulation.enable_history(physical_quantities::Ticks::new(10), usize::MAX);
+		for _ in 0..20 {
+			simulation.step();
+		}
+		simulation.drain_events();
+
+		simulation.rewind_to(physical_quantities::Ticks::new(15));
+
+		assert!(simulation.drain_events().is_empty());
+	}
+
+	#[test]
+	fn memory_budget_discards_oldest_snapshots() {
+		let simulation = history_simulation();
+		simulation.enable_history(physical_quantities::Ticks::new(1), usize::MAX);
+		simulation.step();
+		assert_eq!(simulation.get_earliest_rewind_tick(), Some(physical_quantities::Ticks::new(0)));
+
+		// Enough for a few snapshots, but not all of them.
+		simulation.enable_history(physical_quantities::Ticks::new(1), 8 * size_of::<Snapshot>());
+		for _ in 0..20 {
+			simulation.step();
+		}
+
+		let earliest = simulation.get_earliest_rewind_tick().expect("Should have snapshots.");
+		assert!(earliest > physical_quantities::Ticks::new(1));
+		assert!(earliest < physical_quantities::Ticks::new(21));
+		simulation.rewind_to(earliest);
+		assert_eq!(simulation.get_elapsed_ticks(), earliest);
+	}
+
+	#[test]
+	fn disable_history_discards_snapshots() {
+		let simulation = history_simulation();
+		simulation.enable_history(physical_quantities::Ticks::new(1), usize::MAX);
+		simulation.step();
+		simulation.disable_history();
+
+		assert_eq!(simulation.get_earliest_rewind_tick(), None);
+	}
+
+	#[test]
+	#[should_panic(expected = "Simulation.rewind_to(): history is not enabled")]
+	fn rewind_to_panics_without_history() {
+		let simulation = history_simulation();
+		simulation.step();
+		simulation.rewind_to(physical_quantities::Ticks::new(0));
+	}
+
+	#[test]
+	#[should_panic(expected = "which is after the current tick")]
+	fn rewind_to_panics_on_future_tick() {
+		let simulation = history_simulation();
+		simulation.enable_history(physical_quantities::Ticks::new(1), usize::MAX);
+		simulation.step();
+		simulation.rewind_to(physical_quantities::Ticks::new(2));
+	}
+
+	#[test]
+	#[should_panic(expected = "tick 3 is before the earliest snapshot")]
+	fn rewind_to_panics_before_earliest_snapshot() {
+		let simulation = history_simulation();
+		for _ in 0..5 {
+			simulation.step();
+		}
+		simulation.enable_history(physical_quantities::Ticks::new(1), usize::MAX);
+		simulation.step();
+		simulation.rewind_to(physical_quantities::Ticks::new(3));
+	}
+
+	#[test]
+	#[should_panic(expected = "interval must be at least one tick")]
+	fn enable_history_panics_on_zero_interval() {
+		let simulation = history_simulation();
+		simulation.enable_history(physical_quantities::Ticks::new(0), usize::MAX);
+	}
+
+	/********************* Simulation: diagnostics ********************/
+
+	// Reports a fixed potential energy.
+	struct ConstantEnergyField {
+		energy: f64,
+		name: String,
+	}
+
+	impl simulation_objects::Field for ConstantEnergyField {
+		fn effect(
+			&self,
+			_simulation: &Simulation,
+			_position: physical_quantities::Displacement,
+			_triggered_by: HashMap<Uuid, Vec<Option<simulation_objects::FieldInfo>>>,
+			_field_owner_id: Uuid,
+		) {
+		}
+
+		fn get_radius(&self) -> f64 {
+			0.0
+		}
+
+		fn affects_self(&self) -> bool {
+			false
+		}
+
+		fn affects_others(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_fields(&self) -> bool {
+			false
+		}
+
+		fn triggers_on_particles(&self) -> bool {
+			false
+		}
+
+		fn get_name(&self) -> &String {
+			&self.name
+		}
+
+		fn potential_energy(
+			&self,
+			_simulation: &Simulation,
+			_position: physical_quantities::Displacement,
+			_field_owner_id: Uuid,
+		) -> Option<physical_quantities::Energy> {
+			Some(physical_quantities::Energy::new(self.energy))
+		}
+	}
+
+	fn two_moving_particles() -> Simulation {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(1.0, 0.0),
+			physical_quantities::Velocity::new(0.0, 2.0),
+			Vec::new(),
+		);
+		simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(3.0),
+			physical_quantities::Displacement::new(-1.0, 4.0),
+			physical_quantities::Velocity::new(-2.0, 0.0),
+			Vec::new(),
+		);
+		simulation.step();
+		simulation
+	}
+
+	#[test]
+	fn simulation_gets_kinetic_energy_and_momentum() {
+		let simulation = two_moving_particles();
+
+		assert_eq!(simulation.get_kinetic_energy(), physical_quantities::Energy::new(8.0));
+		assert_eq!(
+			simulation.get_linear_momentum(),
+			physical_quantities::Momentum::new(-6.0, 2.0),
+		);
+		// (1 * 1 * 2 - 2 * 1 * 0) + (-3 * 3 * 0 - 4 * 3 * -2) = 2 + 24
+		assert_eq!(
+			simulation.get_angular_momentum(physical_quantities::Displacement::new(0.0, 0.0)),
+			physical_quantities::AngularMomentum::new(26.0),
+		);
+		// About (1, 2), the first particle is moving straight away from the
+		//	point and contributes nothing: (-2 * 0) - (2 * -6) = 12
+		assert_eq!(
+			simulation.get_angular_momentum(physical_quantities::Displacement::new(1.0, 2.0)),
+			physical_quantities::AngularMomentum::new(12.0),
+		);
+	}
+
+	#[test]
+	fn simulation_gets_center_of_mass() {
+		let simulation = two_moving_particles();
+
+		assert_eq!(
+			simulation.get_center_of_mass(),
+			Some(physical_quantities::Displacement::new(-0.5, 3.0)),
+		);
+		assert_eq!(
+			simulation.get_center_of_mass_velocity(),
+			Some(physical_quantities::Velocity::new(-1.5, 0.5)),
+		);
+	}
+
+	#[test]
+	fn simulation_diagnostics_handle_no_particles() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+
+		assert_eq!(simulation.get_kinetic_energy(), physical_quantities::Energy::new(0.0));
+		assert_eq!(simulation.get_potential_energy(), physical_quantities::Energy::new(0.0));
+		assert_eq!(simulation.get_linear_momentum(), physical_quantities::Momentum::new(0.0, 0.0));
+		assert_eq!(simulation.get_center_of_mass(), None);
+		assert_eq!(simulation.get_center_of_mass_velocity(), None);
+	}
+
+	#[test]
+	fn simulation_gets_potential_energy_from_fields() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec![Box::new(ConstantEnergyField { energy: 2.5, name: String::from("e") })],
+		);
+		simulation.create_world_field(
+			simulation_objects::Region::Everywhere,
+			Box::new(ConstantEnergyField { energy: -1.0, name: String::from("e") }),
+		);
+		simulation.step();
+
+		assert_eq!(simulation.get_potential_energy(), physical_quantities::Energy::new(1.5));
+		assert_eq!(simulation.get_total_energy(), physical_quantities::Energy::new(1.5));
+	}
+
+	fn gravity_field() -> Box<dyn simulation_objects::Field> {
+		Box::new(simulation_objects::UniversalGravitationField::new(
+			f64::INFINITY,
+			Some(1.0),
+			None,
+			None,
+			None,
+			None,
+		))
+	}
+
+	#[test]
+	fn universal_gravitation_field_counts_mutual_pairs_once() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec![gravity_field()],
+		);
+		simulation.create_particle(
+			physical_quantities::Mass::new(3.0),
+			physical_quantities::Displacement::new(4.0, 0.0),
+			vec![gravity_field()],
+		);
+		simulation.step();
+
+		// -G * m_1 * m_2 / r
+		assert_eq!(simulation.get_potential_energy(), physical_quantities::Energy::new(-1.5));
+	}
+
+	#[test]
+	fn universal_gravitation_field_counts_one_sided_pairs_fully() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec![gravity_field()],
+		);
+		simulation.create_particle(
+			physical_quantities::Mass::new(3.0),
+			physical_quantities::Displacement::new(4.0, 0.0),
+			Vec::new(),
+		);
+		simulation.create_particle(
+			physical_quantities::Mass::new(3.0),
+			physical_quantities::Displacement::new(0.0, 2.0),
+			marker("not gravity"),
+		);
+		simulation.step();
+
+		assert_eq!(simulation.get_potential_energy(), physical_quantities::Energy::new(-4.5));
+	}
+
+	#[test]
+	fn universal_gravitation_field_potential_respects_attracts_only() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec![Box::new(simulation_objects::UniversalGravitationField::new(
+				f64::INFINITY,
+				Some(1.0),
+				None,
+				Some(String::from("massive")),
+				None,
+				None,
+			))],
+		);
+		simulation.create_particle(
+			physical_quantities::Mass::new(3.0),
+			physical_quantities::Displacement::new(4.0, 0.0),
+			Vec::new(),
+		);
+		simulation.create_particle(
+			physical_quantities::Mass::new(3.0),
+			physical_quantities::Displacement::new(0.0, 2.0),
+			marker("massive"),
+		);
+		simulation.step();
+
+		assert_eq!(simulation.get_potential_energy(), physical_quantities::Energy::new(-3.0));
+	}
+
+	#[test]
+	fn universal_gravitation_pair_interaction_conserves_energy() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.001), None, None);
+		simulation.create_pair_interaction(
+			String::from("body"),
+			String::from("body"),
+			Box::new(simulation_objects::UniversalGravitation::new(
+				f64::INFINITY,
+				Some(1.0),
+				None,
+			)),
+		);
+		simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(-0.5, 0.0),
+			physical_quantities::Velocity::new(0.0, -0.6),
+			marker("body"),
+		);
+		simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.5, 0.0),
+			physical_quantities::Velocity::new(0.0, 0.6),
+			marker("body"),
+		);
+		simulation.step();
+
+		// -G * m_1 * m_2 / r + 2 * (1/2 * m * v^2)
+		let initial_energy = simulation.get_total_energy();
+		assert!((initial_energy.get_number() - (-1.0 + 0.36)).abs() < 1.0e-12);
+		let initial_momentum = simulation.get_angular_momentum(
+			simulation.get_center_of_mass().expect("Should have particles."),
+		);
+		for _ in 0..2000 {
+			simulation.step();
+		}
+
+		// The integrator isn't symplectic, so energy and angular momentum
+		//	drift a little.
+		let drift = (simulation.get_total_energy() - initial_energy).get_number();
+		assert!(drift.abs() < 0.02 * initial_energy.get_number().abs(), "{}", drift);
+		let momentum = simulation.get_angular_momentum(
+			simulation.get_center_of_mass().expect("Should have particles."),
+		);
+		let change = (momentum - initial_momentum).get_number();
+		assert!(change.abs() < 0.01 * initial_momentum.get_number().abs(), "{}", change);
+		assert!(simulation.get_linear_momentum().get_magnitude() < 1.0e-12);
+	}
+
+	/********************* Simulation: advance ********************/
+
+	fn moving_particle(sim: &Simulation) -> Uuid {
+		let id = sim.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(4.0, 0.0),
+			Vec::new(),
+		);
+		sim.step();
+		id
+	}
+
+	#[test]
+	fn advance_runs_ticks_for_elapsed_time() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.25), Some(1.0), None);
+
+		let advance = sim.advance(Duration::from_millis(800));
+
+		assert_eq!(advance.get_ticks(), physical_quantities::Ticks::new(3));
+		assert!((advance.get_alpha() - 0.2).abs() < 1e-9);
+		assert_eq!(sim.get_elapsed_ticks(), physical_quantities::Ticks::new(3));
+	}
+
+	#[test]
+	fn advance_carries_over_partial_ticks() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.25), Some(1.0), None);
+
+		let first = sim.advance(Duration::from_millis(150));
+		let second = sim.advance(Duration::from_millis(150));
+
+		assert_eq!(first.get_ticks(), physical_quantities::Ticks::new(0));
+		assert!((first.get_alpha() - 0.6).abs() < 1e-9);
+		assert_eq!(second.get_ticks(), physical_quantities::Ticks::new(1));
+		assert!((second.get_alpha() - 0.2).abs() < 1e-9);
+	}
+
+	#[test]
+	fn advance_uses_simulation_speed() {
+		let fast = Simulation::new(physical_quantities::Time::new(0.25), Some(2.0), None);
+		let unpaced = Simulation::new(physical_quantities::Time::new(0.25), None, None);
+
+		assert_eq!(
+			fast.advance(Duration::from_millis(500)).get_ticks(),
+			physical_quantities::Ticks::new(4),
+		);
+		assert_eq!(
+			unpaced.advance(Duration::from_millis(500)).get_ticks(),
+			physical_quantities::Ticks::new(2),
+		);
+	}
+
+	#[test]
+	fn advance_drops_ticks_beyond_cap() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.25), Some(1.0), None);
+		sim.set_max_ticks_per_advance(physical_quantities::Ticks::new(2));
+
+		let slow_frame = sim.advance(Duration::from_millis(1100));
+		let next_frame = sim.advance(Duration::from_millis(0));
+
+		assert_eq!(slow_frame.get_ticks(), physical_quantities::Ticks::new(2));
+		assert!((slow_frame.get_alpha() - 0.4).abs() < 1e-9);
+		assert_eq!(next_frame.get_ticks(), physical_quantities::Ticks::new(0));
+		assert_eq!(sim.get_elapsed_ticks(), physical_quantities::Ticks::new(2));
+	}
+
+	#[test]
+	fn max_ticks_per_advance_defaults() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.25), Some(1.0), None);
+
+		assert_eq!(
+			sim.get_max_ticks_per_advance(),
+			physical_quantities::Ticks::new(Simulation::<f64>::DEFAULT_MAX_TICKS_PER_ADVANCE),
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "max_ticks must be at least one tick")]
+	fn set_max_ticks_per_advance_panics_on_zero() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.25), Some(1.0), None);
+		sim.set_max_ticks_per_advance(physical_quantities::Ticks::new(0));
+	}
+
+	#[test]
+	fn simulation_gets_previous_position() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		let id = moving_particle(&sim);
+		assert_eq!(sim.get_previous_position(id), physical_quantities::Displacement::new(0.0, 0.0));
+
+		sim.step();
+		assert_eq!(sim.get_previous_position(id), physical_quantities::Displacement::new(0.0, 0.0));
+		assert_eq!(sim.get_position(id), physical_quantities::Displacement::new(2.0, 0.0));
+
+		sim.step();
+		assert_eq!(sim.get_previous_position(id), physical_quantities::Displacement::new(2.0, 0.0));
+	}
+
+	#[test]
+	fn simulation_interpolates_position() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		let id = moving_particle(&sim);
+		sim.step();
+
+		assert_eq!(
+			sim.get_interpolated_position(id, 0.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+		);
+		assert_eq!(
+			sim.get_interpolated_position(id, 0.25),
+			physical_quantities::Displacement::new(0.5, 0.0),
+		);
+		assert_eq!(
+			sim.get_interpolated_position(id, 1.0),
+			physical_quantities::Displacement::new(2.0, 0.0),
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "Simulation.get_previous_position(): the provided particle ID was not found")]
+	fn get_previous_position_panics_on_unknown_particle() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		sim.get_previous_position(Uuid::new_v4());
+	}
+
+	#[test]
+	#[should_panic(expected = "Simulation.get_interpolated_position(): the provided particle ID was not found")]
+	fn get_interpolated_position_panics_on_unknown_particle() {
+		let sim = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		sim.get_interpolated_position(Uuid::new_v4(), 0.5);
+	}
+
+	/********************* Simulation: scheduling ********************/
+
+	fn pause_after_ten_ticks(simulation: &Simulation) {
+		if simulation.get_elapsed_ticks() >= physical_quantities::Ticks::new(9) {
+			simulation.pause();
+		}
+	}
+
+	fn slow_tick(simulation: &Simulation) {
+		thread::sleep(Duration::from_millis(3));
+		if simulation.get_elapsed_ticks() >= physical_quantities::Ticks::new(4) {
+			simulation.pause();
+		}
+	}
+
+	// Runs ten ticks of 2 ms at 1x speed with the given strategy.
+	fn run_ten_paced_ticks(strategy: SchedulingStrategy) -> (TimingStats, Duration) {
+		let simulation = Simulation::new(
+			physical_quantities::Time::new(0.002),
+			Some(1.0),
+			Some(pause_after_ten_ticks),
+		);
+		simulation.set_scheduling_strategy(strategy);
+
+		let started = Instant::now();
+		simulation.start();
+		(simulation.get_timing_stats(), started.elapsed())
+	}
+
+	#[test]
+	fn scheduling_strategy_defaults_to_busy_wait() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), Some(1.0), None);
+
+		assert_eq!(simulation.get_scheduling_strategy(), SchedulingStrategy::BusyWait);
+
+		simulation.set_scheduling_strategy(SchedulingStrategy::Sleep);
+		assert_eq!(simulation.get_scheduling_strategy(), SchedulingStrategy::Sleep);
+	}
+
+	#[test]
+	fn every_scheduling_strategy_keeps_pace() {
+		for strategy in [
+			SchedulingStrategy::BusyWait,
+			SchedulingStrategy::Sleep,
+			SchedulingStrategy::Hybrid { spin: Duration::from_millis(1) },
+		] {
+			let (stats, elapsed) = run_ten_paced_ticks(strategy);
+
+			// The first tick is immediate and nine more follow, 2 ms apart.
+			assert_eq!(stats.get_ticks(), physical_quantities::Ticks::new(10), "{:?}", strategy);
+			assert!(elapsed >= Duration::from_millis(18), "{:?}: {:?}", strategy, elapsed);
+			assert!(stats.get_ticks_per_second() <= 10.0 / 0.018, "{:?}", strategy);
+			assert!(stats.get_ticks_per_second() > 0.0, "{:?}", strategy);
+		}
+	}
+
+	#[test]
+	fn unpaced_start_reports_no_lag() {
+		let simulation = Simulation::new(
+			physical_quantities::Time::new(1.0),
+			None,
+			Some(pause_after_ten_ticks),
+		);
+		simulation.set_scheduling_strategy(SchedulingStrategy::Sleep);
+
+		simulation.start();
+		let stats = simulation.get_timing_stats();
+
+		assert_eq!(stats.get_ticks(), physical_quantities::Ticks::new(10));
+		assert_eq!(stats.get_average_lag(), Duration::ZERO);
+		assert_eq!(stats.get_overrun_ticks(), physical_quantities::Ticks::new(0));
+	}
+
+	#[test]
+	fn slow_ticks_are_reported_as_overruns() {
+		let simulation = Simulation::new(
+			physical_quantities::Time::new(0.001),
+			Some(1.0),
+			Some(slow_tick),
+		);
+
+		simulation.start();
+		let stats = simulation.get_timing_stats();
+
+		// Every tick takes 3 ms but only 1 ms is allowed, so every tick after
+		//	the first starts at least 2 ms late.
+		assert_eq!(stats.get_ticks(), physical_quantities::Ticks::new(5));
+		assert_eq!(stats.get_overrun_ticks(), physical_quantities::Ticks::new(5));
+		assert!(stats.get_average_lag() >= Duration::from_micros(1600));
+	}
+
+	#[test]
+	fn timing_stats_accumulate_and_reset() {
+		let simulation = Simulation::new(
+			physical_quantities::Time::new(1.0),
+			None,
+			Some(pause_after_ten_ticks),
+		);
+		assert_eq!(simulation.get_timing_stats(), TimingStats::default());
+		assert_eq!(simulation.get_timing_stats().get_ticks_per_second(), 0.0);
+
+		simulation.start();
+		simulation.start();
+		assert_eq!(simulation.get_timing_stats().get_ticks(), physical_quantities::Ticks::new(11));
+
+		simulation.reset_timing_stats();
+		assert_eq!(simulation.get_timing_stats(), TimingStats::default());
+	}
+
+	/********************* Simulation: runtime timing changes ********************/
+
+	fn shorten_ticks_on_tick_two(simulation: &Simulation) {
+		if simulation.get_elapsed_ticks() == physical_quantities::Ticks::new(2) {
+			simulation.set_tick_duration(physical_quantities::Time::new(0.25));
+		}
+	}
+
+	#[test]
+	fn elapsed_time_accumulates_across_tick_duration_changes() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		for _ in 0..4 {
+			simulation.step();
+		}
+		simulation.set_tick_duration(physical_quantities::Time::new(0.25));
+		assert_eq!(simulation.get_tick_duration(), physical_quantities::Time::new(0.25));
+		assert_eq!(simulation.get_elapsed_time(), physical_quantities::Time::new(2.0));
+
+		for _ in 0..4 {
+			simulation.step();
+		}
+		assert_eq!(simulation.get_elapsed_time(), physical_quantities::Time::new(3.0));
+	}
+
+	#[test]
+	fn tick_duration_set_during_tick_applies_from_next_tick() {
+		let simulation = Simulation::new(
+			physical_quantities::Time::new(0.5),
+			None,
+			Some(shorten_ticks_on_tick_two),
+		);
+		let id = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(1.0, 0.0),
+			Vec::new(),
+		);
+		simulation.step();
+		simulation.step();
+
+		// The tick that changes the duration still moves the particle by a
+		//	whole 0.5 seconds' worth.
+		simulation.step();
+		assert_eq!(simulation.get_tick_duration(), physical_quantities::Time::new(0.25));
+		assert_eq!(simulation.get_elapsed_time(), physical_quantities::Time::new(1.5));
+		assert_eq!(simulation.get_position(id), physical_quantities::Displacement::new(1.0, 0.0));
+
+		simulation.step();
+		assert_eq!(simulation.get_elapsed_time(), physical_quantities::Time::new(1.75));
+		assert_eq!(simulation.get_position(id), physical_quantities::Displacement::new(1.25, 0.0));
+	}
+
+	#[test]
+	fn timing_changes_recalculate_wait_duration() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), Some(1.0), None);
+		assert_eq!(*simulation.tick_wait_duration.borrow(), Duration::from_millis(500));
+
+		simulation.set_simulation_speed(Some(2.0));
+		assert_eq!(simulation.get_simulation_speed(), Some(2.0));
+		assert_eq!(*simulation.tick_wait_duration.borrow(), Duration::from_millis(250));
+
+		simulation.set_tick_duration(physical_quantities::Time::new(0.1));
+		assert_eq!(*simulation.tick_wait_duration.borrow(), Duration::from_millis(50));
+
+		simulation.set_simulation_speed(None);
+		assert_eq!(simulation.get_simulation_speed(), None);
+		assert_eq!(*simulation.tick_wait_duration.borrow(), Duration::ZERO);
+	}
+
+	#[test]
+	fn advance_uses_changed_simulation_speed() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.25), Some(1.0), None);
+		simulation.set_simulation_speed(Some(0.5));
+
+		assert_eq!(
+			simulation.advance(Duration::from_secs(1)).get_ticks(),
+			physical_quantities::Ticks::new(2),
+		);
+	}
+
+	#[test]
+	fn rewind_replays_tick_duration_changes() {
+		let simulation = Simulation::new(
+			physical_quantities::Time::new(0.5),
+			None,
+			Some(shorten_ticks_on_tick_two),
+		);
+		simulation.enable_history(physical_quantities::Ticks::new(1), 1 << 20);
+		for _ in 0..6 {
+			simulation.step();
+		}
+		let elapsed_time = simulation.get_elapsed_time();
+
+		simulation.rewind_to(physical_quantities::Ticks::new(1));
+		assert_eq!(simulation.get_tick_duration(), physical_quantities::Time::new(0.5));
+		assert_eq!(simulation.get_elapsed_time(), physical_quantities::Time::new(0.5));
+
+		for _ in 0..5 {
+			simulation.step();
+		}
+		assert_eq!(simulation.get_elapsed_time(), elapsed_time);
+	}
+
+	#[test]
+	#[should_panic(expected = "tick_duration must be positive")]
+	fn set_tick_duration_panics_on_zero() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		simulation.set_tick_duration(physical_quantities::Time::new(0.0));
+	}
+
+	#[test]
+	#[should_panic(expected = "simulation_speed must be positive")]
+	fn set_simulation_speed_panics_on_negative_speed() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		simulation.set_simulation_speed(Some(-1.0));
+	}
+
+	/********************* Simulation: three dimensions ********************/
+
+	#[test]
+	fn three_dimensional_projectile_follows_parabola() {
+		let simulation = Simulation3::new(physical_quantities::Time::new(0.1), None, None);
+		let id = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement3::new(0.0, 0.0, 0.0),
+			physical_quantities::Velocity3::new(1.0, 2.0, 3.0),
+			vec!(Box::new(simulation_objects::SimpleSelfGravityField::new(
+				physical_quantities::Acceleration3::new(0.0, 0.0, -9.8),
+				None,
+			))),
+		);
+
+		// The particle is added at the end of the first tick, so it moves for
+		//	ten ticks, or one second.
+		for _ in 0..11 {
+			simulation.step();
+		}
+
+		let position = simulation.get_position(id);
+		let velocity = simulation.get_velocity(id);
+		crate::assert_approx_eq!(
+			position,
+			physical_quantities::Displacement3::new(1.0, 2.0, 3.0 - 0.5 * 9.8),
+			testing::Tolerance::Absolute(1e-9),
+		);
+		assert!((velocity.z() - (3.0 - 9.8)).abs() < 1e-9, "{:?}", velocity);
+	}
+
+	#[test]
+	fn three_dimensional_collision_exchanges_velocities() {
+		let simulation = Simulation3::new(physical_quantities::Time::new(0.01), None, None);
+		let a = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement3::new(0.0, 0.0, 0.0),
+			physical_quantities::Velocity3::new(0.0, 1.0, 1.0),
+			vec!(Box::new(simulation_objects::Collider::new(0.5, 1.0, None, None))),
+		);
+		let b = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement3::new(0.0, 2.0, 2.0),
+			physical_quantities::Velocity3::new(0.0, -1.0, -1.0),
+			vec!(Box::new(simulation_objects::Collider::new(0.5, 1.0, None, None))),
+		);
+
+		for _ in 0..200 {
+			simulation.step();
+		}
+
+		let velocity_a = simulation.get_velocity(a);
+		let velocity_b = simulation.get_velocity(b);
+		let error_a = velocity_a - physical_quantities::Velocity3::new(0.0, -1.0, -1.0);
+		let error_b = velocity_b - physical_quantities::Velocity3::new(0.0, 1.0, 1.0);
+		assert!(error_a.get_magnitude() < 1e-9, "{:?}", velocity_a);
+		assert!(error_b.get_magnitude() < 1e-9, "{:?}", velocity_b);
+	}
+
+	#[test]
+	fn three_dimensional_orbit_stays_in_its_plane() {
+		// A light particle in a circular orbit around a heavy one, in a plane
+		//	tilted away from the xy plane.
+		let simulation = Simulation3::new(physical_quantities::Time::new(0.001), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(1000.0),
+			physical_quantities::Displacement3::new(0.0, 0.0, 0.0),
+			vec!(Box::new(simulation_objects::MarkerField::new(String::from("gravity")))),
+		);
+		let speed = 1000.0_f64.sqrt();
+		simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement3::new(1.0, 0.0, 0.0),
+			physical_quantities::Velocity3::new(0.0, 0.6 * speed, 0.8 * speed),
+			vec!(Box::new(simulation_objects::MarkerField::new(String::from("gravity")))),
+		);
+		simulation.create_pair_interaction(
+			String::from("gravity"),
+			String::from("gravity"),
+			Box::new(simulation_objects::UniversalGravitation::new(f64::INFINITY, Some(1.0), None)),
+		);
+		simulation.step();
+
+		let origin = physical_quantities::Displacement3::new(0.0, 0.0, 0.0);
+		let initial_momentum = simulation.get_angular_momentum(origin);
+		for _ in 0..1000 {
+			simulation.step();
+		}
+		let final_momentum = simulation.get_angular_momentum(origin);
+
+		// The orbit stays in its plane, so the angular momentum keeps its
+		//	direction. Its magnitude drifts because of the integrator.
+		let direction_change =
+			(final_momentum.get_unit_vector() - initial_momentum.get_unit_vector()).get_magnitude();
+		assert!(direction_change < 1e-9, "{:?} {:?}", initial_momentum, final_momentum);
+	}
+
+	#[test]
+	fn three_dimensional_world_field_uses_sphere_region() {
+		let simulation = Simulation3::new(physical_quantities::Time::new(1.0), None, None);
+		let inside = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement3::new(0.0, 0.0, 0.5),
+			Vec::new(),
+		);
+		let outside = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement3::new(0.0, 0.0, 1.5),
+			Vec::new(),
+		);
+		simulation.create_world_field(
+			simulation_objects::Region::Circle {
+				center: physical_quantities::Displacement3::new(0.0, 0.0, 0.0),
+				radius: 1.0,
+			},
+			Box::new(simulation_objects::UniformForceField::new(
+				physical_quantities::Force3::new(0.0, 0.0, 2.0),
+				None,
+			)),
+		);
+		simulation.step();
+		simulation.step();
+
+		assert_eq!(simulation.get_velocity(inside), physical_quantities::Velocity3::new(0.0, 0.0, 2.0));
+		assert_eq!(simulation.get_velocity(outside), physical_quantities::Velocity3::new(0.0, 0.0, 0.0));
+	}
+
+	/********************* Simulation: unit systems ********************/
+
+	#[test]
+	fn simulation_starts_in_si_units() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		assert_eq!(simulation.get_unit_system(), units::UnitSystem::SI);
+	}
+
+	#[test]
+	fn gravity_defaults_to_g_in_the_simulations_units() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		simulation.set_unit_system(units::UnitSystem::ASTRONOMICAL);
+		// The Sun, and a planet 1 AU away.
+		simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			vec![Box::new(simulation_objects::UniversalGravitationField::new(10.0, None, None, None, None, None))],
+		);
+		let planet_id = simulation.create_particle(
+			physical_quantities::Mass::new(3.0e-6),
+			physical_quantities::Displacement::new(1.0, 0.0),
+			Vec::new(),
+		);
+		simulation.step();
+		simulation.step();
+
+		// a = G M / r^2 = G, for half a year.
+		let big_g = units::UnitSystem::ASTRONOMICAL.get_gravitational_constant();
+		assert_eq!(simulation.get_velocity(planet_id), physical_quantities::Velocity::new(-big_g * 0.5, 0.0));
+		assert!((big_g - 39.48).abs() < 0.1, "{}", big_g);
+	}
+
+	/********************* Simulation: rotation ********************/
+
+	fn create_spinning_particle(simulation: &Simulation) -> Uuid {
+		let particle_id = simulation.create_particle_with_rotation(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(1.0, 0.0),
+			physical_quantities::Velocity::new(0.0, 2.0),
+			simulation_objects::Rotation::new(
+				physical_quantities::Angle::new(0.0),
+				physical_quantities::AngularVelocity::new(1.0),
+				physical_quantities::MomentOfInertia::new(2.0),
+			),
+			Vec::new(),
+		);
+		simulation.step();
+		particle_id
+	}
+
+	#[test]
+	fn simulation_applies_torque() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let particle_id = create_spinning_particle(&simulation);
+		assert_eq!(simulation.get_orientation(particle_id), physical_quantities::Angle::new(0.0));
+
+		// α = τ / I = 2, so ω = 1 + 2 and θ = 1 + 1/2 * 2.
+		simulation.apply_torque(particle_id, physical_quantities::Torque::new(1.0));
+		simulation.apply_torque(particle_id, physical_quantities::Torque::new(3.0));
+		simulation.step();
+		assert_eq!(simulation.get_angular_velocity(particle_id), physical_quantities::AngularVelocity::new(3.0));
+		assert_eq!(simulation.get_orientation(particle_id), physical_quantities::Angle::new(2.0));
+
+		// Torques only last one tick.
+		simulation.step();
+		assert_eq!(simulation.get_angular_velocity(particle_id), physical_quantities::AngularVelocity::new(3.0));
+		assert_eq!(simulation.get_orientation(particle_id), physical_quantities::Angle::new(5.0));
+	}
+
+	#[test]
+	fn simulation_ignores_torque_without_rotation() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		let particle_id = simulation.create_particle(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			Vec::new(),
+		);
+		simulation.step();
+
+		simulation.apply_torque(particle_id, physical_quantities::Torque::new(1.0));
+		simulation.step();
+		assert_eq!(simulation.get_rotation(particle_id), None);
+		assert_eq!(simulation.get_orientation(particle_id), physical_quantities::Angle::new(0.0));
+		assert_eq!(simulation.get_angular_velocity(particle_id), physical_quantities::AngularVelocity::new(0.0));
+	}
+
+	#[test]
+	#[should_panic(expected = "the provided particle ID was not found: ")]
+	fn simulation_apply_torque_panics_on_missing_id() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		simulation.apply_torque(Uuid::new_v4(), physical_quantities::Torque::new(1.0));
+	}
+
+	#[test]
+	fn simulation_includes_spin_in_energy_and_angular_momentum() {
+		let simulation = Simulation::new(physical_quantities::Time::new(1.0), None, None);
+		create_spinning_particle(&simulation);
+
+		// 1/2 m v^2 + 1/2 I ω^2 = 2 + 1.
+		assert_eq!(simulation.get_kinetic_energy(), physical_quantities::Energy::new(3.0));
+		// The particle is at (1, 0) moving up at 2, so r x p = 1 * 2, plus I ω = 2.
+		assert_eq!(
+			simulation.get_angular_momentum(physical_quantities::Displacement::new(0.0, 0.0)),
+			physical_quantities::AngularMomentum::new(4.0),
+		);
+	}
+
+	#[test]
+	fn rewind_to_restores_rotation_and_pending_torques() {
+		let simulation = Simulation::new(physical_quantities::Time::new(0.5), None, None);
+		simulation.enable_history(physical_quantities::Ticks::new(1), usize::MAX);
+		let particle_id = create_spinning_particle(&simulation);
+		simulation.apply_torque(particle_id, physical_quantities::Torque::new(1.0));
+		let mut states = Vec::new();
+		for _ in 0..3 {
+			states.push(simulation.get_rotation(particle_id));
+			simulation.step();
+		}
+
+		simulation.rewind_to(physical_quantities::Ticks::new(1));
+		assert_eq!(simulation.get_rotation(particle_id), states[0]);
+		simulation.step();
+		assert_eq!(simulation.get_rotation(particle_id), states[1]);
+	}
+
+	/********************* Simulation: scalar types ********************/
+
+	// Runs an orbit like the one in three_dimensional_orbit_stays_in_its_plane
+	//	(but in two dimensions) with the given scalar type and returns the
+	//	light particle's position and the total energy, as f64s.
+	fn run_orbit<S: physical_quantities::Scalar>() -> (f64, f64, f64) {
+		let number = S::from_f64;
+		let simulation: Simulation<S> =
+			Simulation::new(physical_quantities::Time::new(number(0.001)), None, None);
+		simulation.create_particle(
+			physical_quantities::Mass::new(number(1000.0)),
+			physical_quantities::Displacement::new(number(0.0), number(0.0)),
+			vec!(Box::new(simulation_objects::MarkerField::new(String::from("gravity")))),
+		);
+		let id = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(number(1.0)),
+			physical_quantities::Displacement::new(number(1.0), number(0.0)),
+			physical_quantities::Velocity::new(number(0.0), number(1000.0_f64.sqrt())),
+			vec!(Box::new(simulation_objects::MarkerField::new(String::from("gravity")))),
+		);
+		simulation.create_pair_interaction(
+			String::from("gravity"),
+			String::from("gravity"),
+			Box::new(simulation_objects::UniversalGravitation::new(f64::INFINITY, Some(1.0), None)),
+		);
+		for _ in 0..1001 {
+			simulation.step();
+		}
+
+		let position = simulation.get_position(id);
+		(
+			position.x().to_f64(),
+			position.y().to_f64(),
+			simulation.get_total_energy().get_number().to_f64(),
+		)
+	}
+
+	#[test]
+	fn single_precision_simulation_tracks_double_precision() {
+		let (x_32, y_32, energy_32) = run_orbit::<f32>();
+		let (x_64, y_64, energy_64) = run_orbit::<f64>();
+
+		assert!((x_32 - x_64).abs() < 1e-3, "{} {}", x_32, x_64);
+		assert!((y_32 - y_64).abs() < 1e-3, "{} {}", y_32, y_64);
+		assert!((energy_32 - energy_64).abs() < 1e-3 * energy_64.abs(), "{} {}", energy_32, energy_64);
+	}
+
+	// Throws a particle with linear drag and gravity, and bounces it off of
+	//	another particle. Returns the thrown particle's position every ten
+	//	ticks, as f64s.
+	#[cfg(feature = "fixed-point")]
+	fn run_projectile<S: physical_quantities::Scalar>() -> Vec<(f64, f64)> {
+		let number = S::from_f64;
+		let simulation: Simulation<S> =
+			Simulation::new(physical_quantities::Time::new(number(0.01)), None, None);
+		let id = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(number(2.0)),
+			physical_quantities::Displacement::new(number(0.0), number(0.0)),
+			physical_quantities::Velocity::new(number(8.0), number(12.0)),
+			vec!(
+				Box::new(simulation_objects::SimpleSelfGravityField::new(
+					physical_quantities::Acceleration::new(number(0.0), number(-9.8)),
+					None,
+				)),
+				Box::new(simulation_objects::LinearDragField::new(0.3, None)),
+				Box::new(simulation_objects::Collider::new(0.5, 0.8, None, None)),
+			),
+		);
+		simulation.create_particle(
+			physical_quantities::Mass::new(number(5.0)),
+			physical_quantities::Displacement::new(number(12.0), number(3.0)),
+			vec!(Box::new(simulation_objects::Collider::new(1.0, 0.8, None, None))),
+		);
+
+		let mut positions = Vec::new();
+		for tick in 0..300 {
+			simulation.step();
+			if tick % 10 == 0 {
+				let position = simulation.get_position(id);
+				positions.push((position.x().to_f64(), position.y().to_f64()));
+			}
+		}
+		positions
+	}
+
+	#[cfg(feature = "fixed-point")]
+	#[test]
+	fn fixed_point_orbit_tracks_double_precision() {
+		let (x_fixed, y_fixed, energy_fixed) = run_orbit::<fixed_point::Fixed>();
+		let (x_64, y_64, energy_64) = run_orbit::<f64>();
+
+		assert!((x_fixed - x_64).abs() < 1e-6, "{} {}", x_fixed, x_64);
+		assert!((y_fixed - y_64).abs() < 1e-6, "{} {}", y_fixed, y_64);
+		assert!((energy_fixed - energy_64).abs() < 1e-6 * energy_64.abs(), "{} {}", energy_fixed, energy_64);
+	}
+
+	#[cfg(feature = "fixed-point")]
+	#[test]
+	fn fixed_point_projectile_tracks_double_precision() {
+		let fixed = run_projectile::<fixed_point::Fixed>();
+		let double = run_projectile::<f64>();
+
+		for (tick, ((x_fixed, y_fixed), (x_64, y_64))) in fixed.iter().zip(&double).enumerate() {
+			assert!(
+				(x_fixed - x_64).abs() < 1e-6 && (y_fixed - y_64).abs() < 1e-6,
+				"sample {}: ({}, {}) != ({}, {})", tick, x_fixed, y_fixed, x_64, y_64,
+			);
+		}
+	}
+
+	#[test]
+	fn single_precision_particles_are_smaller() {
+		assert!(
+			std::mem::size_of::<simulation_objects::Particle<physical_quantities::Vector2<f32>>>()
+				< std::mem::size_of::<simulation_objects::Particle>()
+		);
+	}
+}
+
+
+// Everything needed to put a simulation back the way it was at the start of a
+//	tick.
+#[derive(Clone)]
+struct Snapshot<V: physical_quantities::Vector = physical_quantities::Vector2> {
+	elapsed_ticks: physical_quantities::Ticks,
+	tick_duration: physical_quantities::Time<V::Scalar>,
+	tick_duration_changed_at: (physical_quantities::Ticks, physical_quantities::Time<V::Scalar>),
+	particles: BTreeMap<Uuid, simulation_objects::Particle<V>>,
+	applied_forces: HashMap<Uuid, Vec<physical_quantities::ForceN<V>>>,
+	applied_torques: HashMap<Uuid, Vec<physical_quantities::Torque<V::Scalar>>>,
+	particle_ids_to_delete: Vec<Uuid>,
+	particles_to_add: Vec<simulation_objects::Particle<V>>,
+	world_fields: BTreeMap<Uuid, simulation_objects::WorldField<V>>,
+	world_fields_to_add: Vec<simulation_objects::WorldField<V>>,
+	world_field_ids_to_delete: Vec<Uuid>,
+	pair_interactions: BTreeMap<Uuid, simulation_objects::PairInteractionEntry<V>>,
+	pair_interactions_to_add: Vec<simulation_objects::PairInteractionEntry<V>>,
+	pair_interaction_ids_to_delete: Vec<Uuid>,
+	layer_masks: [simulation_objects::Layers; simulation_objects::Layers::COUNT as usize],
+	active_triggers: BTreeMap<(Uuid, usize, Uuid), String>,
+	// The index into History::generated_ids of the next ID to hand out.
+	next_id_index: usize,
+}
+
+impl<V: physical_quantities::Vector> Snapshot<V> {
+	// Roughly how many bytes the snapshot uses, not counting the fields and
+	//	pair interactions it shares with the simulation.
+	fn estimate_size(&self) -> usize {
+		let particle_size = |p: &simulation_objects::Particle<V>| {
+			size_of::<Uuid>()
+				+ size_of::<simulation_objects::Particle<V>>()
+				+ p.get_fields().len() * size_of::<std::rc::Rc<dyn simulation_objects::Field<V>>>()
+		};
+		let forces = self.applied_forces.values()
+			.map(|f| size_of::<Uuid>() + size_of_val(f.as_slice()))
+			.sum::<usize>();
+		let torques = self.applied_torques.values()
+			.map(|t| size_of::<Uuid>() + size_of_val(t.as_slice()))
+			.sum::<usize>();
+		let triggers = self.active_triggers.values()
+			.map(|name| size_of::<(Uuid, usize, Uuid)>() + size_of::<String>() + name.len())
+			.sum::<usize>();
+
+		size_of::<Self>()
+			+ self.particles.values().map(particle_size).sum::<usize>()
+			+ self.particles_to_add.iter().map(particle_size).sum::<usize>()
+			+ forces
+			+ torques
+			+ (self.world_fields.len() + self.world_fields_to_add.len())
+				* (size_of::<Uuid>() + size_of::<simulation_objects::WorldField<V>>())
+			+ (self.pair_interactions.len() + self.pair_interactions_to_add.len())
+				* (size_of::<Uuid>() + size_of::<simulation_objects::PairInteractionEntry<V>>())
+			+ (self.particle_ids_to_delete.len()
+				+ self.world_field_ids_to_delete.len()
+				+ self.pair_interaction_ids_to_delete.len()) * size_of::<Uuid>()
+			+ triggers
+	}
+}
+
+// A ring buffer of snapshots, oldest first, kept within a memory budget.
+struct History<V: physical_quantities::Vector> {
+	interval: physical_quantities::Ticks,
+	memory_budget: usize,
+	snapshots: VecDeque<(Snapshot<V>, usize)>,
+	// The estimated size of all snapshots, in bytes.
+	memory_used: usize,
+	// IDs generated since the oldest snapshot, in order.
+	generated_ids: Vec<Uuid>,
+	// The index into generated_ids of the next ID to hand out. Less than
+	//	generated_ids.len() after a rewind.
+	next_id_index: usize,
+}
+
+impl<V: physical_quantities::Vector> History<V> {
+	fn new(interval: physical_quantities::Ticks, memory_budget: usize) -> Self {
+		Self {
+			interval: interval,
+			memory_budget: memory_budget,
+			snapshots: VecDeque::new(),
+			memory_used: 0,
+			generated_ids: Vec::new(),
+			next_id_index: 0,
+		}
+	}
+
+	fn generate_id(&mut self) -> Uuid {
+		if self.next_id_index == self.generated_ids.len() {
+			self.generated_ids.push(Uuid::new_v4());
+		}
+		self.next_id_index += 1;
+		self.generated_ids[self.next_id_index - 1]
+	}
+
+	// Adds a snapshot, discarding the oldest snapshots as needed to stay
+	//	within the memory budget.
+	fn push(&mut self, snapshot: Snapshot<V>) {
+		let size = snapshot.estimate_size();
+		self.snapshots.push_back((snapshot, size));
+		self.memory_used += size;
+
+		while self.memory_used > self.memory_budget {
+			let Some((_, size)) = self.snapshots.pop_front() else {
+				break;
+			};
+			self.memory_used -= size;
+		}
+
+		// IDs handed out before the oldest snapshot will never be handed out
+		//	again.
+		let first_needed = self.snapshots.front()
+			.map_or(self.next_id_index, |(snapshot, _)| snapshot.next_id_index);
+		if first_needed > 0 {
+			self.generated_ids.drain(..first_needed);
+			self.next_id_index -= first_needed;
+			for (snapshot, _) in self.snapshots.iter_mut() {
+				snapshot.next_id_index -= first_needed;
+			}
+		}
+	}
+
+	// Discards snapshots taken after the given tick and returns a copy of the
+	//	latest one that remains, if any. The next IDs handed out will be the
+	//	ones handed out after that snapshot was taken.
+	fn rewind(&mut self, tick: physical_quantities::Ticks) -> Option<Snapshot<V>> {
+		while self.snapshots.back().is_some_and(|(s, _)| s.elapsed_ticks > tick) {
+			if let Some((_, size)) = self.snapshots.pop_back() {
+				self.memory_used -= size;
+			}
+		}
+
+		let (snapshot, _) = self.snapshots.back()?;
+		self.next_id_index = snapshot.next_id_index;
+		Some(snapshot.clone())
+	}
+}
+
+
+/// What happened during a call to `Simulation::advance`.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub struct Advance {
+	ticks: physical_quantities::Ticks,
+	alpha: f64,
+}
+
+impl Advance {
+	/// Gets the number of ticks that were executed.
+	pub fn get_ticks(&self) -> physical_quantities::Ticks {
+		self.ticks
+	}
+
+	/// Gets how far, from 0.0 to 1.0, the simulation has gotten toward its
+	/// next tick. Pass this to `Simulation::get_interpolated_position` to draw
+	/// particles between their positions at the previous and current ticks.
+	pub fn get_alpha(&self) -> f64 {
+		self.alpha
+	}
+}
+
+
+/// How `Simulation::start` waits between ticks when it has a simulation speed.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub enum SchedulingStrategy {
+	/// Repeatedly checks the time until the next tick is due. The most precise
+	/// option, but keeps a CPU core busy the whole time.
+	BusyWait,
+	/// Sleeps until the next tick is due. Uses almost no CPU, but the operating
+	/// system may wake the thread late, so ticks may lag.
+	Sleep,
+	/// Sleeps until `spin` before the next tick is due, then busy-waits for the
+	/// rest. Nearly as precise as `BusyWait` if `spin` is longer than the
+	/// operating system usually oversleeps by (often around a millisecond).
+	Hybrid { spin: Duration },
+}
+
+/// Statistics about how closely `Simulation::start` has kept to its schedule.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(Default)]
+#[derive(PartialEq)]
+pub struct TimingStats {
+	ticks: u64,
+	running_time: Duration,
+	total_lag: Duration,
+	overrun_ticks: u64,
+}
+
+impl TimingStats {
+	/// Gets the number of ticks executed by `start`.
+	pub fn get_ticks(&self) -> physical_quantities::Ticks {
+		physical_quantities::Ticks::new(self.ticks)
+	}
+
+	/// Gets the real world time spent in `start`.
+	pub fn get_running_time(&self) -> Duration {
+		self.running_time
+	}
+
+	/// Gets the number of ticks executed per real world second, or 0.0 if
+	/// `start` hasn't run.
+	pub fn get_ticks_per_second(&self) -> f64 {
+		if self.running_time.is_zero() {
+			return 0.0;
+		}
+		self.ticks as f64 / self.running_time.as_secs_f64()
+	}
+
+	/// Gets how late, on average, ticks started compared to when they were
+	/// due.
+	pub fn get_average_lag(&self) -> Duration {
+		if self.ticks == 0 {
+			return Duration::ZERO;
+		}
+		self.total_lag.div_f64(self.ticks as f64)
+	}
+
+	/// Gets the number of ticks that took longer to execute than the real
+	/// world time allowed for a tick, making the next tick late.
+	pub fn get_overrun_ticks(&self) -> physical_quantities::Ticks {
+		physical_quantities::Ticks::new(self.overrun_ticks)
+	}
+}
+
+
+/// Owns all of the data that constitutes a physics simulation. Provides methods
+/// for interacting with and running the simulation. Generic over the vector
+/// type, which sets the number of dimensions and the number type. Use
+/// `Simulation` for two dimensions and `Simulation3` for three. Both use `f64`
+/// unless given another `physical_quantities::Scalar` (e.g., `Simulation<f32>`).
+pub struct SimulationN<V: physical_quantities::Vector> {
+	// The number of simulated seconds that elapse in a single tick.
+	//	This is effectively the resolution of the simulation.
+	tick_duration: RefCell<physical_quantities::Time<V::Scalar>>,
+	// A new tick duration set during a tick, to be used from the next tick.
+	pending_tick_duration: RefCell<Option<physical_quantities::Time<V::Scalar>>>,
+	// The elapsed ticks and time when the tick duration last changed. Elapsed
+	//	time is accumulated from here rather than by multiplying every elapsed
+	//	tick by the current duration.
+	tick_duration_changed_at: RefCell<(physical_quantities::Ticks, physical_quantities::Time<V::Scalar>)>,
+	// A collection that owns all particles in the simulation. Ordered by ID so
+	//	ticks always process particles in the same order.
+	particles: RefCell<BTreeMap<Uuid, simulation_objects::Particle<V>>>,
+	// The number of ticks that have passed so far.
+	elapsed_ticks: RefCell<physical_quantities::Ticks>,
+	// Speed at which the simulation will run, resources permitting. Units are
+	//	(simulated seconds) / (real world seconds). If None, run as fast as
+	//	possible.
+	simulation_speed: RefCell<Option<f64>>,
+	// The units that every number in the simulation is in. Built-in fields use
+	//	it to pick constants, like G, in the right units.
+	unit_system: RefCell<units::UnitSystem>,
+	// A function called on each tick. Allows user-defined logic to be driven
+	//	by the simulation.
+	on_tick: Option<fn(&SimulationN<V>)>,
+	// Holds forces, keyed by particle_id, to simulate when appropriate.
+	applied_forces: RefCell<HashMap<Uuid, Vec<physical_quantities::ForceN<V>>>>,
+	// Holds torques, keyed by particle_id, to simulate when appropriate.
+	applied_torques: RefCell<HashMap<Uuid, Vec<physical_quantities::Torque<V::Scalar>>>>,
+	// Stores the IDs of particles to delete when appropriate.
+	particle_ids_to_delete: RefCell<Vec<Uuid>>,
+	// Stores particles to add to the simulation when appropriate.
+	particles_to_add: RefCell<Vec<simulation_objects::Particle<V>>>,
+	// Fields owned by the simulation rather than by a particle, keyed by ID.
+	world_fields: RefCell<BTreeMap<Uuid, simulation_objects::WorldField<V>>>,
+	// Stores world fields to add to the simulation when appropriate.
+	world_fields_to_add: RefCell<Vec<simulation_objects::WorldField<V>>>,
+	// Stores the IDs of world fields to delete when appropriate.
+	world_field_ids_to_delete: RefCell<Vec<Uuid>>,
+	// Interactions evaluated once per pair of particles, keyed by ID.
+	pair_interactions: RefCell<BTreeMap<Uuid, simulation_objects::PairInteractionEntry<V>>>,
+	// Stores pair interactions to add to the simulation when appropriate.
+	pair_interactions_to_add: RefCell<Vec<simulation_objects::PairInteractionEntry<V>>>,
+	// Stores the IDs of pair interactions to delete when appropriate.
+	pair_interaction_ids_to_delete: RefCell<Vec<Uuid>>,
+	// The layer interaction matrix. Element n is the set of layers that can
+	//	trigger fields on layer n.
+	layer_masks: RefCell<[simulation_objects::Layers; simulation_objects::Layers::COUNT as usize]>,
+	// If true, the simulation should be paused. If false, the simulation should
+	//	be running.
+	is_paused: RefCell<bool>,
+	// Keeps track of when the last tick started, in real world time. This
+	//	allows step_synchronized() to call tick at the appropriate rate.
+	previous_tick_instant: RefCell<Instant>,
+	// How long start() and step_synchronize should wait between calls to tick.
+	tick_wait_duration: RefCell<Duration>,
+	// Events emitted during the current tick. These are passed to subscribers
+	//	and moved to `events` at the end of the tick.
+	tick_events: RefCell<Vec<events::Event>>,
+	// Events waiting to be drained by the user.
+	events: RefCell<Vec<events::Event>>,
+	// Notified of every event at the end of each tick.
+	event_subscribers: RefCell<Vec<Box<dyn events::EventSubscriber<V>>>>,
+	// Particles that triggered each field on the previous tick, keyed by
+	//	(field owner ID, index of the field on its owner, triggering particle
+	//	ID). The value is the field's name. Used to detect when particles start
+	//	or stop triggering a field.
+	active_triggers: RefCell<BTreeMap<(Uuid, usize, Uuid), String>>,
+	// Record frames at the end of ticks, keyed by ID.
+	recorders: RefCell<BTreeMap<Uuid, recording::RecorderN<V>>>,
+	// Snapshots for rewinding the simulation. None unless enabled with
+	//	enable_history().
+	history: RefCell<Option<History<V>>>,
+	// True while rewind_to() is re-simulating ticks that already happened.
+	is_replaying: RefCell<bool>,
+	// True while a tick is being executed.
+	is_ticking: RefCell<bool>,
+	// Simulated time that advance() has been given but hasn't simulated yet
+	//	because it's less than a tick.
+	accumulated_time: RefCell<f64>,
+	// The most ticks a single call to advance() may execute.
+	max_ticks_per_advance: RefCell<physical_quantities::Ticks>,
+	// How start() waits between ticks.
+	scheduling_strategy: RefCell<SchedulingStrategy>,
+	// How well start() has kept to its schedule.
+	timing_stats: RefCell<TimingStats>,
+}
+
+/// A two-dimensional simulation. `S` is the number type used for every
+/// quantity in the simulation (see `physical_quantities::Scalar`).
+pub type Simulation<S = f64> = SimulationN<physical_quantities::Vector2<S>>;
+/// A three-dimensional simulation.
+pub type Simulation3<S = f64> = SimulationN<physical_quantities::Vector3<S>>;
+
+impl<V: physical_quantities::Vector> SimulationN<V> {
+	/// The default for `set_max_ticks_per_advance`.
+	pub const DEFAULT_MAX_TICKS_PER_ADVANCE: u64 = 10;
+
+	fn tick(&self) {
+		*self.previous_tick_instant.borrow_mut() = Instant::now();
+		*self.is_ticking.borrow_mut() = true;
+
+		self.take_snapshot_if_due();
+
+		// Call the on_tick fn pointer, if it exists.
+		match self.on_tick {
+			Some(f) => f(self),
+			None => (),
+		}
+
+		// For each field, find all particles affected by that field and apply
+		//	the field's effect to each of those particles.
+		// TODO (for v1): There are algorithms and data structures to more
+		//	efficiently find things based on spatial relationships (e.g.,
+		//	distance). Do some research and optimize this so searching for
+		//	nearby particles isn't so inefficient.
+		// Vec of (field owner, field, affected particles).
+		let mut current_triggers = BTreeMap::new();
+		for field_owner in self.particles.borrow().values() {
+			for (field_index, field) in field_owner.get_fields().iter().enumerate() {
+				let owner_id = field_owner.get_id();
+				let mut triggered_by = HashMap::new();
+				// Particles and fields that aren't on any of these layers
+				//	can't trigger this field.
+				let triggering_layers = self.get_triggering_layers(field.get_layers());
+
+				// Add the field owner if the field affects it.
+				if field.affects_self() {
+					// The field owner's fields should never trigger each other.
+					// Insert an empty Vec of fields.
+					triggered_by.insert(owner_id, vec![None]);
+				}
+
+				// Add all particles, other than the field owner, that are
+				//	affected by the field, if this field affects particles other
+				//	than the particle to which it's attached.
+				if field.affects_others() {
+					for particle in self.particles.borrow().values() {
+						let particle_id = particle.get_id();
+						// Skip the field owner.
+						if owner_id != particle_id {
+
+							// If the particle is within this field, this field
+							//	affects particles within it, and the particle is
+							//	on a layer that can trigger this field.
+							if field.triggers_on_particles()
+								&& particle.get_layers().intersects(triggering_layers)
+								&& utilities::is_within_radius(
+									particle.get_position(),
+									field.get_radius(),
+									field_owner.get_position(),
+									true,
+								) {
+								// If this field is trigered by a particle,
+								//	insert the particle with a vector containing
+								//	None to denote that it wasn't added by field
+								//	overlap.
+								triggered_by.insert(particle_id, vec![None]);
+							}
+
+							// Check whether this field affects the owners of
+							//	overlapping fields and whether the particle has
+							//	fields that overlap with this field. Record any
+							//	overlapping fields to send to the effect method.
+							if field.triggers_on_fields() {
+								for info in particle.get_field_info() {
+									if !info.get_layers().intersects(triggering_layers) {
+										continue;
+									}
+									if utilities::is_within_radius(
+										particle.get_position(),
+										field.get_radius() + info.get_radius(),
+										field_owner.get_position(),
+ 										// TODO: It probably makes more sense for
+										//	this to be false. I.e., consider
+										//	fields that just touch to be
+										//	overlapping.
+										true,
+									) {
+										if let Some(v) = triggered_by.get_mut(&particle_id) {
+												v.push(Some(info));
+										} else {
+											triggered_by.insert(
+												particle_id,
+												vec![Some(info)],
+											);
+										}
+									}
+								}
+							}
+						}
+					}
+				}
+
+				// Remember which other particles triggered this field so we can
+				//	tell when they start or stop triggering it.
+				for particle_id in triggered_by.keys() {
+					if *particle_id != owner_id {
+						current_triggers.insert(
+							(owner_id, field_index, *particle_id),
+							field.get_name().clone(),
+						);
+					}
+				}
+
+				// Add this Field's effects to the lists of actions to take.
+				field.effect(
+					self,
+					field_owner.get_position(),
+					triggered_by,
+					field_owner.get_id(),
+				);
+			}
+		}
+
+		// Apply the effects of world fields to all particles inside their
+		//	regions.
+		for world_field in self.world_fields.borrow().values() {
+			let field_id = world_field.get_id();
+			let field = world_field.get_field();
+			let mut triggered_by = HashMap::new();
+			let triggering_layers = self.get_triggering_layers(field.get_layers());
+
+			for particle in self.particles.borrow().values() {
+				if particle.get_layers().intersects(triggering_layers)
+					&& world_field.get_region().contains(particle.get_position()) {
+					triggered_by.insert(particle.get_id(), vec![None]);
+					current_triggers.insert(
+						(field_id, 0, particle.get_id()),
+						field.get_name().clone(),
+					);
+				}
+			}
+
+			field.effect(
+				self,
+				world_field.get_region().get_center(),
+				triggered_by,
+				field_id,
+			);
+		}
+
+		self.apply_pair_interactions();
+
+		self.update_triggers(current_triggers);
+
+		// Delete any particles that were staged for deletion. Doing this before
+		//	applying forces avoids having to do calculations for particles that
+		//	are being deleted anyway.
+		for particle_id in &*self.particle_ids_to_delete.borrow() {
+			// A particle may have been staged for deletion more than once.
+			if self.particles.borrow_mut().remove(particle_id).is_some() {
+				self.queue_event(events::EventKind::ParticleDeleted {
+					particle_id: *particle_id,
+				});
+			}
+		}
+		self.particle_ids_to_delete.borrow_mut().clear();
+		for field_id in self.world_field_ids_to_delete.borrow_mut().drain(..) {
+			self.world_fields.borrow_mut().remove(&field_id);
+		}
+		for interaction_id in self.pair_interaction_ids_to_delete.borrow_mut().drain(..) {
+			self.pair_interactions.borrow_mut().remove(&interaction_id);
+		}
+
+		// For each particle, calculate and apply any change in velocity or
+		//	position that should occur during this tick.
+		let tick_duration = self.get_tick_duration();
+		for (particle_id, particle) in self.particles.borrow_mut().iter_mut() {
+			match self.applied_forces.borrow_mut().get_mut(particle_id) {
+				Some(forces) => {
+					// Fields may apply forces in any order (e.g., the order in
+					//	which they iterate over triggered_by). Add them up in a
+					//	fixed order so re-simulating a tick gives exactly the
+					//	same result.
+					forces.sort_by(|a, b| {
+						let (a, b) = (a.get_vector(), b.get_vector());
+						(0..V::DIMENSIONS)
+							.map(|i| a.get_component(i).to_f64().total_cmp(&b.get_component(i).to_f64()))
+							.fold(std::cmp::Ordering::Equal, std::cmp::Ordering::then)
+					});
+					particle.accelerate(forces, tick_duration)
+				},
+				None => particle.coast(tick_duration),
+			}
+			match self.applied_torques.borrow_mut().get_mut(particle_id) {
+				Some(torques) => {
+					torques.sort_by(|a, b| a.get_number().to_f64().total_cmp(&b.get_number().to_f64()));
+					particle.turn(torques, tick_duration)
+				},
+				None => particle.turn(&[], tick_duration),
+			}
+		}
+		self.applied_forces.borrow_mut().clear();
+		self.applied_torques.borrow_mut().clear();
+
+		// Add any newly created particles to the simulation. Doing this after
+		//	applying changes in velocity and position avoids iterating through
+		//	particles that can't have changes during this tick anyway.
+		for particle in self.particles_to_add.borrow_mut().drain(..) {
+			let particle_id = particle.get_id();
+			let v = self.particles.borrow_mut().insert(particle_id, particle);
+			// If v is Some, it means we already had a particle with this
+			//	particle's ID. This should not happen.
 			if v.is_some() {
 				panic!("Created a particle with an existing key. This probably \
 						means there is a bug in the physics engine.");
 			}
+			self.queue_event(events::EventKind::ParticleCreated {
+				particle_id: particle_id,
+			});
+		}
+
+		for world_field in self.world_fields_to_add.borrow_mut().drain(..) {
+			self.world_fields.borrow_mut().insert(world_field.get_id(), world_field);
+		}
+
+		for entry in self.pair_interactions_to_add.borrow_mut().drain(..) {
+			self.pair_interactions.borrow_mut().insert(entry.get_id(), entry);
+		}
+
+		*self.elapsed_ticks.borrow_mut() += physical_quantities::Ticks::new(1);
+
+		*self.is_ticking.borrow_mut() = false;
+		if let Some(tick_duration) = self.pending_tick_duration.borrow_mut().take() {
+			self.change_tick_duration(tick_duration);
+		}
+
+		// Ticks that are being re-simulated already recorded their frames and
+		//	emitted their events the first time around.
+		if *self.is_replaying.borrow() {
+			self.tick_events.borrow_mut().clear();
+			return;
+		}
+
+		for recorder in self.recorders.borrow_mut().values_mut() {
+			recorder.on_tick(self);
+		}
+
+		self.dispatch_events();
+	}
+
+	// Generates an ID for a new particle, world field, or pair interaction.
+	//	While history is enabled, IDs are remembered so that re-simulating
+	//	after a rewind hands out the same IDs as the first time.
+	fn generate_id(&self) -> Uuid {
+		match self.history.borrow_mut().as_mut() {
+			Some(history) => history.generate_id(),
+			None => Uuid::new_v4(),
+		}
+	}
+
+	// Takes a snapshot if history is enabled and the current tick is a
+	//	multiple of the snapshot interval.
+	fn take_snapshot_if_due(&self) {
+		let elapsed_ticks = *self.elapsed_ticks.borrow();
+		let mut history = self.history.borrow_mut();
+		let Some(history) = history.as_mut() else {
+			return;
+		};
+
+		// The latest snapshot may already be of this tick if we just rewound
+		//	to it.
+		let is_due = elapsed_ticks.get_number().is_multiple_of(history.interval.get_number())
+			&& history.snapshots.back().is_none_or(|(s, _)| s.elapsed_ticks < elapsed_ticks);
+		if !is_due {
+			return;
+		}
+
+		let snapshot = Snapshot {
+			elapsed_ticks: elapsed_ticks,
+			tick_duration: *self.tick_duration.borrow(),
+			tick_duration_changed_at: *self.tick_duration_changed_at.borrow(),
+			particles: self.particles.borrow().clone(),
+			applied_forces: self.applied_forces.borrow().clone(),
+			applied_torques: self.applied_torques.borrow().clone(),
+			particle_ids_to_delete: self.particle_ids_to_delete.borrow().clone(),
+			particles_to_add: self.particles_to_add.borrow().clone(),
+			world_fields: self.world_fields.borrow().clone(),
+			world_fields_to_add: self.world_fields_to_add.borrow().clone(),
+			world_field_ids_to_delete: self.world_field_ids_to_delete.borrow().clone(),
+			pair_interactions: self.pair_interactions.borrow().clone(),
+			pair_interactions_to_add: self.pair_interactions_to_add.borrow().clone(),
+			pair_interaction_ids_to_delete:
+				self.pair_interaction_ids_to_delete.borrow().clone(),
+			layer_masks: *self.layer_masks.borrow(),
+			active_triggers: self.active_triggers.borrow().clone(),
+			next_id_index: history.next_id_index,
+		};
+		history.push(snapshot);
+	}
+
+	// Gets the set of layers that can trigger a field on any of the given
+	//	layers.
+	fn get_triggering_layers(
+		&self,
+		field_layers: simulation_objects::Layers,
+	) -> simulation_objects::Layers {
+		let layer_masks = self.layer_masks.borrow();
+		(0..simulation_objects::Layers::COUNT)
+			.filter(|index| field_layers.contains(*index))
+			.fold(simulation_objects::Layers::NONE, |layers, index| {
+				layers | layer_masks[index as usize]
+			})
+	}
+
+	// Evaluates each pair interaction once for every unordered pair of
+	//	particles carrying the interaction's fields and applies equal and
+	//	opposite forces to both particles.
+	fn apply_pair_interactions(&self) {
+		self.for_each_interacting_pair(|interaction, id_a, position_a, id_b, position_b| {
+			if let Some(force) = interaction.force(
+				self,
+				id_a,
+				position_a,
+				id_b,
+				position_b,
+			) {
+				self.apply_force(id_a, force);
+				self.apply_force(id_b, -force);
+			}
+		});
+	}
+
+	// Calls f once for each pair interaction and each unordered pair of
+	//	particles that carry the interaction's fields and are within its
+	//	cutoff, with the particles in the roles of "a" and "b".
+	fn for_each_interacting_pair<F>(&self, mut f: F)
+	where
+		F: FnMut(
+			&dyn simulation_objects::PairInteraction<V>,
+			Uuid,
+			physical_quantities::DisplacementN<V>,
+			Uuid,
+			physical_quantities::DisplacementN<V>,
+		),
+	{
+		if self.pair_interactions.borrow().is_empty() {
+			return;
 		}
 
-		*self.elapsed_ticks.borrow_mut() += physical_quantities::Ticks::new(1);
+		// (ID, position, field names) for every particle, in ID order so the
+		//	order in which forces are summed doesn't change from run to run.
+		let particles: Vec<(Uuid, physical_quantities::DisplacementN<V>, Vec<String>)> =
+			self.particles.borrow().values().map(|particle| {
+				(
+					particle.get_id(),
+					particle.get_position(),
+					particle.get_fields().iter().map(|f| f.get_name().clone()).collect(),
+				)
+			}).collect();
+
+		for entry in self.pair_interactions.borrow().values() {
+			let interaction = entry.get_interaction();
+			let name_a = entry.get_field_name_a();
+			let name_b = entry.get_field_name_b();
+
+			for i in 0..particles.len() {
+				for j in (i + 1)..particles.len() {
+					let (id_i, position_i, names_i) = &particles[i];
+					let (id_j, position_j, names_j) = &particles[j];
+
+					// Figure out which particle plays the role of "a". If both
+					//	orientations match (e.g., both names are the same), the
+					//	pair is still only evaluated once.
+					let (id_a, position_a, id_b, position_b) =
+						if names_i.contains(name_a) && names_j.contains(name_b) {
+							(*id_i, *position_i, *id_j, *position_j)
+						} else if names_j.contains(name_a) && names_i.contains(name_b) {
+							(*id_j, *position_j, *id_i, *position_i)
+						} else {
+							continue;
+						};
+
+					if !utilities::is_within_radius(
+						position_b,
+						interaction.get_cutoff(),
+						position_a,
+						true,
+					) {
+						continue;
+					}
+
+					f(interaction, id_a, position_a, id_b, position_b);
+				}
+			}
+		}
+	}
+
+	// Compares the particles that triggered each field during this tick with
+	//	those that triggered it during the previous tick and queues
+	//	TriggerEnter and TriggerExit events for the differences.
+	fn update_triggers(&self, current_triggers: BTreeMap<(Uuid, usize, Uuid), String>) {
+		let previous_triggers = self.active_triggers.replace(current_triggers);
+		let active_triggers = self.active_triggers.borrow();
+
+		for ((owner_id, field_index, particle_id), name) in &previous_triggers {
+			if !active_triggers.contains_key(&(*owner_id, *field_index, *particle_id)) {
+				self.queue_event(events::EventKind::TriggerExit {
+					field_owner_id: *owner_id,
+					field_name: name.clone(),
+					particle_id: *particle_id,
+				});
+			}
+		}
+
+		for ((owner_id, field_index, particle_id), name) in active_triggers.iter() {
+			if !previous_triggers.contains_key(&(*owner_id, *field_index, *particle_id)) {
+				self.queue_event(events::EventKind::TriggerEnter {
+					field_owner_id: *owner_id,
+					field_name: name.clone(),
+					particle_id: *particle_id,
+				});
+			}
+		}
+	}
+
+	// Passes the events emitted during this tick to each subscriber, then
+	//	moves them to the queue drained by drain_events().
+	fn dispatch_events(&self) {
+		// Take the events first, so subscribers can emit events (which will be
+		//	dispatched at the end of the next tick).
+		let tick_events = self.tick_events.take();
+
+		if !tick_events.is_empty() {
+			for subscriber in self.event_subscribers.borrow_mut().iter_mut() {
+				for event in tick_events.iter() {
+					subscriber.on_event(self, event);
+				}
+			}
+		}
+
+		self.events.borrow_mut().extend(tick_events);
+	}
+
+	// Records an event that occurred during the current tick.
+	pub(crate) fn queue_event(&self, kind: events::EventKind) {
+		let event = events::Event::new(*self.elapsed_ticks.borrow(), kind);
+		self.tick_events.borrow_mut().push(event);
 	}
 
 	/// Creates an instance of `Simulation`.
@@ -2658,12 +5710,12 @@ impl Simulation {
 	/// Panics if `tick_duration` or `simulation_speed` is less than or equal to
 	/// zero.
 	pub fn new(
-		tick_duration: physical_quantities::Time,
+		tick_duration: physical_quantities::Time<V::Scalar>,
 		simulation_speed: Option<f64>,
-		on_tick: Option<fn(&Simulation)>,
+		on_tick: Option<fn(&SimulationN<V>)>,
 	) -> Self {
 
-		if tick_duration <= physical_quantities::Time::new(0.0) {
+		if tick_duration <= physical_quantities::Time::new(V::Scalar::zero()) {
 			panic!("tick_duration must be positive");
 		}
 
@@ -2673,27 +5725,49 @@ impl Simulation {
 			}
 		}
 
-		// Calculate how long to wait between calls to tick() based on
-		//	simulation speed and tick duration.
-		let wait_duration;
-		match simulation_speed {
-			Some(speed) => wait_duration =
-				Duration::from_secs_f64(tick_duration.get_number() / speed),
-			None => wait_duration = Duration::from_secs_f64(0.0),
-		}
-
 		Self {
-			tick_duration: tick_duration,
-			particles: RefCell::new(HashMap::new()),
+			tick_duration: RefCell::new(tick_duration),
+			pending_tick_duration: RefCell::new(None),
+			tick_duration_changed_at: RefCell::new(
+				(physical_quantities::Ticks::new(0), physical_quantities::Time::new(V::Scalar::zero()))
+			),
+			particles: RefCell::new(BTreeMap::new()),
 			elapsed_ticks: RefCell::new(physical_quantities::Ticks::new(0)),
-			simulation_speed: simulation_speed,
+			simulation_speed: RefCell::new(simulation_speed),
+			unit_system: RefCell::new(units::UnitSystem::SI),
 			on_tick: on_tick,
 			applied_forces: RefCell::new(HashMap::new()),
+			applied_torques: RefCell::new(HashMap::new()),
 			particle_ids_to_delete: RefCell::new(Vec::new()),
 			particles_to_add: RefCell::new(Vec::new()),
+			world_fields: RefCell::new(BTreeMap::new()),
+			world_fields_to_add: RefCell::new(Vec::new()),
+			world_field_ids_to_delete: RefCell::new(Vec::new()),
+			pair_interactions: RefCell::new(BTreeMap::new()),
+			pair_interactions_to_add: RefCell::new(Vec::new()),
+			pair_interaction_ids_to_delete: RefCell::new(Vec::new()),
+			layer_masks: RefCell::new(
+				[simulation_objects::Layers::ALL; simulation_objects::Layers::COUNT as usize]
+			),
 			is_paused: RefCell::new(true),
 			previous_tick_instant: RefCell::new(Instant::now()),
-			tick_wait_duration: wait_duration,
+			tick_wait_duration: RefCell::new(
+				Self::calculate_wait_duration(tick_duration, simulation_speed)
+			),
+			tick_events: RefCell::new(Vec::new()),
+			events: RefCell::new(Vec::new()),
+			event_subscribers: RefCell::new(Vec::new()),
+			active_triggers: RefCell::new(BTreeMap::new()),
+			recorders: RefCell::new(BTreeMap::new()),
+			history: RefCell::new(None),
+			is_replaying: RefCell::new(false),
+			is_ticking: RefCell::new(false),
+			accumulated_time: RefCell::new(0.0),
+			max_ticks_per_advance: RefCell::new(
+				physical_quantities::Ticks::new(Self::DEFAULT_MAX_TICKS_PER_ADVANCE)
+			),
+			scheduling_strategy: RefCell::new(SchedulingStrategy::BusyWait),
+			timing_stats: RefCell::new(TimingStats::default()),
 		}
 	}
 
@@ -2707,17 +5781,74 @@ impl Simulation {
 	/// * `fields` - Fields to attach to the particle.
 	pub fn create_particle(
 		&self,
-		mass: physical_quantities::Mass,
-		position: physical_quantities::Displacement,
-		fields: Vec<Box<dyn simulation_objects::Field>>,
+		mass: physical_quantities::Mass<V::Scalar>,
+		position: physical_quantities::DisplacementN<V>,
+		fields: Vec<Box<dyn simulation_objects::Field<V>>>,
 	) -> Uuid {
-		let particle = simulation_objects::Particle::new(
+		self.create_particle_with_velocity(
 			mass,
 			position,
-			physical_quantities::Velocity::new(0.0, 0.0),
+			physical_quantities::VelocityN::from_vector(V::zero()),
+			fields,
+		)
+	}
+
+	/// Creates a new, already moving particle to be added to the simulation
+	/// during the next tick. Returns that particle's unique ID.
+	///
+	/// # Arguments
+	/// * `position` - The particle's coordinates in space.
+	/// * `mass` - The particle's mass.
+	/// * `velocity` - The particle's initial velocity.
+	/// * `fields` - Fields to attach to the particle.
+	pub fn create_particle_with_velocity(
+		&self,
+		mass: physical_quantities::Mass<V::Scalar>,
+		position: physical_quantities::DisplacementN<V>,
+		velocity: physical_quantities::VelocityN<V>,
+		fields: Vec<Box<dyn simulation_objects::Field<V>>>,
+	) -> Uuid {
+		let particle = simulation_objects::Particle::from_parts(
+			self.generate_id(),
+			mass,
+			position,
+			velocity,
 			fields,
 		);
+		self.queue_particle(particle)
+	}
+
+	/// Creates a new particle that can spin, to be added to the simulation
+	/// during the next tick. Returns that particle's unique ID.
+	///
+	/// # Arguments
+	/// * `position` - The particle's coordinates in space.
+	/// * `mass` - The particle's mass.
+	/// * `velocity` - The particle's initial velocity.
+	/// * `rotation` - The particle's initial orientation, angular velocity,
+	///		and moment of inertia.
+	/// * `fields` - Fields to attach to the particle.
+	pub fn create_particle_with_rotation(
+		&self,
+		mass: physical_quantities::Mass<V::Scalar>,
+		position: physical_quantities::DisplacementN<V>,
+		velocity: physical_quantities::VelocityN<V>,
+		rotation: simulation_objects::Rotation<V::Scalar>,
+		fields: Vec<Box<dyn simulation_objects::Field<V>>>,
+	) -> Uuid {
+		let particle = simulation_objects::Particle::from_parts(
+			self.generate_id(),
+			mass,
+			position,
+			velocity,
+			fields,
+		).with_rotation(rotation);
+		self.queue_particle(particle)
+	}
 
+	// Adds a newly created particle to the queue of particles to add during the
+	//	next tick, and returns its ID.
+	fn queue_particle(&self, particle: simulation_objects::Particle<V>) -> Uuid {
 		// Get the return value before handing off ownership of the particle.
 		let id = particle.get_id();
 
@@ -2751,6 +5882,173 @@ impl Simulation {
 		self.particle_ids_to_delete.borrow_mut().push(particle_id);
 	}
 
+	/// Creates a field that is owned by the simulation instead of a particle.
+	/// The field will be added to the simulation during the next tick and,
+	/// from then on, its `effect` method will be called on every tick with all
+	/// particles inside `region`. Returns the world field's unique ID.
+	///
+	/// # Arguments
+	/// * `region` - The part of the world in which the field affects
+	///		particles. Use `Region::Everywhere` for things like uniform gravity
+	///		or global drag.
+	/// * `field` - The field. Its radius and `affects_*`/`triggers_on_*`
+	///		settings are ignored. Every particle inside `region` is passed to
+	///		`effect` with a `None` in its vector of `FieldInfo`.
+	///
+	/// <div class="warning">
+	///
+	/// `effect` will be passed the center of the region as `position` and the
+	/// ID of the world field as `field_owner_id`. The world field is not a
+	/// particle, so fields that look up their owner (e.g., with
+	/// `get_mass(field_owner_id)`) will panic. `SimpleSelfGravityField`,
+	/// `UniformForceField`, and `LinearDragField` only use the particles
+	/// they're passed and work as world fields.
+	///
+	/// </div>
+	pub fn create_world_field(
+		&self,
+		region: simulation_objects::Region<V>,
+		field: Box<dyn simulation_objects::Field<V>>,
+	) -> Uuid {
+		let world_field =
+			simulation_objects::WorldField::from_parts(self.generate_id(), region, field);
+		let id = world_field.get_id();
+		self.world_fields_to_add.borrow_mut().push(world_field);
+		id
+	}
+
+	/// Removes a world field from the simulation during the next tick.
+	///
+	/// # Arguments
+	/// * `field_id` - The unique ID of the world field to delete.
+	///
+	/// # Panics
+	/// This method will panic if there is no world field identified by
+	/// 	`field_id`.
+	pub fn delete_world_field(&self, field_id: Uuid) {
+		if !self.world_fields.borrow().contains_key(&field_id) {
+			panic!(
+				"Simulation.delete_world_field(): \
+					the provided world field ID was not found: {}",
+				field_id,
+			);
+		}
+
+		self.world_field_ids_to_delete.borrow_mut().push(field_id);
+	}
+
+	/// Creates an interaction that will be evaluated once per tick for every
+	/// unordered pair of particles in which one particle has a field named
+	/// `field_name_a` and the other has a field named `field_name_b`. The
+	/// force returned by the interaction is applied to the first particle and
+	/// the opposite force is applied to the second. The interaction will be
+	/// added to the simulation during the next tick. Returns the interaction's
+	/// unique ID.
+	///
+	/// # Arguments
+	/// * `field_name_a` - The name of a field that the first particle in each
+	///		pair must have.
+	/// * `field_name_b` - The name of a field that the second particle in each
+	///		pair must have. May be the same as `field_name_a`.
+	/// * `interaction` - The interaction. Only pairs of particles closer
+	///		together than its cutoff are passed to its `force` method.
+	///
+	/// Particles that should only be identified by name, without any other
+	/// field behavior, can be given a `MarkerField`.
+	pub fn create_pair_interaction(
+		&self,
+		field_name_a: String,
+		field_name_b: String,
+		interaction: Box<dyn simulation_objects::PairInteraction<V>>,
+	) -> Uuid {
+		let entry = simulation_objects::PairInteractionEntry::from_parts(
+			self.generate_id(),
+			field_name_a,
+			field_name_b,
+			interaction,
+		);
+		let id = entry.get_id();
+		self.pair_interactions_to_add.borrow_mut().push(entry);
+		id
+	}
+
+	/// Removes a pair interaction from the simulation during the next tick.
+	///
+	/// # Arguments
+	/// * `interaction_id` - The unique ID of the pair interaction to delete.
+	///
+	/// # Panics
+	/// This method will panic if there is no pair interaction identified by
+	/// 	`interaction_id`.
+	pub fn delete_pair_interaction(&self, interaction_id: Uuid) {
+		if !self.pair_interactions.borrow().contains_key(&interaction_id) {
+			panic!(
+				"Simulation.delete_pair_interaction(): \
+					the provided pair interaction ID was not found: {}",
+				interaction_id,
+			);
+		}
+
+		self.pair_interaction_ids_to_delete.borrow_mut().push(interaction_id);
+	}
+
+	/// Sets whether particles and fields on one layer can trigger fields on
+	/// another layer. By default, every layer can trigger every layer.
+	/// Changes take effect immediately.
+	///
+	/// # Arguments
+	/// * `field_layer` - The index of the layer of the field being triggered.
+	/// * `other_layer` - The index of the layer of the particle or field
+	///		doing the triggering.
+	/// * `interacts` - If true, `other_layer` can trigger fields on
+	///		`field_layer`. If false, it can't.
+	///
+	/// This is one-directional. For example, to keep colliders on layer 1 from
+	/// colliding with each other while still colliding with everything else:
+	/// `simulation.set_layer_interaction(1, 1, false)`. To keep layers 1 and 2
+	/// from affecting each other at all, call this twice, swapping the layers.
+	///
+	/// # Panics
+	/// Panics if either layer index is not less than `Layers::COUNT`.
+	pub fn set_layer_interaction(&self, field_layer: u32, other_layer: u32, interacts: bool) {
+		let mask = self.get_layer_mask(field_layer);
+		let other = simulation_objects::Layers::layer(other_layer);
+		let new_mask = if interacts {
+			mask | other
+		} else {
+			simulation_objects::Layers::new(mask.get_bits() & !other.get_bits())
+		};
+		self.set_layer_mask(field_layer, new_mask);
+	}
+
+	/// Sets all of the layers that can trigger fields on a layer at once. See
+	/// `set_layer_interaction`.
+	///
+	/// # Arguments
+	/// * `field_layer` - The index of the layer of the field being triggered.
+	/// * `mask` - The layers that can trigger fields on `field_layer`.
+	///
+	/// # Panics
+	/// Panics if `field_layer` is not less than `Layers::COUNT`.
+	pub fn set_layer_mask(&self, field_layer: u32, mask: simulation_objects::Layers) {
+		// Validate the index.
+		simulation_objects::Layers::layer(field_layer);
+		self.layer_masks.borrow_mut()[field_layer as usize] = mask;
+	}
+
+	/// Gets the layers that can trigger fields on a layer.
+	///
+	/// # Arguments
+	/// * `field_layer` - The index of the layer of the field being triggered.
+	///
+	/// # Panics
+	/// Panics if `field_layer` is not less than `Layers::COUNT`.
+	pub fn get_layer_mask(&self, field_layer: u32) -> simulation_objects::Layers {
+		// Validate the index.
+		simulation_objects::Layers::layer(field_layer);
+		self.layer_masks.borrow()[field_layer as usize]
+	}
+
 	/// Applies a force to a specific particle for the duration of the next
 	/// tick.
 	///
@@ -2765,7 +6063,7 @@ impl Simulation {
 	pub fn apply_force(
 		&self,
 		particle_id: Uuid,
-		force: physical_quantities::Force,
+		force: physical_quantities::ForceN<V>,
 	) {
 		if !self.particles.borrow().contains_key(&particle_id) {
 			panic!(
@@ -2786,6 +6084,41 @@ impl Simulation {
 		}
 	}
 
+	/// Applies a torque to a specific particle for the duration of the next
+	/// tick. Particles created without a rotation can't spin, so they ignore
+	/// torques.
+	///
+	/// # Arguments
+	/// * `particle_id` - The unique ID of the particle to which to apply a
+	/// 	torque.
+	/// * `torque` - The torque to apply to the particle. Positive torques turn
+	/// 	it counterclockwise.
+	///
+	/// # Panics
+	/// This method will panic if there is no particle identified by
+	/// 	`particle_id`.
+	pub fn apply_torque(
+		&self,
+		particle_id: Uuid,
+		torque: physical_quantities::Torque<V::Scalar>,
+	) {
+		if !self.particles.borrow().contains_key(&particle_id) {
+			panic!(
+				"Simulation.apply_torque(): \
+					the provided particle ID was not found: {}",
+				particle_id,
+			);
+		}
+
+		let mut applied_torques = self.applied_torques.borrow_mut();
+		match applied_torques.get_mut(&particle_id) {
+			Some(vec) => vec.push(torque),
+			None => {
+				applied_torques.insert(particle_id, vec![torque]);
+			},
+		}
+	}
+
 	/// Gets the mass of a specific particle.
 	///
 	/// # Arguments
@@ -2795,55 +6128,190 @@ impl Simulation {
 	/// # Panics
 	/// This method will panic if there is no particle identified by
 	/// 	`particle_id`.
-	pub fn get_mass(&self, particle_id: Uuid) -> physical_quantities::Mass {
+	pub fn get_mass(&self, particle_id: Uuid) -> physical_quantities::Mass<V::Scalar> {
+		match self.particles.borrow().get(&particle_id) {
+			Some(particle) => return particle.get_mass(),
+			None =>
+				panic!(
+					"Simulation.get_mass(): \
+						the provided particle ID was not found: {}",
+					particle_id,
+				),
+		}
+	}
+
+	/// Gets the position (i.e., displacement from the origin) of a specific
+	/// particle.
+	///
+	/// # Arguments
+	/// * `particle_id` - The unique ID of the particle for which to retrieve
+	///		position.
+	///
+	/// # Panics
+	/// This method will panic if there is no particle identified by
+	/// 	`particle_id`.
+	pub fn get_position(&self, particle_id: Uuid) -> physical_quantities::DisplacementN<V> {
+		match self.particles.borrow().get(&particle_id) {
+			Some(particle) => return particle.get_position(),
+			None =>
+				panic!(
+					"Simulation.get_position(): \
+						the provided particle ID was not found: {}",
+					particle_id,
+				),
+		}
+	}
+
+	/// Gets the position of a specific particle before the most recent tick.
+	///
+	/// # Arguments
+	/// * `particle_id` - The unique ID of the particle for which to retrieve
+	///		position.
+	///
+	/// # Panics
+	/// This method will panic if there is no particle identified by
+	/// 	`particle_id`.
+	pub fn get_previous_position(&self, particle_id: Uuid) -> physical_quantities::DisplacementN<V> {
+		match self.particles.borrow().get(&particle_id) {
+			Some(particle) => particle.get_previous_position(),
+			None =>
+				panic!(
+					"Simulation.get_previous_position(): \
+						the provided particle ID was not found: {}",
+					particle_id,
+				),
+		}
+	}
+
+	/// Gets a position for a specific particle between its positions before
+	/// and after the most recent tick. Useful for drawing smooth motion when
+	/// frames don't line up with ticks.
+	///
+	/// # Arguments
+	/// * `particle_id` - The unique ID of the particle for which to retrieve
+	///		position.
+	/// * `alpha` - How far between the two positions to go. 0.0 gives the
+	///		previous position and 1.0 gives the current position. Usually
+	///		`Advance::get_alpha`.
+	///
+	/// # Panics
+	/// This method will panic if there is no particle identified by
+	/// 	`particle_id`.
+	pub fn get_interpolated_position(
+		&self,
+		particle_id: Uuid,
+		alpha: f64,
+	) -> physical_quantities::DisplacementN<V> {
+		match self.particles.borrow().get(&particle_id) {
+			Some(particle) => {
+				let previous = particle.get_previous_position();
+				let current = particle.get_position();
+				physical_quantities::DisplacementN::from_vector(
+					previous.get_vector()
+						+ (current.get_vector() - previous.get_vector()) * V::Scalar::from_f64(alpha)
+				)
+			},
+			None =>
+				panic!(
+					"Simulation.get_interpolated_position(): \
+						the provided particle ID was not found: {}",
+					particle_id,
+				),
+		}
+	}
+
+	/// Gets the velocity of a specific particle.
+	///
+	/// # Arguments
+	/// * `particle_id` - The unique ID of the particle for which to retrieve
+	///		velocity.
+	///
+	/// # Panics
+	/// This method will panic if there is no particle identified by
+	/// 	`particle_id`.
+	pub fn get_velocity(&self, particle_id: Uuid) -> physical_quantities::VelocityN<V> {
+		match self.particles.borrow().get(&particle_id) {
+			Some(particle) => return particle.get_velocity(),
+			None =>
+				panic!(
+					"Simulation.get_velocity(): \
+						the provided particle ID was not found: {}",
+					particle_id,
+				),
+		}
+	}
+
+	/// Gets the rotational state of a specific particle, or `None` if the
+	/// particle can't spin.
+	///
+	/// # Arguments
+	/// * `particle_id` - The unique ID of the particle for which to retrieve
+	///		rotation.
+	///
+	/// # Panics
+	/// This method will panic if there is no particle identified by
+	/// 	`particle_id`.
+	pub fn get_rotation(
+		&self,
+		particle_id: Uuid,
+	) -> Option<simulation_objects::Rotation<V::Scalar>> {
 		match self.particles.borrow().get(&particle_id) {
-			Some(particle) => return particle.get_mass(),
+			Some(particle) => particle.get_rotation(),
 			None =>
 				panic!(
-					"Simulation.get_mass(): \
+					"Simulation.get_rotation(): \
 						the provided particle ID was not found: {}",
 					particle_id,
 				),
 		}
 	}
 
-	/// Gets the position (i.e., displacement from the origin) of a specific
-	/// particle.
+	/// Gets the orientation of a specific particle. Particles that can't spin
+	/// are always at an angle of zero.
 	///
 	/// # Arguments
 	/// * `particle_id` - The unique ID of the particle for which to retrieve
-	///		position.
+	///		orientation.
 	///
 	/// # Panics
 	/// This method will panic if there is no particle identified by
 	/// 	`particle_id`.
-	pub fn get_position(&self, particle_id: Uuid) -> physical_quantities::Displacement {
+	pub fn get_orientation(&self, particle_id: Uuid) -> physical_quantities::Angle<V::Scalar> {
 		match self.particles.borrow().get(&particle_id) {
-			Some(particle) => return particle.get_position(),
+			Some(particle) => particle.get_rotation()
+				.map_or(physical_quantities::Angle::new(V::Scalar::zero()), |r| r.get_orientation()),
 			None =>
 				panic!(
-					"Simulation.get_position(): \
+					"Simulation.get_orientation(): \
 						the provided particle ID was not found: {}",
 					particle_id,
 				),
 		}
 	}
 
-	/// Gets the velocity of a specific particle.
+	/// Gets the angular velocity of a specific particle. Particles that can't
+	/// spin always have an angular velocity of zero.
 	///
 	/// # Arguments
 	/// * `particle_id` - The unique ID of the particle for which to retrieve
-	///		velocity.
+	///		angular velocity.
 	///
 	/// # Panics
 	/// This method will panic if there is no particle identified by
 	/// 	`particle_id`.
-	pub fn get_velocity(&self, particle_id: Uuid) -> physical_quantities::Velocity {
+	pub fn get_angular_velocity(
+		&self,
+		particle_id: Uuid,
+	) -> physical_quantities::AngularVelocity<V::Scalar> {
 		match self.particles.borrow().get(&particle_id) {
-			Some(particle) => return particle.get_velocity(),
+			Some(particle) => particle.get_rotation()
+				.map_or(
+					physical_quantities::AngularVelocity::new(V::Scalar::zero()),
+					|r| r.get_angular_velocity(),
+				),
 			None =>
 				panic!(
-					"Simulation.get_velocity(): \
+					"Simulation.get_angular_velocity(): \
 						the provided particle ID was not found: {}",
 					particle_id,
 				),
@@ -2872,26 +6340,93 @@ impl Simulation {
 		}
 	}
 
-	/// Starts the simulation. For v0, this will just start a loop that polls a
-	///		timer and calls tick(). For later versions, it will probably create
-	///		its own thread.
+	/// Gets the IDs of all particles in the simulation, sorted. Particles that
+	/// have been created, but not yet added to the simulation, are not
+	/// included.
+	pub fn get_particle_ids(&self) -> Vec<Uuid> {
+		let mut ids: Vec<Uuid> = self.particles.borrow().keys().copied().collect();
+		ids.sort();
+		ids
+	}
+
+	/// Starts the simulation. For v0, this will just start a loop that waits
+	///		for each tick to be due, according to the scheduling strategy, and
+	///		calls tick(). For later versions, it will probably create its own
+	///		thread. See `set_scheduling_strategy` and `get_timing_stats`.
 	pub fn start(&self) {
 		*self.is_paused.borrow_mut() = false;
 
+		let started = Instant::now();
+		// The first tick is due immediately.
+		let mut next_tick_due = started;
+
 		// Run until paused.
-		// TODO: Consider sleeping between ticks instead of constantly polling.
-		//	Maybe make sleep behavior a parameter in the constructor. The
-		//	tradeoff is that sleeping may make it harder for the simulation to
-		//	run at exactly the desired rate but not sleeping may block a
-		//	CPU thread for other programs.
 		while !*self.is_paused.borrow() {
-			if self.simulation_speed.is_none()
-				|| self.previous_tick_instant.borrow().elapsed()
-					>= self.tick_wait_duration
-			{
-				self.tick();
+			let is_paced = self.simulation_speed.borrow().is_some();
+			if is_paced {
+				self.wait_until(next_tick_due);
+			}
+
+			let tick_started = Instant::now();
+			self.tick();
+			let tick_duration = tick_started.elapsed();
+
+			let mut stats = self.timing_stats.borrow_mut();
+			stats.ticks += 1;
+			// Without a simulation speed, ticks are never late.
+			let tick_wait_duration = *self.tick_wait_duration.borrow();
+			if is_paced {
+				stats.total_lag += tick_started.saturating_duration_since(next_tick_due);
+				if tick_duration > tick_wait_duration {
+					stats.overrun_ticks += 1;
+				}
 			}
+			next_tick_due = tick_started + tick_wait_duration;
+		}
+
+		self.timing_stats.borrow_mut().running_time += started.elapsed();
+	}
+
+	// Waits, according to the scheduling strategy, until the given instant.
+	fn wait_until(&self, instant: Instant) {
+		match *self.scheduling_strategy.borrow() {
+			SchedulingStrategy::BusyWait => (),
+			SchedulingStrategy::Sleep => {
+				thread::sleep(instant.saturating_duration_since(Instant::now()));
+			},
+			SchedulingStrategy::Hybrid { spin } => {
+				let remaining = instant.saturating_duration_since(Instant::now());
+				if remaining > spin {
+					thread::sleep(remaining - spin);
+				}
+			},
 		}
+
+		while Instant::now() < instant {
+			std::hint::spin_loop();
+		}
+	}
+
+	/// Sets how `start` waits between ticks. Defaults to
+	/// `SchedulingStrategy::BusyWait`. Has no effect if the simulation speed
+	/// is `None`, since `start` doesn't wait at all.
+	pub fn set_scheduling_strategy(&self, strategy: SchedulingStrategy) {
+		*self.scheduling_strategy.borrow_mut() = strategy;
+	}
+
+	pub fn get_scheduling_strategy(&self) -> SchedulingStrategy {
+		*self.scheduling_strategy.borrow()
+	}
+
+	/// Gets statistics about how closely `start` has kept to its schedule,
+	/// accumulated over every call to `start` since the simulation was created
+	/// or `reset_timing_stats` was called.
+	pub fn get_timing_stats(&self) -> TimingStats {
+		*self.timing_stats.borrow()
+	}
+
+	pub fn reset_timing_stats(&self) {
+		*self.timing_stats.borrow_mut() = TimingStats::default();
 	}
 
 	/// Pauses the simulation. In v0, it will only be possible to call this
@@ -2951,27 +6486,858 @@ impl Simulation {
 			panic!("The simulation must be paused to call step_synchronized().");
 		}
 
-		if self.simulation_speed.is_none()
+		if self.simulation_speed.borrow().is_none()
 			|| self.previous_tick_instant.borrow().elapsed()
-				>= self.tick_wait_duration
+				>= *self.tick_wait_duration.borrow()
+		{
+			self.tick();
+		}
+	}
+
+	/// While the simulation is paused, executes as many ticks as it takes to
+	/// catch up with the given amount of real world time. Call this once per
+	/// frame with the time since the previous frame. Simulated time that
+	/// doesn't add up to a whole tick is carried over to the next call, so no
+	/// time is lost when frames are slow or uneven.
+	///
+	/// Real world time is converted to simulated time using the simulation
+	/// speed, or 1.0 if the simulation speed is `None`.
+	///
+	/// To keep a slow frame from causing ever longer frames, at most
+	/// `get_max_ticks_per_advance` ticks are executed per call. If more time
+	/// than that has accumulated, the extra whole ticks are dropped and the
+	/// simulation falls behind real time.
+	///
+	/// Returns the number of ticks executed and the fraction of a tick that
+	/// has accumulated toward the next one. Renderers can use the latter to
+	/// interpolate with `get_interpolated_position`.
+	///
+	/// # Arguments
+	/// * `real_elapsed` - The real world time since the previous call.
+	///
+	/// # Panics
+	/// This method will panic if the simulation is not paused.
+	pub fn advance(&self, real_elapsed: Duration) -> Advance {
+		if !*self.is_paused.borrow() {
+			panic!("The simulation must be paused to call advance().");
+		}
+
+		let speed = self.simulation_speed.borrow().unwrap_or(1.0);
+		*self.accumulated_time.borrow_mut() += real_elapsed.as_secs_f64() * speed;
+
+		// The tick duration may change during a tick, so check it each time.
+		let max_ticks = self.max_ticks_per_advance.borrow().get_number();
+		let mut ticks = 0;
+		while *self.accumulated_time.borrow() >= self.get_tick_duration().get_number().to_f64()
+			&& ticks < max_ticks
 		{
+			let tick_duration = self.get_tick_duration().get_number().to_f64();
 			self.tick();
+			*self.accumulated_time.borrow_mut() -= tick_duration;
+			ticks += 1;
+		}
+
+		// Drop any whole ticks the cap didn't allow.
+		let tick_duration = self.get_tick_duration().get_number().to_f64();
+		let mut accumulated_time = self.accumulated_time.borrow_mut();
+		*accumulated_time %= tick_duration;
+
+		Advance {
+			ticks: physical_quantities::Ticks::new(ticks),
+			alpha: *accumulated_time / tick_duration,
+		}
+	}
+
+	/// Sets the most ticks a single call to `advance` may execute. Defaults to
+	/// `DEFAULT_MAX_TICKS_PER_ADVANCE`.
+	///
+	/// # Panics
+	/// Panics if `max_ticks` is zero.
+	pub fn set_max_ticks_per_advance(&self, max_ticks: physical_quantities::Ticks) {
+		if max_ticks.get_number() == 0 {
+			panic!("max_ticks must be at least one tick");
+		}
+
+		*self.max_ticks_per_advance.borrow_mut() = max_ticks;
+	}
+
+	pub fn get_max_ticks_per_advance(&self) -> physical_quantities::Ticks {
+		*self.max_ticks_per_advance.borrow()
+	}
+
+	/// Removes and returns all events emitted since the last call to this
+	/// method, in the order in which they occurred.
+	///
+	/// Events accumulate until they are drained, so call this regularly (e.g.,
+	/// after each call to `step` or `step_synchronized`) if events are of
+	/// interest.
+	pub fn drain_events(&self) -> Vec<events::Event> {
+		self.events.take()
+	}
+
+	/// Registers a subscriber that will be passed every event emitted by the
+	/// simulation from now on. See `events::EventSubscriber`.
+	///
+	/// # Panics
+	/// This method will panic if called from within
+	/// `EventSubscriber::on_event`.
+	pub fn subscribe(&self, subscriber: Box<dyn events::EventSubscriber<V>>) {
+		self.event_subscribers.borrow_mut().push(subscriber);
+	}
+
+	/// Attaches a recorder, which will record frames at the end of ticks from
+	/// now on. Returns an ID that can be passed to `detach_recorder`. See
+	/// `recording::Recorder`.
+	pub fn attach_recorder(&self, recorder: recording::RecorderN<V>) -> Uuid {
+		let id = Uuid::new_v4();
+		self.recorders.borrow_mut().insert(id, recorder);
+		id
+	}
+
+	/// Detaches a recorder and returns it. Call `Recorder::finish` on the
+	/// result to flush it and check for errors.
+	///
+	/// # Arguments
+	/// * `recorder_id` - The ID returned by `attach_recorder`.
+	///
+	/// # Panics
+	/// This method will panic if the ID is not that of an attached recorder.
+	pub fn detach_recorder(&self, recorder_id: Uuid) -> recording::RecorderN<V> {
+		match self.recorders.borrow_mut().remove(&recorder_id) {
+			Some(recorder) => recorder,
+			None => panic!(
+				"Simulation.detach_recorder(): \
+					the provided recorder ID was not found: {}",
+				recorder_id,
+			),
 		}
 	}
 
+	/// Emits a user-defined event. Intended to be called from `Field::effect`
+	/// or `on_tick`, but may be called at any time. The event will be passed to
+	/// subscribers at the end of the current (or next, if called between ticks)
+	/// tick and can then be drained with `drain_events`.
+	///
+	/// # Arguments
+	/// * `source_id` - The ID of whatever emitted the event. Typically, the ID
+	///		of the particle that owns the emitting field.
+	/// * `name` - Identifies the kind of event.
+	/// * `payload` - Any data to attach to the event.
+	pub fn emit_event(&self, source_id: Uuid, name: String, payload: Box<dyn Any>) {
+		self.queue_event(events::EventKind::Custom {
+			source_id: source_id,
+			name: name,
+			payload: payload,
+		});
+	}
+
 	/// Returns the number of elapsed ticks since the start of the simulation.
 	pub fn get_elapsed_ticks(&self) -> physical_quantities::Ticks {
 		*self.elapsed_ticks.borrow()
 	}
 
 	/// Returns the amount of simulated time (e.g., seconds) since the start of
-	///	the simulation.
-	pub fn get_elapsed_time(&self) -> physical_quantities::Time {
-		self.tick_duration * self.elapsed_ticks.borrow().get_number() as f64
+	/// the simulation. Each tick counts for the tick duration in effect when
+	/// it was executed.
+	pub fn get_elapsed_time(&self) -> physical_quantities::Time<V::Scalar> {
+		let (changed_at_ticks, changed_at_time) = *self.tick_duration_changed_at.borrow();
+		let ticks_since_change = *self.elapsed_ticks.borrow() - changed_at_ticks;
+		changed_at_time
+			+ self.get_tick_duration() * V::Scalar::from_f64(ticks_since_change.get_number() as f64)
 	}
 
 	/// Returns the amount of simulated time that passes in a single tick.
-	pub fn get_tick_duration(&self) -> physical_quantities::Time {
-		self.tick_duration
+	pub fn get_tick_duration(&self) -> physical_quantities::Time<V::Scalar> {
+		*self.tick_duration.borrow()
+	}
+
+	/// Changes the amount of simulated time that passes in a single tick, e.g.,
+	/// to trade speed for precision. Ticks that have already been executed
+	/// keep counting for their original duration in `get_elapsed_time`. If
+	/// called during a tick (e.g., from `on_tick` or a field effect), the
+	/// change takes effect from the next tick.
+	///
+	/// With a simulation speed, the real world time between ticks changes in
+	/// proportion, so simulated time keeps passing at the same rate.
+	///
+	/// # Panics
+	/// Panics if `tick_duration` is less than or equal to zero.
+	pub fn set_tick_duration(&self, tick_duration: physical_quantities::Time<V::Scalar>) {
+		if tick_duration <= physical_quantities::Time::new(V::Scalar::zero()) {
+			panic!("tick_duration must be positive");
+		}
+
+		if *self.is_ticking.borrow() {
+			*self.pending_tick_duration.borrow_mut() = Some(tick_duration);
+		} else {
+			self.change_tick_duration(tick_duration);
+		}
+	}
+
+	/// Returns the speed at which the simulation runs, in simulated seconds per
+	/// real world second, or None if it runs as fast as possible.
+	pub fn get_simulation_speed(&self) -> Option<f64> {
+		*self.simulation_speed.borrow()
+	}
+
+	/// Changes the speed at which the simulation runs, e.g., for slow motion
+	/// or fast-forward. Only the real world time between ticks changes, so
+	/// the outcome of the simulation is the same at any speed. Takes effect
+	/// from the next tick.
+	///
+	/// # Arguments
+	/// * `simulation_speed` - Simulated seconds per real world second, or
+	///		None to run as fast as possible.
+	///
+	/// # Panics
+	/// Panics if `simulation_speed` is less than or equal to zero.
+	pub fn set_simulation_speed(&self, simulation_speed: Option<f64>) {
+		if let Some(speed) = simulation_speed
+			&& speed <= 0.0
+		{
+			panic!("simulation_speed must be positive");
+		}
+
+		*self.simulation_speed.borrow_mut() = simulation_speed;
+		*self.tick_wait_duration.borrow_mut() =
+			Self::calculate_wait_duration(self.get_tick_duration(), simulation_speed);
+	}
+
+	/// Returns the units that the numbers in the simulation are in. See
+	/// `set_unit_system`.
+	pub fn get_unit_system(&self) -> units::UnitSystem {
+		*self.unit_system.borrow()
+	}
+
+	/// Sets the units that the numbers in the simulation are in. Every
+	/// quantity passed to or returned by the simulation is in these units,
+	/// including the tick duration, so the simulation speed is in units of
+	/// time per real world second. Built-in fields that default to a
+	/// physical constant, like `UniversalGravitation`'s G, use its value in
+	/// these units. The simulation starts out in SI units.
+	///
+	/// Numbers already in the simulation are not converted, so this is
+	/// usually called before creating any particles.
+	///
+	/// # Arguments
+	/// * `unit_system` - The units to use, e.g. `UnitSystem::ASTRONOMICAL`.
+	pub fn set_unit_system(&self, unit_system: units::UnitSystem) {
+		*self.unit_system.borrow_mut() = unit_system;
+	}
+
+	// Sets the tick duration, starting a new span of elapsed time, and
+	//	recalculates how long to wait between ticks.
+	fn change_tick_duration(&self, tick_duration: physical_quantities::Time<V::Scalar>) {
+		*self.tick_duration_changed_at.borrow_mut() =
+			(self.get_elapsed_ticks(), self.get_elapsed_time());
+		*self.tick_duration.borrow_mut() = tick_duration;
+		*self.tick_wait_duration.borrow_mut() =
+			Self::calculate_wait_duration(tick_duration, self.get_simulation_speed());
+	}
+
+	// Calculates how long to wait between calls to tick() based on simulation
+	//	speed and tick duration.
+	fn calculate_wait_duration(
+		tick_duration: physical_quantities::Time<V::Scalar>,
+		simulation_speed: Option<f64>,
+	) -> Duration {
+		match simulation_speed {
+			Some(speed) => Duration::from_secs_f64(tick_duration.get_number().to_f64() / speed),
+			None => Duration::from_secs_f64(0.0),
+		}
+	}
+
+	/// Returns the total kinetic energy of all particles in the simulation,
+	/// including the energy of particles that are spinning.
+	pub fn get_kinetic_energy(&self) -> physical_quantities::Energy<V::Scalar> {
+		// KE = 1/2 m v^2. The compiler checks that kg m² / s² is an energy.
+		self.particles.borrow().values()
+			.map(|particle| {
+				let speed = particle.get_velocity().get_norm();
+				let translational = particle.get_mass() * V::Scalar::from_f64(0.5) * speed * speed;
+				match particle.get_rotation() {
+					Some(rotation) => translational + rotation.get_kinetic_energy(),
+					None => translational,
+				}
+			})
+			.sum()
+	}
+
+	/// Returns the total potential energy reported by the fields and pair
+	/// interactions in the simulation. See `Field::potential_energy` and
+	/// `PairInteraction::potential_energy`. Fields and interactions that don't
+	/// report a potential energy contribute nothing.
+	pub fn get_potential_energy(&self) -> physical_quantities::Energy<V::Scalar> {
+		let mut energy = physical_quantities::Energy::new(V::Scalar::zero());
+
+		for particle in self.particles.borrow().values() {
+			for field in particle.get_fields() {
+				if let Some(e) = field.potential_energy(
+					self,
+					particle.get_position(),
+					particle.get_id(),
+				) {
+					energy += e;
+				}
+			}
+		}
+
+		for world_field in self.world_fields.borrow().values() {
+			if let Some(e) = world_field.get_field().potential_energy(
+				self,
+				world_field.get_region().get_center(),
+				world_field.get_id(),
+			) {
+				energy += e;
+			}
+		}
+
+		self.for_each_interacting_pair(|interaction, id_a, position_a, id_b, position_b| {
+			if let Some(e) = interaction.potential_energy(
+				self,
+				id_a,
+				position_a,
+				id_b,
+				position_b,
+			) {
+				energy += e;
+			}
+		});
+
+		energy
+	}
+
+	/// Returns the sum of the kinetic and potential energy of the simulation.
+	pub fn get_total_energy(&self) -> physical_quantities::Energy<V::Scalar> {
+		self.get_kinetic_energy() + self.get_potential_energy()
+	}
+
+	/// Returns the total linear momentum of all particles in the simulation.
+	pub fn get_linear_momentum(&self) -> physical_quantities::MomentumN<V> {
+		self.particles.borrow().values()
+			.map(|particle| particle.get_mass() * particle.get_velocity())
+			.fold(physical_quantities::MomentumN::from_vector(V::zero()), |total, p| total + p)
+	}
+
+	/// Returns the center of mass of all particles in the simulation, or None
+	/// if there are no particles.
+	pub fn get_center_of_mass(&self) -> Option<physical_quantities::DisplacementN<V>> {
+		let particles = self.particles.borrow();
+		if particles.is_empty() {
+			return None;
+		}
+
+		let (total_mass, weighted_total) = particles.values()
+			.fold((V::Scalar::zero(), V::zero()), |(total_mass, weighted_total), particle| {
+				let m = particle.get_mass().get_number();
+				let position = particle.get_position();
+				(total_mass + m, weighted_total + position.get_vector() * m)
+			});
+
+		Some(physical_quantities::DisplacementN::from_vector(weighted_total / total_mass))
+	}
+
+	/// Returns the velocity of the center of mass of all particles in the
+	/// simulation, or None if there are no particles.
+	pub fn get_center_of_mass_velocity(&self) -> Option<physical_quantities::VelocityN<V>> {
+		let particles = self.particles.borrow();
+		if particles.is_empty() {
+			return None;
+		}
+
+		let total_mass: physical_quantities::Mass<V::Scalar> = particles.values()
+			.map(|particle| particle.get_mass())
+			.sum();
+
+		Some(self.get_linear_momentum() / total_mass)
+	}
+
+	/// Starts keeping snapshots of the simulation so it can be rewound with
+	/// `rewind_to`. Any snapshots kept so far are discarded.
+	///
+	/// # Arguments
+	/// * `interval` - The number of ticks between snapshots. Rewinding
+	///		re-simulates up to this many ticks, so smaller intervals make
+	///		rewinding faster but use more memory.
+	/// * `memory_budget` - The approximate number of bytes the snapshots may
+	///		use. The oldest snapshots are discarded to stay within the budget.
+	///		Fields are shared between the simulation and its snapshots, so
+	///		only the memory used to refer to them is counted.
+	///
+	/// # Panics
+	/// This method will panic if `interval` is zero.
+	pub fn enable_history(&self, interval: physical_quantities::Ticks, memory_budget: usize) {
+		if interval.get_number() == 0 {
+			panic!("interval must be at least one tick");
+		}
+
+		*self.history.borrow_mut() = Some(History::new(interval, memory_budget));
+	}
+
+	/// Stops keeping snapshots and discards those kept so far.
+	pub fn disable_history(&self) {
+		*self.history.borrow_mut() = None;
+	}
+
+	/// Returns the earliest tick that `rewind_to` can currently go back to, or
+	/// None if no snapshots have been kept.
+	pub fn get_earliest_rewind_tick(&self) -> Option<physical_quantities::Ticks> {
+		self.history.borrow().as_ref()
+			.and_then(|history| history.snapshots.front())
+			.map(|(snapshot, _)| snapshot.elapsed_ticks)
+	}
+
+	/// Returns the simulation to the state it was in when `tick` ticks had
+	/// elapsed. The latest snapshot taken at or before `tick` is restored, then
+	/// the remaining ticks are re-simulated. Snapshots taken after `tick` are
+	/// discarded.
+	///
+	/// Re-simulated ticks call `on_tick` and field effects as usual, so
+	/// anything they do happens again, but they don't emit events or record
+	/// frames. Particles and fields created while re-simulating get the same
+	/// IDs as the first time. Anything done from outside the simulation
+	/// (e.g., calling `apply_force` between calls to `step`) after the
+	/// snapshot is not repeated. Fields are shared with the snapshots, so
+	/// state kept inside a field (e.g., in a `RefCell`) is not rewound.
+	///
+	/// # Arguments
+	/// * `tick` - The number of elapsed ticks to rewind to.
+	///
+	/// # Panics
+	/// This method will panic if the simulation is not paused, if history is
+	/// not enabled, if `tick` is after the current tick, or if `tick` is
+	/// before the earliest snapshot (see `get_earliest_rewind_tick`).
+	pub fn rewind_to(&self, tick: physical_quantities::Ticks) {
+		if !*self.is_paused.borrow() {
+			panic!("The simulation must be paused to call rewind_to().");
+		}
+
+		let elapsed_ticks = self.get_elapsed_ticks();
+		if tick > elapsed_ticks {
+			panic!(
+				"Simulation.rewind_to(): \
+					cannot rewind to tick {}, which is after the current tick {}",
+				tick.get_number(),
+				elapsed_ticks.get_number(),
+			);
+		}
+
+		let snapshot = match self.history.borrow_mut().as_mut() {
+			Some(history) => match history.rewind(tick) {
+				Some(snapshot) => snapshot,
+				None => panic!(
+					"Simulation.rewind_to(): \
+						tick {} is before the earliest snapshot",
+					tick.get_number(),
+				),
+			},
+			None => panic!("Simulation.rewind_to(): history is not enabled"),
+		};
+
+		*self.elapsed_ticks.borrow_mut() = snapshot.elapsed_ticks;
+		*self.tick_duration.borrow_mut() = snapshot.tick_duration;
+		*self.tick_duration_changed_at.borrow_mut() = snapshot.tick_duration_changed_at;
+		*self.tick_wait_duration.borrow_mut() =
+			Self::calculate_wait_duration(snapshot.tick_duration, self.get_simulation_speed());
+		*self.particles.borrow_mut() = snapshot.particles;
+		*self.applied_forces.borrow_mut() = snapshot.applied_forces;
+		*self.applied_torques.borrow_mut() = snapshot.applied_torques;
+		*self.particle_ids_to_delete.borrow_mut() = snapshot.particle_ids_to_delete;
+		*self.particles_to_add.borrow_mut() = snapshot.particles_to_add;
+		*self.world_fields.borrow_mut() = snapshot.world_fields;
+		*self.world_fields_to_add.borrow_mut() = snapshot.world_fields_to_add;
+		*self.world_field_ids_to_delete.borrow_mut() = snapshot.world_field_ids_to_delete;
+		*self.pair_interactions.borrow_mut() = snapshot.pair_interactions;
+		*self.pair_interactions_to_add.borrow_mut() = snapshot.pair_interactions_to_add;
+		*self.pair_interaction_ids_to_delete.borrow_mut() =
+			snapshot.pair_interaction_ids_to_delete;
+		*self.layer_masks.borrow_mut() = snapshot.layer_masks;
+		*self.active_triggers.borrow_mut() = snapshot.active_triggers;
+		self.tick_events.borrow_mut().clear();
+
+		*self.is_replaying.borrow_mut() = true;
+		while self.get_elapsed_ticks() < tick {
+			self.tick();
+		}
+		*self.is_replaying.borrow_mut() = false;
+	}
+}
+
+impl<S: physical_quantities::Scalar> Simulation<S> {
+	/// Returns the total angular momentum of all particles in the simulation
+	/// about a point, including the spin of particles that are rotating.
+	/// Positive values are counterclockwise.
+	///
+	/// # Arguments
+	/// * `point` - The point about which to measure angular momentum (e.g.,
+	///		the origin or the center of mass).
+	pub fn get_angular_momentum(
+		&self,
+		point: physical_quantities::Displacement<S>,
+	) -> physical_quantities::AngularMomentum<S> {
+		// L = r x p
+		let angular_momentum = self.particles.borrow().values()
+			.map(|particle| {
+				let r = particle.get_position() - point;
+				let p = particle.get_mass() * particle.get_velocity();
+				let spin = particle.get_rotation()
+					.map_or(S::zero(), |rotation| rotation.get_angular_momentum().get_number());
+				r.get_vector().cross(p.get_vector()) + spin
+			})
+			.fold(S::zero(), |total, l| total + l);
+
+		physical_quantities::AngularMomentum::new(angular_momentum)
+	}
+}
+
+impl<S: physical_quantities::Scalar> Simulation3<S> {
+	/// Returns the total angular momentum of all particles in the simulation
+	/// about a point, as a vector. Its direction is the axis of rotation
+	/// (counterclockwise when viewed from the tip of the vector) and its
+	/// magnitude is the angular momentum about that axis. Spinning particles
+	/// rotate about the z axis, so their spin adds to the z component.
+	///
+	/// # Arguments
+	/// * `point` - The point about which to measure angular momentum (e.g.,
+	///		the origin or the center of mass).
+	pub fn get_angular_momentum(
+		&self,
+		point: physical_quantities::Displacement3<S>,
+	) -> physical_quantities::Vector3<S> {
+		// L = r x p
+		self.particles.borrow().values()
+			.map(|particle| {
+				let r = particle.get_position() - point;
+				let p = particle.get_mass() * particle.get_velocity();
+				let spin = particle.get_rotation()
+					.map_or(S::zero(), |rotation| rotation.get_angular_momentum().get_number());
+				r.get_vector().cross(p.get_vector()) + physical_quantities::Vector3::new(S::zero(), S::zero(), spin)
+			})
+			.fold(physical_quantities::Vector::zero(), |total, l| total + l)
+	}
+}
+
+#[cfg(feature = "persistence")]
+impl Simulation {
+	/// Captures everything needed to recreate this simulation, including
+	/// forces, particles, and fields that have been queued but not yet
+	/// applied. See the `persistence` module for saving the state to JSON or
+	/// binary. Only two-dimensional `f64` simulations can be saved.
+	///
+	/// Returns an error if any field or pair interaction doesn't support
+	/// saving.
+	pub fn save_state(&self) -> Result<persistence::SimulationState, persistence::PersistenceError> {
+		let mut particles = self.particles.borrow().values()
+			.map(Self::save_particle)
+			.collect::<Result<Vec<_>, _>>()?;
+		// Sort everything keyed by ID so saving the same simulation twice
+		//	produces the same output.
+		particles.sort_by_key(|p| p.id);
+
+		let mut applied_forces: Vec<(Uuid, Vec<physical_quantities::Force>)> =
+			self.applied_forces.borrow().iter()
+			.map(|(id, forces)| (*id, forces.clone()))
+			.collect();
+		applied_forces.sort_by_key(|(id, _)| *id);
+		let mut applied_torques: Vec<(Uuid, Vec<physical_quantities::Torque>)> =
+			self.applied_torques.borrow().iter()
+			.map(|(id, torques)| (*id, torques.clone()))
+			.collect();
+		applied_torques.sort_by_key(|(id, _)| *id);
+
+		Ok(persistence::SimulationState {
+			version: persistence::FORMAT_VERSION,
+			tick_duration: self.get_tick_duration(),
+			simulation_speed: self.get_simulation_speed(),
+			unit_system: self.get_unit_system(),
+			elapsed_ticks: *self.elapsed_ticks.borrow(),
+			elapsed_time: Some(self.get_elapsed_time()),
+			particles: particles,
+			world_fields: self.world_fields.borrow().values()
+				.map(Self::save_world_field)
+				.collect::<Result<_, _>>()?,
+			pair_interactions: self.pair_interactions.borrow().values()
+				.map(Self::save_pair_interaction)
+				.collect::<Result<_, _>>()?,
+			layer_masks: self.layer_masks.borrow().to_vec(),
+			active_triggers: self.active_triggers.borrow().iter()
+				.map(|((owner_id, field_index, particle_id), name)| persistence::TriggerState {
+					field_owner_id: *owner_id,
+					field_index: *field_index,
+					particle_id: *particle_id,
+					field_name: name.clone(),
+				})
+				.collect(),
+			applied_forces: applied_forces,
+			applied_torques: applied_torques,
+			particle_ids_to_delete: self.particle_ids_to_delete.borrow().clone(),
+			particles_to_add: self.particles_to_add.borrow().iter()
+				.map(Self::save_particle)
+				.collect::<Result<_, _>>()?,
+			world_field_ids_to_delete: self.world_field_ids_to_delete.borrow().clone(),
+			world_fields_to_add: self.world_fields_to_add.borrow().iter()
+				.map(Self::save_world_field)
+				.collect::<Result<_, _>>()?,
+			pair_interaction_ids_to_delete: self.pair_interaction_ids_to_delete.borrow().clone(),
+			pair_interactions_to_add: self.pair_interactions_to_add.borrow().iter()
+				.map(Self::save_pair_interaction)
+				.collect::<Result<_, _>>()?,
+			max_ticks_per_advance: Some(self.get_max_ticks_per_advance()),
+			scheduling_strategy: Some(self.get_scheduling_strategy()),
+		})
+	}
+
+	/// Recreates a simulation from a state captured with `save_state`. The
+	/// new simulation is paused and has no event subscribers.
+	///
+	/// # Arguments
+	/// * `state` - The saved state.
+	/// * `registry` - Used to recreate fields and pair interactions from
+	///		their type tags.
+	/// * `on_tick` - The function to call on each tick. See `Simulation::new`.
+	///
+	/// Returns an error if the state was saved by an incompatible version,
+	/// if a field or pair interaction can't be recreated, or if a value is
+	/// invalid, e.g., a tick duration or particle mass that isn't positive, or
+	/// two particles with the same ID.
+	pub fn from_state(
+		state: persistence::SimulationState,
+		registry: &persistence::Registry,
+		on_tick: Option<fn(&Simulation)>,
+	) -> Result<Self, persistence::PersistenceError> {
+		if state.version != persistence::FORMAT_VERSION {
+			return Err(persistence::PersistenceError::UnsupportedVersion {
+				found: state.version,
+				supported: persistence::FORMAT_VERSION,
+			});
+		}
+
+		// Check everything `Simulation::new`, the setters and `tick` would panic
+		//	on, since the state may come from an untrusted file.
+		let invalid_state = |message: &str| persistence::PersistenceError::InvalidState {
+			message: message.to_string(),
+		};
+		let tick_duration = state.tick_duration.get_number();
+		if !(tick_duration.is_finite() && tick_duration > 0.0) {
+			return Err(invalid_state("tick duration must be positive and finite"));
+		}
+		if let Some(speed) = state.simulation_speed && !(speed.is_finite() && speed > 0.0) {
+			return Err(invalid_state("simulation speed must be positive and finite"));
+		}
+		if state.layer_masks.len() > simulation_objects::Layers::COUNT as usize {
+			return Err(invalid_state("too many layer masks"));
+		}
+		if let Some(max_ticks) = state.max_ticks_per_advance && max_ticks.get_number() == 0 {
+			return Err(invalid_state("max ticks per advance must be at least one tick"));
+		}
+		let mut particle_ids = std::collections::HashSet::new();
+		for particle in state.particles.iter().chain(&state.particles_to_add) {
+			if !particle_ids.insert(particle.id) {
+				return Err(persistence::PersistenceError::InvalidState {
+					message: format!("more than one particle has the ID {}", particle.id),
+				});
+			}
+		}
+
+		let simulation = Simulation::new(state.tick_duration, state.simulation_speed, on_tick);
+		simulation.set_unit_system(state.unit_system);
+		*simulation.elapsed_ticks.borrow_mut() = state.elapsed_ticks;
+		// State saved before the tick duration could change doesn't include
+		//	the elapsed time, but it's just the ticks times the duration.
+		if let Some(elapsed_time) = state.elapsed_time {
+			*simulation.tick_duration_changed_at.borrow_mut() = (state.elapsed_ticks, elapsed_time);
+		}
+
+		for particle in state.particles {
+			let particle = Self::load_particle(particle, registry)?;
+			simulation.particles.borrow_mut().insert(particle.get_id(), particle);
+		}
+		for world_field in state.world_fields {
+			let world_field = Self::load_world_field(world_field, registry)?;
+			simulation.world_fields.borrow_mut().insert(world_field.get_id(), world_field);
+		}
+		for entry in state.pair_interactions {
+			let entry = Self::load_pair_interaction(entry, registry)?;
+			simulation.pair_interactions.borrow_mut().insert(entry.get_id(), entry);
+		}
+		for (index, mask) in state.layer_masks.into_iter().enumerate() {
+			simulation.set_layer_mask(index as u32, mask);
+		}
+		if let Some(max_ticks) = state.max_ticks_per_advance {
+			simulation.set_max_ticks_per_advance(max_ticks);
+		}
+		if let Some(strategy) = state.scheduling_strategy {
+			simulation.set_scheduling_strategy(strategy);
+		}
+		*simulation.active_triggers.borrow_mut() = state.active_triggers.into_iter()
+			.map(|t| ((t.field_owner_id, t.field_index, t.particle_id), t.field_name))
+			.collect();
+
+		*simulation.applied_forces.borrow_mut() = state.applied_forces.into_iter().collect();
+		*simulation.applied_torques.borrow_mut() = state.applied_torques.into_iter().collect();
+		*simulation.particle_ids_to_delete.borrow_mut() = state.particle_ids_to_delete;
+		*simulation.particles_to_add.borrow_mut() = state.particles_to_add.into_iter()
+			.map(|p| Self::load_particle(p, registry))
+			.collect::<Result<_, _>>()?;
+		*simulation.world_field_ids_to_delete.borrow_mut() = state.world_field_ids_to_delete;
+		*simulation.world_fields_to_add.borrow_mut() = state.world_fields_to_add.into_iter()
+			.map(|w| Self::load_world_field(w, registry))
+			.collect::<Result<_, _>>()?;
+		*simulation.pair_interaction_ids_to_delete.borrow_mut() =
+			state.pair_interaction_ids_to_delete;
+		*simulation.pair_interactions_to_add.borrow_mut() =
+			state.pair_interactions_to_add.into_iter()
+			.map(|e| Self::load_pair_interaction(e, registry))
+			.collect::<Result<_, _>>()?;
+
+		Ok(simulation)
+	}
+
+	fn save_fields(
+		fields: &[std::rc::Rc<dyn simulation_objects::Field>],
+	) -> Result<Vec<persistence::SavedObject>, persistence::PersistenceError> {
+		fields.iter()
+			.map(|field| field.save().ok_or_else(|| {
+				persistence::PersistenceError::UnsavableField {
+					name: field.get_name().clone(),
+				}
+			}))
+			.collect()
+	}
+
+	fn save_particle(
+		particle: &simulation_objects::Particle,
+	) -> Result<persistence::ParticleState, persistence::PersistenceError> {
+		Ok(persistence::ParticleState {
+			id: particle.get_id(),
+			mass: particle.get_mass(),
+			position: particle.get_position(),
+			velocity: particle.get_velocity(),
+			rotation: particle.get_rotation(),
+			previous_position: Some(particle.get_previous_position()),
+			fields: Self::save_fields(particle.get_fields())?,
+		})
+	}
+
+	fn save_world_field(
+		world_field: &simulation_objects::WorldField,
+	) -> Result<persistence::WorldFieldState, persistence::PersistenceError> {
+		let field = world_field.get_field();
+		Ok(persistence::WorldFieldState {
+			id: world_field.get_id(),
+			region: world_field.get_region().clone(),
+			field: field.save().ok_or_else(|| persistence::PersistenceError::UnsavableField {
+				name: field.get_name().clone(),
+			})?,
+		})
+	}
+
+	fn save_pair_interaction(
+		entry: &simulation_objects::PairInteractionEntry,
+	) -> Result<persistence::PairInteractionState, persistence::PersistenceError> {
+		let interaction = entry.get_interaction().save().ok_or_else(|| {
+			persistence::PersistenceError::UnsavablePairInteraction {
+				field_name_a: entry.get_field_name_a().clone(),
+				field_name_b: entry.get_field_name_b().clone(),
+			}
+		})?;
+
+		Ok(persistence::PairInteractionState {
+			id: entry.get_id(),
+			field_name_a: entry.get_field_name_a().clone(),
+			field_name_b: entry.get_field_name_b().clone(),
+			interaction: interaction,
+		})
+	}
+
+	fn load_particle(
+		state: persistence::ParticleState,
+		registry: &persistence::Registry,
+	) -> Result<simulation_objects::Particle, persistence::PersistenceError> {
+		// Quantities are deserialized directly, so check what their
+		//	constructors would have.
+		let invalid_particle = |message: &str| persistence::PersistenceError::InvalidState {
+			message: format!("particle {}: {}", state.id, message),
+		};
+		let mass = state.mass.get_number();
+		if !(mass.is_finite() && mass > 0.0) {
+			return Err(invalid_particle("mass must be positive and finite"));
+		}
+		if let Some(rotation) = &state.rotation {
+			let moment_of_inertia = rotation.get_moment_of_inertia().get_number();
+			if !(moment_of_inertia.is_finite() && moment_of_inertia > 0.0) {
+				return Err(invalid_particle("moment of inertia must be positive and finite"));
+			}
+		}
+		for saved in &state.fields {
+			Self::check_softening_length(saved)?;
+		}
+
+		let fields = state.fields.into_iter()
+			.map(|saved| registry.load_field(saved))
+			.collect::<Result<_, _>>()?;
+
+		let particle = simulation_objects::Particle::from_parts(
+			state.id,
+			state.mass,
+			state.position,
+			state.velocity,
+			fields,
+		);
+		let particle = match state.previous_position {
+			Some(previous_position) => particle.with_previous_position(previous_position),
+			None => particle,
+		};
+
+		Ok(match state.rotation {
+			Some(rotation) => particle.with_rotation(rotation),
+			None => particle,
+		})
+	}
+
+	fn load_world_field(
+		state: persistence::WorldFieldState,
+		registry: &persistence::Registry,
+	) -> Result<simulation_objects::WorldField, persistence::PersistenceError> {
+		Self::check_softening_length(&state.field)?;
+		Ok(simulation_objects::WorldField::from_parts(
+			state.id,
+			state.region,
+			registry.load_field(state.field)?,
+		))
+	}
+
+	fn load_pair_interaction(
+		state: persistence::PairInteractionState,
+		registry: &persistence::Registry,
+	) -> Result<simulation_objects::PairInteractionEntry, persistence::PersistenceError> {
+		Self::check_softening_length(&state.interaction)?;
+		Ok(simulation_objects::PairInteractionEntry::from_parts(
+			state.id,
+			state.field_name_a,
+			state.field_name_b,
+			registry.load_pair_interaction(state.interaction)?,
+		))
+	}
+
+	// Loading skips the checks in the built-in gravity types' constructors, so
+	//	check their softening lengths before loading them.
+	fn check_softening_length(
+		saved: &persistence::SavedObject,
+	) -> Result<(), persistence::PersistenceError> {
+		let gravitation = match saved.get_type_tag().as_str() {
+			"UniversalGravitationField" => saved.get_data().get("gravitation"),
+			"UniversalGravitation" => Some(saved.get_data()),
+			_ => None,
+		};
+		let softening_length = gravitation
+			.and_then(|g| g.get("softening_length"))
+			.and_then(|s| s.as_f64());
+		match softening_length {
+			Some(s) if s < 0.0 => Err(persistence::PersistenceError::InvalidState {
+				message: format!("{}: softening_length must not be negative", saved.get_type_tag()),
+			}),
+			_ => Ok(()),
+		}
 	}
 }
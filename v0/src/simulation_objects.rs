@@ -1,5 +1,9 @@
-use crate::{physical_quantities, simulation, utilities};
+use crate::{events, physical_quantities, simulation, utilities};
+#[cfg(feature = "persistence")]
+use crate::persistence;
+use crate::physical_quantities::Scalar;
 use std::collections::HashMap;
+use std::rc::Rc;
 use uuid::Uuid;
 
 
@@ -15,23 +19,217 @@ mod tests {
 		let collider1 = Collider::new(
 			100.0,
 			50.0,
+			Some(Layers::layer(3)),
 			Some(String::from("SomeName")),
 		);
 		let collider2 = Collider::new(
 			50.0,
 			100.0,
 			None,
+			None,
 		);
 
 		assert_eq!(100.0, collider1.get_radius());
 		assert_eq!(50.0, collider1.coefficient_of_restitution);
+		assert_eq!(Layers::layer(3), collider1.get_layers());
 		assert_eq!(&String::from("SomeName"), collider1.get_name());
 
 		assert_eq!(50.0, collider2.get_radius());
 		assert_eq!(100.0, collider2.coefficient_of_restitution);
+		assert_eq!(Layers::DEFAULT, collider2.get_layers());
 		assert_eq!(&String::from("Collider"), collider2.get_name());
 	}
 
+	#[test]
+	fn collider_field_info_includes_coefficient_of_restitution() {
+		let particle = Particle::new(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(0.0, 0.0),
+			vec!(
+				Box::new(Collider::new(1.0, 0.5, None, None)),
+				Box::new(DummyField {
+					radius: 1.0,
+					affects_self: false,
+					affects_others: false,
+					name: String::from("dummy"),
+				}),
+			),
+		);
+		let info = particle.get_field_info();
+
+		assert_eq!(Some(0.5), info[0].get_coefficient_of_restitution());
+		assert_eq!(None, info[1].get_coefficient_of_restitution());
+	}
+
+	/********************* Region ********************/
+
+	#[test]
+	fn everywhere_region_contains_every_point() {
+		let region = Region::Everywhere;
+		assert!(region.contains(physical_quantities::Displacement::new(0.0, 0.0)));
+		assert!(region.contains(physical_quantities::Displacement::new(-1.0e12, 1.0e12)));
+		assert_eq!(
+			region.get_center(),
+			physical_quantities::Displacement::new(0.0, 0.0),
+		);
+	}
+
+	#[test]
+	fn circle_region_contains_points_inside_radius() {
+		let region = Region::Circle {
+			center: physical_quantities::Displacement::new(1.0, 1.0),
+			radius: 2.0,
+		};
+		assert!(region.contains(physical_quantities::Displacement::new(1.0, 1.0)));
+		assert!(region.contains(physical_quantities::Displacement::new(2.9, 1.0)));
+		// On the boundary.
+		assert!(!region.contains(physical_quantities::Displacement::new(3.0, 1.0)));
+		assert!(!region.contains(physical_quantities::Displacement::new(3.0, 3.0)));
+		assert_eq!(
+			region.get_center(),
+			physical_quantities::Displacement::new(1.0, 1.0),
+		);
+	}
+
+	#[test]
+	fn rectangle_region_contains_points_inside_corners() {
+		// Corners given in "reverse" order should work too.
+		let region = Region::Rectangle {
+			min: physical_quantities::Displacement::new(4.0, 2.0),
+			max: physical_quantities::Displacement::new(-4.0, -2.0),
+		};
+		assert!(region.contains(physical_quantities::Displacement::new(0.0, 0.0)));
+		assert!(region.contains(physical_quantities::Displacement::new(-3.9, 1.9)));
+		assert!(!region.contains(physical_quantities::Displacement::new(4.0, 0.0)));
+		assert!(!region.contains(physical_quantities::Displacement::new(0.0, -2.5)));
+		assert_eq!(
+			region.get_center(),
+			physical_quantities::Displacement::new(0.0, 0.0),
+		);
+	}
+
+	#[test]
+	fn polygon_region_contains_points_inside_concave_polygon() {
+		// A "U" shape, open at the top.
+		let region = Region::Polygon {
+			vertices: vec!(
+				physical_quantities::Displacement::new(0.0, 0.0),
+				physical_quantities::Displacement::new(3.0, 0.0),
+				physical_quantities::Displacement::new(3.0, 3.0),
+				physical_quantities::Displacement::new(2.0, 3.0),
+				physical_quantities::Displacement::new(2.0, 1.0),
+				physical_quantities::Displacement::new(1.0, 1.0),
+				physical_quantities::Displacement::new(1.0, 3.0),
+				physical_quantities::Displacement::new(0.0, 3.0),
+			),
+		};
+		assert!(region.contains(physical_quantities::Displacement::new(0.5, 2.5)));
+		assert!(region.contains(physical_quantities::Displacement::new(2.5, 2.5)));
+		assert!(region.contains(physical_quantities::Displacement::new(1.5, 0.5)));
+		// Inside the "U", but outside the polygon.
+		assert!(!region.contains(physical_quantities::Displacement::new(1.5, 2.0)));
+		assert!(!region.contains(physical_quantities::Displacement::new(-1.0, 0.5)));
+		assert!(!region.contains(physical_quantities::Displacement::new(1.5, 3.5)));
+	}
+
+	#[test]
+	fn degenerate_polygon_region_contains_nothing() {
+		let region = Region::Polygon {
+			vertices: vec!(
+				physical_quantities::Displacement::new(0.0, 0.0),
+				physical_quantities::Displacement::new(3.0, 0.0),
+			),
+		};
+		assert!(!region.contains(physical_quantities::Displacement::new(1.0, 0.0)));
+	}
+
+	#[test]
+	fn regions_work_in_three_dimensions() {
+		let sphere = Region::Circle {
+			center: physical_quantities::Displacement3::new(0.0, 0.0, 1.0),
+			radius: 1.0,
+		};
+		assert!(sphere.contains(physical_quantities::Displacement3::new(0.0, 0.0, 1.9)));
+		assert!(!sphere.contains(physical_quantities::Displacement3::new(0.0, 0.0, -0.1)));
+
+		let box_region = Region::Rectangle {
+			min: physical_quantities::Displacement3::new(2.0, 2.0, 2.0),
+			max: physical_quantities::Displacement3::new(-2.0, -2.0, 0.0),
+		};
+		assert!(box_region.contains(physical_quantities::Displacement3::new(1.0, -1.0, 1.0)));
+		assert!(!box_region.contains(physical_quantities::Displacement3::new(1.0, -1.0, 3.0)));
+		assert_eq!(
+			box_region.get_center(),
+			physical_quantities::Displacement3::new(0.0, 0.0, 1.0),
+		);
+
+		// Polygons ignore z.
+		let prism = Region::Polygon {
+			vertices: vec!(
+				physical_quantities::Displacement3::new(0.0, 0.0, 0.0),
+				physical_quantities::Displacement3::new(2.0, 0.0, 5.0),
+				physical_quantities::Displacement3::new(2.0, 2.0, -5.0),
+				physical_quantities::Displacement3::new(0.0, 2.0, 0.0),
+			),
+		};
+		assert!(prism.contains(physical_quantities::Displacement3::new(1.0, 1.0, 100.0)));
+		assert!(!prism.contains(physical_quantities::Displacement3::new(3.0, 1.0, 0.0)));
+	}
+
+	/********************* Layers ********************/
+
+	#[test]
+	fn layers_contain_their_layers() {
+		let layers = Layers::layer(0) | Layers::layer(31);
+		assert_eq!(layers.get_bits(), 0x8000_0001);
+		assert!(layers.contains(0));
+		assert!(layers.contains(31));
+		assert!(!layers.contains(1));
+		assert!(!layers.contains(32));
+		assert_eq!(Layers::layer(0), Layers::DEFAULT);
+		assert!(!Layers::NONE.contains(0));
+		assert!(Layers::ALL.contains(17));
+	}
+
+	#[test]
+	fn layers_intersect_when_sharing_a_layer() {
+		let a = Layers::new(0b0110);
+		assert!(a.intersects(Layers::layer(2)));
+		assert!(!a.intersects(Layers::layer(3)));
+		assert!(!a.intersects(Layers::NONE));
+		assert_eq!(a.union(Layers::layer(3)), Layers::new(0b1110));
+	}
+
+	#[test]
+	#[should_panic(expected = "Layer index must be less than 32: 32")]
+	fn layers_layer_panics_on_invalid_index() {
+		Layers::layer(32);
+	}
+
+	#[test]
+	fn particle_is_on_its_fields_layers() {
+		let unlayered = Particle::new(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(0.0, 0.0),
+			Vec::new(),
+		);
+		let layered = Particle::new(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(0.0, 0.0),
+			vec!(
+				Box::new(Collider::new(1.0, 1.0, Some(Layers::layer(2)), None)),
+				Box::new(Collider::new(1.0, 1.0, Some(Layers::layer(5)), None)),
+			),
+		);
+
+		assert_eq!(unlayered.get_layers(), Layers::DEFAULT);
+		assert_eq!(layered.get_layers(), Layers::layer(2) | Layers::layer(5));
+		assert_eq!(layered.get_field_info()[1].get_layers(), Layers::layer(5));
+	}
+
 
 	/********************* Particle ********************/
 
@@ -55,13 +253,73 @@ mod tests {
 		assert_eq!(particle.velocity, physical_quantities::Velocity::new(0.0, 0.0));
 		assert_eq!(particle.fields.len(), 1);
 	}
+
+	#[test]
+	fn particle_remembers_previous_position() {
+		let mut particle = Particle::new(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(1.0, 1.0),
+			physical_quantities::Velocity::new(1.0, 0.0),
+			Vec::new(),
+		);
+		assert_eq!(particle.get_previous_position(), physical_quantities::Displacement::new(1.0, 1.0));
+
+		particle.coast(physical_quantities::Time::new(1.0));
+		assert_eq!(particle.get_previous_position(), physical_quantities::Displacement::new(1.0, 1.0));
+		assert_eq!(particle.get_position(), physical_quantities::Displacement::new(2.0, 1.0));
+
+		particle.accelerate(
+			&[physical_quantities::Force::new(0.0, 4.0)],
+			physical_quantities::Time::new(1.0),
+		);
+		assert_eq!(particle.get_previous_position(), physical_quantities::Displacement::new(2.0, 1.0));
+		assert_eq!(particle.get_position(), physical_quantities::Displacement::new(3.0, 2.0));
+	}
+
+	#[test]
+	fn particle_turns_only_with_rotation() {
+		let mut particle: Particle = Particle::new(
+			physical_quantities::Mass::new(2.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			physical_quantities::Velocity::new(0.0, 0.0),
+			Vec::new(),
+		);
+		particle.turn(&[physical_quantities::Torque::new(1.0)], physical_quantities::Time::new(1.0));
+		assert_eq!(particle.get_rotation(), None);
+
+		let mut particle = particle.with_rotation(Rotation::new(
+			physical_quantities::Angle::new(1.0),
+			physical_quantities::AngularVelocity::new(0.5),
+			physical_quantities::MomentOfInertia::new(2.0),
+		));
+		particle.turn(&[], physical_quantities::Time::new(2.0));
+		let rotation = particle.get_rotation().unwrap();
+		assert_eq!(rotation.get_orientation(), physical_quantities::Angle::new(2.0));
+		assert_eq!(rotation.get_angular_velocity(), physical_quantities::AngularVelocity::new(0.5));
+
+		// α = τ / I = 1, so ω = 0.5 + 2 and θ = 2 + 0.5 * 2 + 1/2 * 1 * 2^2.
+		particle.turn(
+			&[physical_quantities::Torque::new(3.0), physical_quantities::Torque::new(-1.0)],
+			physical_quantities::Time::new(2.0),
+		);
+		let rotation = particle.get_rotation().unwrap();
+		assert_eq!(rotation.get_angular_velocity(), physical_quantities::AngularVelocity::new(2.5));
+		assert_eq!(rotation.get_orientation(), physical_quantities::Angle::new(5.0));
+		assert_eq!(rotation.get_angular_momentum(), physical_quantities::AngularMomentum::new(5.0));
+		assert_eq!(rotation.get_kinetic_energy(), physical_quantities::Energy::new(6.25));
+	}
 }
 
 /// Defines a field. A field is a struct implementing a method that is called by
 /// the physics engine on each tick in which a particle is within a radius
 /// specified by the field, centered on a particle to which the field is
 /// attached.
-pub trait Field {
+///
+/// The type parameter is the vector type of the simulation the field is used
+/// in. It defaults to `Vector2`, so `impl Field for MyField` defines a
+/// two-dimensional field. Fields that work in any number of dimensions can
+/// implement `Field<V>` for every `V: Vector` instead.
+pub trait Field<V: physical_quantities::Vector = physical_quantities::Vector2> {
 	/// Determines what happens when the field is triggered.
 	/// # Arguments
 	/// * `simulation` - The Simulation that called the effect function.
@@ -83,8 +341,8 @@ pub trait Field {
 	///		attached.
 	fn effect(
 		&self,
-		simulation: &simulation::Simulation,
-		position: physical_quantities::Displacement,
+		simulation: &simulation::SimulationN<V>,
+		position: physical_quantities::DisplacementN<V>,
 		triggered_by: HashMap<Uuid, Vec<Option<FieldInfo>>>,
 		field_owner_id: Uuid,
 	);
@@ -118,6 +376,58 @@ pub trait Field {
 	/// particles with a water field and uses different rules to apply an
 	/// adhesion force to particles without the water field.
 	fn get_name(&self) -> &String;
+
+	/// Called by the simulation to get the coefficient of restitution of this
+	/// field, which it will then make available to user-defined code through
+	/// `simulation.get_field_info()`. Fields that don't take part in
+	/// collisions can rely on the default implementation, which returns `None`.
+	fn get_coefficient_of_restitution(&self) -> Option<f64> {
+		None
+	}
+
+	/// Called by the simulation to get the layers this field is on. The
+	/// simulation only passes a particle to this field's `effect` if the
+	/// particle (or, for fields that trigger on fields, one of the
+	/// particle's overlapping fields) is on a layer that the simulation's
+	/// layer interaction matrix allows to trigger one of these layers. The
+	/// default implementation returns `Layers::DEFAULT`.
+	fn get_layers(&self) -> Layers {
+		Layers::DEFAULT
+	}
+
+	/// Called by `Simulation::get_potential_energy` to get the potential
+	/// energy of the particles this field acts on due to the field. Fields
+	/// that don't have a potential energy can rely on the default
+	/// implementation, which returns `None`.
+	///
+	/// # Arguments
+	/// * `simulation` - The Simulation asking for the energy.
+	/// * `position` - The position of the particle to which this field is
+	///		attached.
+	/// * `field_owner_id` - The ID of the particle to which this field is
+	///		attached.
+	///
+	/// If two particles' fields act on each other (e.g., both particles have a
+	/// gravity field), each field should only report half of the pair's
+	/// energy, so the simulation doesn't count it twice.
+	fn potential_energy(
+		&self,
+		_simulation: &simulation::SimulationN<V>,
+		_position: physical_quantities::DisplacementN<V>,
+		_field_owner_id: Uuid,
+	) -> Option<physical_quantities::Energy<V::Scalar>> {
+		None
+	}
+
+	/// Called when the simulation is saved (see the `persistence` module) to
+	/// get a serializable copy of this field. The type tag of the returned
+	/// `SavedObject` must be registered with the `persistence::Registry` used
+	/// to load the simulation. The default implementation returns `None`,
+	/// which makes saving a simulation containing this field fail.
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		None
+	}
 }
 
 #[cfg(test)]
@@ -171,19 +481,20 @@ impl Field for DummyField {
 /// Applies "gravity" to the particle to which the field is attached.
 /// Implemented as a force that pulls the object to which it's attached in the
 /// direction of the specified acceleration.
-pub struct SimpleSelfGravityField {
-	acceleration: physical_quantities::Acceleration,
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimpleSelfGravityField<V = physical_quantities::Vector2> {
+	acceleration: physical_quantities::AccelerationN<V>,
 	name: String,
 }
 
-impl SimpleSelfGravityField {
+impl<V: physical_quantities::Vector> SimpleSelfGravityField<V> {
 	/// Creates an instance of `SimpleSelfGravityField `.
 	///
 	/// # Arguments
 	/// * `acceleration` - The acceleration due to gravity, "little 'g'."
 	/// * `name` - The field name. Defaults to "SimpleSelfGravityField" if `None`.
-	pub fn new(acceleration: physical_quantities::Acceleration, name: Option<String>)
-		-> SimpleSelfGravityField
+	pub fn new(acceleration: physical_quantities::AccelerationN<V>, name: Option<String>)
+		-> SimpleSelfGravityField<V>
 	{
 		let field_name = match name {
 			Some(s) => s,
@@ -197,11 +508,11 @@ impl SimpleSelfGravityField {
 	}
 }
 
-impl Field for SimpleSelfGravityField {
+impl<V: physical_quantities::Vector> Field<V> for SimpleSelfGravityField<V> {
 	fn effect(
 		&self,
-		simulation: &simulation::Simulation,
-		_position: physical_quantities::Displacement,
+		simulation: &simulation::SimulationN<V>,
+		_position: physical_quantities::DisplacementN<V>,
 		triggered_by: HashMap<Uuid, Vec<Option<FieldInfo>>>,
 		_field_owner_id: Uuid,
 	) {
@@ -236,6 +547,491 @@ impl Field for SimpleSelfGravityField {
 	fn get_name(&self) -> &String {
 		&self.name
 	}
+
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		Some(persistence::SavedObject::new("SimpleSelfGravityField", self))
+	}
+}
+
+/// A field that does nothing. Attach one to a particle to give the particle a
+/// name that other fields or pair interactions (see
+/// `Simulation::create_pair_interaction`) can look for.
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct MarkerField {
+	name: String,
+}
+
+impl MarkerField {
+	/// Creates an instance of `MarkerField`.
+	///
+	/// # Arguments
+	/// * `name` - The field name.
+	pub fn new(name: String) -> MarkerField {
+		MarkerField {
+			name: name,
+		}
+	}
+}
+
+impl<V: physical_quantities::Vector> Field<V> for MarkerField {
+	fn effect(
+		&self,
+		_simulation: &simulation::SimulationN<V>,
+		_position: physical_quantities::DisplacementN<V>,
+		_triggered_by: HashMap<Uuid, Vec<Option<FieldInfo>>>,
+		_field_owner_id: Uuid,
+	) {
+	}
+
+	fn get_radius(&self) -> f64 {
+		0.0
+	}
+
+	fn affects_self(&self) -> bool {
+		false
+	}
+
+	fn affects_others(&self) -> bool {
+		false
+	}
+
+	fn triggers_on_fields(&self) -> bool {
+		false
+	}
+
+	fn triggers_on_particles(&self) -> bool {
+		false
+	}
+
+	fn get_name(&self) -> &String {
+		&self.name
+	}
+
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		Some(persistence::SavedObject::new("MarkerField", self))
+	}
+}
+
+/// Applies a constant force to every particle it affects. Attached to a
+/// particle, it pushes that particle. Used as a world field (see
+/// `Simulation::create_world_field`), it can model wind or other force zones.
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct UniformForceField<V = physical_quantities::Vector2> {
+	force: physical_quantities::ForceN<V>,
+	name: String,
+}
+
+impl<V: physical_quantities::Vector> UniformForceField<V> {
+	/// Creates an instance of `UniformForceField`.
+	///
+	/// # Arguments
+	/// * `force` - The force applied to each affected particle on every tick.
+	/// * `name` - The field name. Defaults to "UniformForceField" if `None`.
+	pub fn new(force: physical_quantities::ForceN<V>, name: Option<String>)
+		-> UniformForceField<V>
+	{
+		let field_name = match name {
+			Some(s) => s,
+			None => String::from("UniformForceField"),
+		};
+
+		UniformForceField {
+			force: force,
+			name: field_name,
+		}
+	}
+}
+
+impl<V: physical_quantities::Vector> Field<V> for UniformForceField<V> {
+	fn effect(
+		&self,
+		simulation: &simulation::SimulationN<V>,
+		_position: physical_quantities::DisplacementN<V>,
+		triggered_by: HashMap<Uuid, Vec<Option<FieldInfo>>>,
+		_field_owner_id: Uuid,
+	) {
+		for id in triggered_by.keys() {
+			simulation.apply_force(*id, self.force);
+		}
+	}
+
+	fn get_radius(&self) -> f64 {
+		0.0
+	}
+
+	fn affects_self(&self) -> bool {
+		true
+	}
+
+	fn affects_others(&self) -> bool {
+		false
+	}
+
+	fn triggers_on_fields(&self) -> bool {
+		false
+	}
+
+	fn triggers_on_particles(&self) -> bool {
+		false
+	}
+
+	fn get_name(&self) -> &String {
+		&self.name
+	}
+
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		Some(persistence::SavedObject::new("UniformForceField", self))
+	}
+}
+
+/// Applies linear drag (a force proportional to, and opposing, velocity) to
+/// every particle it affects. Attached to a particle, it slows that particle.
+/// Used as a world field (see `Simulation::create_world_field`), it can model
+/// global drag or a region of thick fluid.
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct LinearDragField {
+	drag_coefficient: f64,
+	name: String,
+}
+
+impl LinearDragField {
+	/// Creates an instance of `LinearDragField`.
+	///
+	/// # Arguments
+	/// * `drag_coefficient` - The drag force applied to a particle is
+	///		`-drag_coefficient * velocity`. Units can be thought of as kg/s.
+	/// * `name` - The field name. Defaults to "LinearDragField" if `None`.
+	pub fn new(drag_coefficient: f64, name: Option<String>) -> LinearDragField {
+		let field_name = match name {
+			Some(s) => s,
+			None => String::from("LinearDragField"),
+		};
+
+		LinearDragField {
+			drag_coefficient: drag_coefficient,
+			name: field_name,
+		}
+	}
+}
+
+impl<V: physical_quantities::Vector> Field<V> for LinearDragField {
+	fn effect(
+		&self,
+		simulation: &simulation::SimulationN<V>,
+		_position: physical_quantities::DisplacementN<V>,
+		triggered_by: HashMap<Uuid, Vec<Option<FieldInfo>>>,
+		_field_owner_id: Uuid,
+	) {
+		for id in triggered_by.keys() {
+			let velocity = simulation.get_velocity(*id);
+			let force = physical_quantities::ForceN::from_vector(
+				velocity.get_vector() * V::Scalar::from_f64(-self.drag_coefficient)
+			);
+			simulation.apply_force(*id, force);
+		}
+	}
+
+	fn get_radius(&self) -> f64 {
+		0.0
+	}
+
+	fn affects_self(&self) -> bool {
+		true
+	}
+
+	fn affects_others(&self) -> bool {
+		false
+	}
+
+	fn triggers_on_fields(&self) -> bool {
+		false
+	}
+
+	fn triggers_on_particles(&self) -> bool {
+		false
+	}
+
+	fn get_name(&self) -> &String {
+		&self.name
+	}
+
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		Some(persistence::SavedObject::new("LinearDragField", self))
+	}
+}
+
+/// Defines an interaction between two particles (e.g., gravity between two
+/// masses) that is evaluated once for each unordered pair of particles and
+/// applies equal and opposite forces to both, in accordance with Newton's third
+/// law. Register an implementation with `Simulation::create_pair_interaction`.
+///
+/// Unlike a `Field`, which only affects other particles when it's their turn to
+/// be processed, a pair interaction can't apply a force to one particle of a
+/// pair without applying the opposite force to the other.
+///
+/// Like `Field`, the type parameter is the vector type of the simulation and
+/// defaults to `Vector2`.
+pub trait PairInteraction<V: physical_quantities::Vector = physical_quantities::Vector2> {
+	/// Calculates the force that particle b exerts on particle a. The
+	/// simulation applies this force to particle a and the opposite force to
+	/// particle b. Return `None` to apply no force.
+	///
+	/// # Arguments
+	/// * `simulation` - The Simulation that is evaluating the interaction.
+	/// * `particle_id_a` - The ID of the first particle. This particle has a
+	///		field with the first name passed to `create_pair_interaction`.
+	/// * `position_a` - The position of the first particle.
+	/// * `particle_id_b` - The ID of the second particle. This particle has a
+	///		field with the second name passed to `create_pair_interaction`.
+	/// * `position_b` - The position of the second particle.
+	fn force(
+		&self,
+		simulation: &simulation::SimulationN<V>,
+		particle_id_a: Uuid,
+		position_a: physical_quantities::DisplacementN<V>,
+		particle_id_b: Uuid,
+		position_b: physical_quantities::DisplacementN<V>,
+	) -> Option<physical_quantities::ForceN<V>>;
+
+	/// Called by the simulation to get the distance beyond which particles
+	/// don't interact. Only pairs of particles closer together than this
+	/// distance are passed to `force`.
+	fn get_cutoff(&self) -> f64;
+
+	/// Called by `Simulation::get_potential_energy` to get the potential
+	/// energy of a pair of particles due to this interaction. Only pairs
+	/// that would be passed to `force` are passed here. The default
+	/// implementation returns `None`, meaning the interaction has no potential
+	/// energy.
+	fn potential_energy(
+		&self,
+		_simulation: &simulation::SimulationN<V>,
+		_particle_id_a: Uuid,
+		_position_a: physical_quantities::DisplacementN<V>,
+		_particle_id_b: Uuid,
+		_position_b: physical_quantities::DisplacementN<V>,
+	) -> Option<physical_quantities::Energy<V::Scalar>> {
+		None
+	}
+
+	/// Called when the simulation is saved to get a serializable copy of this
+	/// interaction. Works the same way as `Field::save`.
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		None
+	}
+}
+
+/// A `PairInteraction`, along with the field names that determine which
+/// particles it applies to.
+#[derive(Clone)]
+pub(crate) struct PairInteractionEntry<V: physical_quantities::Vector = physical_quantities::Vector2> {
+	id: Uuid,
+	field_name_a: String,
+	field_name_b: String,
+	// Shared with any snapshots kept for rewinding the simulation.
+	interaction: Rc<dyn PairInteraction<V>>,
+}
+
+impl<V: physical_quantities::Vector> PairInteractionEntry<V> {
+	// The simulation supplies the ID so that it can hand out the same IDs
+	//	when re-simulating after a rewind or recreate saved interactions.
+	pub(crate) fn from_parts(
+		id: Uuid,
+		field_name_a: String,
+		field_name_b: String,
+		interaction: Box<dyn PairInteraction<V>>,
+	) -> Self {
+		Self {
+			id: id,
+			field_name_a: field_name_a,
+			field_name_b: field_name_b,
+			interaction: Rc::from(interaction),
+		}
+	}
+
+	pub(crate) fn get_id(&self) -> Uuid {
+		self.id
+	}
+
+	pub(crate) fn get_field_name_a(&self) -> &String {
+		&self.field_name_a
+	}
+
+	pub(crate) fn get_field_name_b(&self) -> &String {
+		&self.field_name_b
+	}
+
+	pub(crate) fn get_interaction(&self) -> &dyn PairInteraction<V> {
+		self.interaction.as_ref()
+	}
+}
+
+/// Newtonian gravity between pairs of particles. Each particle in a pair is
+/// pulled toward the other with a force of magnitude
+/// `G * m_1 * m_2 * r / (r^2 + ε^2)^(3/2)`, where ε is the softening length.
+/// With no softening, this is the familiar `G * m_1 * m_2 / r^2`.
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct UniversalGravitation {
+	#[cfg_attr(feature = "persistence", serde(with = "crate::persistence::extended_f64"))]
+	cutoff: f64,
+	// If None, use the real value in the simulation's unit system.
+	gravitational_constant: Option<f64>,
+	softening_length: f64,
+}
+
+impl UniversalGravitation {
+	/// Creates an instance of `UniversalGravitation`.
+	///
+	/// # Arguments
+	/// * `cutoff` - Particles this far apart, or farther, don't attract each
+	///		other. Use `f64::INFINITY` for no cutoff.
+	/// * `gravitational_constant` - The gravitational constant, G. If this is
+	///		`None`, the real world value in the simulation's unit system will be
+	///		used (6.6743e−11 in SI units). See `Simulation::set_unit_system`.
+	/// * `softening_length` - Plummer softening length, ε. Keeps the force
+	///		bounded when particles get very close to each other, which would
+	///		otherwise fling them apart at unrealistic speeds. The force peaks
+	///		at a separation of about 0.7 ε and falls to zero as the particles
+	///		meet. Defaults to 0.0 (no softening) if `None`.
+	///
+	/// # Panics
+	/// Panics if `softening_length` is negative.
+	pub fn new(
+		cutoff: f64,
+		gravitational_constant: Option<f64>,
+		softening_length: Option<f64>,
+	) -> UniversalGravitation {
+		let epsilon = softening_length.unwrap_or(0.0);
+
+		if epsilon < 0.0 {
+			panic!("softening_length must not be negative");
+		}
+
+		UniversalGravitation {
+			cutoff: cutoff,
+			gravitational_constant: gravitational_constant,
+			softening_length: epsilon,
+		}
+	}
+
+	/// Gets the gravitational constant, or `None` if the real value in the
+	/// simulation's unit system is used.
+	pub fn get_gravitational_constant(&self) -> Option<f64> {
+		self.gravitational_constant
+	}
+
+	// Gets G in the units `simulation` uses.
+	fn get_big_g<V: physical_quantities::Vector>(
+		&self,
+		simulation: &simulation::SimulationN<V>,
+	) -> physical_quantities::GravitationalConstant<V::Scalar> {
+		let big_g = self.gravitational_constant
+			.unwrap_or_else(|| simulation.get_unit_system().get_gravitational_constant());
+		physical_quantities::GravitationalConstant::new(V::Scalar::from_f64(big_g))
+	}
+
+	pub fn get_softening_length(&self) -> f64 {
+		self.softening_length
+	}
+
+	pub fn get_cutoff(&self) -> f64 {
+		self.cutoff
+	}
+}
+
+impl<V: physical_quantities::Vector> PairInteraction<V> for UniversalGravitation {
+	fn force(
+		&self,
+		simulation: &simulation::SimulationN<V>,
+		particle_id_a: Uuid,
+		position_a: physical_quantities::DisplacementN<V>,
+		particle_id_b: Uuid,
+		position_b: physical_quantities::DisplacementN<V>,
+	) -> Option<physical_quantities::ForceN<V>> {
+		// We want to calculate
+		// F = G * ((m_a * m_b) / (|r_ab|^2 + ε^2)^(3/2)) * r_ab
+		// Where r_ab is the vector from particle a to particle b and ε is the
+		//	softening length. When ε = 0, this is
+		// F = G * ((m_a * m_b) / |r_ab|^2) * ru_ab
+		// Where ru_ab is the unit vector derived from r_ab.
+		// The units are checked by the compiler: G * kg * kg / m³ * m is a
+		//	force.
+		let displacement = utilities::get_displacement_vector(
+			position_a,
+			position_b,
+		);
+		// Magnitude = sqrt(r . r) => Magnitude^2 = r . r
+		let magnitude_squared = displacement.get_norm_squared();
+
+		// Don't divide by 0. If we ever encounter this situation, it seems
+		//	extremely unlikely that it would last for more than one tick if
+		//	things are allowed to move (i.e., the user isn't intentionally
+		//	pinning two particles to the same location).
+		if magnitude_squared.get_number() > V::Scalar::zero() {
+			let big_g = self.get_big_g(simulation);
+			let softening_length = physical_quantities::Length::new(
+				V::Scalar::from_f64(self.softening_length)
+			);
+			let softened_magnitude_squared =
+				magnitude_squared + softening_length * softening_length;
+			// x^(3/2) = x * sqrt(x)
+			let force: physical_quantities::ForceN<V> = displacement * (
+				big_g
+				* (
+				(simulation.get_mass(particle_id_a) * simulation.get_mass(particle_id_b))
+				/ (softened_magnitude_squared * softened_magnitude_squared.sqrt())));
+
+			Some(force)
+		} else {
+			None
+		}
+	}
+
+	fn get_cutoff(&self) -> f64 {
+		self.cutoff
+	}
+
+	// U = -G * m_a * m_b / (|r_ab|^2 + ε^2)^(1/2), the potential that the
+	//	force above is derived from.
+	fn potential_energy(
+		&self,
+		simulation: &simulation::SimulationN<V>,
+		particle_id_a: Uuid,
+		position_a: physical_quantities::DisplacementN<V>,
+		particle_id_b: Uuid,
+		position_b: physical_quantities::DisplacementN<V>,
+	) -> Option<physical_quantities::Energy<V::Scalar>> {
+		let distance = utilities::get_displacement_vector(position_a, position_b).get_norm();
+		let softening_length = physical_quantities::Length::new(
+			V::Scalar::from_f64(self.softening_length)
+		);
+		let softened_distance =
+			(distance * distance + softening_length * softening_length).sqrt();
+
+		// Don't divide by 0. The force is also None in this case.
+		if softened_distance.get_number() > V::Scalar::zero() {
+			let big_g = self.get_big_g(simulation);
+
+			Some(
+				-big_g
+				* simulation.get_mass(particle_id_a)
+				* simulation.get_mass(particle_id_b)
+				/ softened_distance
+			)
+		} else {
+			None
+		}
+	}
+
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		Some(persistence::SavedObject::new("UniversalGravitation", self))
+	}
 }
 
 /// Makes a particle apply a gravitational pull to other particles within the
@@ -247,12 +1043,23 @@ impl Field for SimpleSelfGravityField {
 ///	particle, but not have that other particle within its own gravity field.
 /// This would lead to the law of "equal and opposite reactions" being ignored
 /// (i.e. one particle would experience a force while the other would not).
-// TODO: This Field's effect should probably only apply forces to particles
-//	containing a field of the same name as this field. Otherwise, all particles
-//	in a simulation with a UniversalGravitationField would be affected by it.
+/// Registering a `UniversalGravitation` pair interaction with
+/// `Simulation::create_pair_interaction` avoids this problem and only
+/// calculates the force between each pair of particles once.
+///
+/// By default, every particle within the field's radius is attracted,
+/// including particles that only exist as markers, floors, etc. Pass
+/// `attracts_only` to restrict the field to particles with a field of a given
+/// name (usually the name of this field, so only particles with gravity
+/// attract each other), or `attracts_layers` to restrict it to particles with
+/// a field on one of the given layers (e.g., a layer used to tag massive
+/// bodies).
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
 pub struct UniversalGravitationField {
-	radius: f64,
-	gravitational_constant: f64,
+	gravitation: UniversalGravitation,
+	attracts_only: Option<String>,
+	#[cfg_attr(feature = "persistence", serde(default))]
+	attracts_layers: Option<Layers>,
 	name: String,
 }
 
@@ -261,13 +1068,31 @@ impl UniversalGravitationField {
 	/// Creates an instance of `UniversalGravitationField`.
 	///
 	/// # Arguments
+	/// * `radius` - The radius of the field.
 	/// * `gravitational_constant` - The gravitational constant, G. If this is
-	///		`None`, the real world value of 6.6743e−11 will be used.
+	///		`None`, the real world value in the simulation's unit system will be
+	///		used. See `UniversalGravitation::new`.
+	/// * `softening_length` - Plummer softening length. See
+	///		`UniversalGravitation::new`. Defaults to 0.0 (no softening) if
+	///		`None`.
+	/// * `attracts_only` - If this is `Some`, only particles that have a field
+	///		with this name are attracted. If `None`, particles are attracted
+	///		whatever their fields are named.
+	/// * `attracts_layers` - If this is `Some`, only particles that have a
+	///		field on at least one of these layers are attracted. If `None`,
+	///		particles are attracted whatever layers their fields are on. If both
+	///		this and `attracts_only` are `Some`, a particle must satisfy both.
 	/// * `name` - The field name. Defaults to "UniversalGravitationField" if
 	///		`None`.
+	///
+	/// # Panics
+	/// Panics if `softening_length` is negative.
 	pub fn new(
 		radius: f64,
 		gravitational_constant: Option<f64>,
+		softening_length: Option<f64>,
+		attracts_only: Option<String>,
+		attracts_layers: Option<Layers>,
 		name: Option<String>)
 		-> UniversalGravitationField
 	{
@@ -276,62 +1101,125 @@ impl UniversalGravitationField {
 			None => String::from("UniversalGravitationField"),
 		};
 
-		let big_g = match gravitational_constant {
-			Some(g) => g,
-			None => 6.6743e-11,
-		};
-
 		UniversalGravitationField {
-			radius: radius,
-			gravitational_constant: big_g,
+			gravitation: UniversalGravitation::new(
+				radius,
+				gravitational_constant,
+				softening_length,
+			),
+			attracts_only: attracts_only,
+			attracts_layers: attracts_layers,
 			name: field_name,
 		}
 	}
+
+	// Determines whether a particle with the given fields passes the
+	//	`attracts_only` and `attracts_layers` filters.
+	fn attracts(&self, field_info: &[FieldInfo]) -> bool {
+		if let Some(required_name) = &self.attracts_only
+			&& !field_info.iter().any(|info| info.get_name() == required_name)
+		{
+			return false;
+		}
+		if let Some(required_layers) = self.attracts_layers
+			&& !field_info.iter().any(|info| info.get_layers().intersects(required_layers))
+		{
+			return false;
+		}
+
+		true
+	}
 }
 
-impl Field for UniversalGravitationField {
+impl<V: physical_quantities::Vector> Field<V> for UniversalGravitationField {
 	fn effect(
 		&self,
-		simulation: &simulation::Simulation,
-		position: physical_quantities::Displacement,
+		simulation: &simulation::SimulationN<V>,
+		position: physical_quantities::DisplacementN<V>,
 		triggered_by: HashMap<Uuid, Vec<Option<FieldInfo>>>,
 		field_owner_id: Uuid,
 	) {
+		// Only the other particle in each pair is pulled toward this field's
+		//	owner. The owner is pulled by the other particle's field, if it has
+		//	one.
 		for id in triggered_by.keys() {
-			// We want to calculate
-			// F = G * ((m_1 * m_2) / |r_12|^2) * ru_12
-			// Where r_12 is the vector from the other particle to this field's
-			//	owner particle and ru_12 is the unit vector derived from r_12.
-			let other_position = simulation.get_position(*id);
-			let displacement_vector = utilities::get_displacement_vector(
-				other_position,
+			if !self.attracts(&simulation.get_field_info(*id)) {
+				continue;
+			}
+
+			let force = self.gravitation.force(
+				simulation,
+				*id,
+				simulation.get_position(*id),
+				field_owner_id,
 				position,
 			);
-			let unit_vector = displacement_vector.get_vector().get_unit_vector();
-			// Magnitude = sqrt(x^2 + y^2) => Magnitude^2 = x^2 + y^2
-			let magnitude_squared = displacement_vector.x().powf(2.0) + displacement_vector.y().powf(2.0);
-
-			// Don't divide by 0. If we ever encounter this situation, it seems
-			//	extremely unlikely that it would last for more than one tick if
-			//	things are allowed to move (i.e., the user isn't intentionally
-			//	pinning two particles to the same location).
-			if magnitude_squared > 0.0 {
-				let force_vector =
-					self.gravitational_constant
-					* (
-					(simulation.get_mass(field_owner_id).get_number() * simulation.get_mass(*id).get_number())
-					/ magnitude_squared) * unit_vector;
-
-				let force =
-					physical_quantities::Force::new(force_vector.x(), force_vector.y());
-
-				simulation.apply_force(*id, force);
+
+			if let Some(f) = force {
+				simulation.apply_force(*id, f);
 			}
 		}
 	}
 
 	fn get_radius(&self) -> f64 {
-		self.radius
+		self.gravitation.get_cutoff()
+	}
+
+	// Sums the potential energy of every particle this field pulls. Pairs in
+	//	which the other particle pulls this field's owner back, with a field of
+	//	the same name, are counted by both fields, so each field only reports
+	//	half of the pair's energy.
+	fn potential_energy(
+		&self,
+		simulation: &simulation::SimulationN<V>,
+		position: physical_quantities::DisplacementN<V>,
+		field_owner_id: Uuid,
+	) -> Option<physical_quantities::Energy<V::Scalar>> {
+		let mut total = physical_quantities::Energy::new(V::Scalar::zero());
+
+		for id in simulation.get_particle_ids() {
+			let other_position = simulation.get_position(id);
+			if id == field_owner_id
+				|| !utilities::is_within_radius(
+					other_position,
+					self.gravitation.get_cutoff(),
+					position,
+					true,
+				) {
+				continue;
+			}
+
+			let field_info = simulation.get_field_info(id);
+			if !self.attracts(&field_info) {
+				continue;
+			}
+
+			let energy = self.gravitation.potential_energy(
+				simulation,
+				id,
+				other_position,
+				field_owner_id,
+				position,
+			);
+			if let Some(e) = energy {
+				let is_mutual = field_info.iter().any(|info| {
+					info.get_name() == &self.name
+						&& utilities::is_within_radius(
+							position,
+							info.get_radius(),
+							other_position,
+							true,
+						)
+				});
+				total += if is_mutual {
+					e * V::Scalar::from_f64(0.5)
+				} else {
+					e
+				};
+			}
+		}
+
+		Some(total)
 	}
 
 	fn affects_self(&self) -> bool {
@@ -353,14 +1241,23 @@ impl Field for UniversalGravitationField {
 	fn get_name(&self) -> &String {
 		&self.name
 	}
+
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		Some(persistence::SavedObject::new("UniversalGravitationField", self))
+	}
 }
 
 /// Allows collisions between two or more particles to be simulated, instead of
-///		letting particles pass through each other. Each particle involved in a
-///		collision must have colliders with the same name in order to be affected.
+///		letting particles pass through each other. Colliders only collide with
+///		colliders on layers that the simulation's layer interaction matrix
+///		allows to trigger them (by default, all layers trigger each other).
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
 pub struct Collider {
+	#[cfg_attr(feature = "persistence", serde(with = "crate::persistence::extended_f64"))]
 	radius: f64,
 	coefficient_of_restitution: f64,
+	layers: Layers,
 	name: String,
 }
 
@@ -376,11 +1273,14 @@ impl Collider {
 	///		is 1 will be perfectly elastic. A collision where this product is
 	///		0 will be perfectly inelastic. Values outside of this range are
 	///		allowed and will result in unrealistic behavior.
-	/// * `name` - The field name. Defaults to "Collider" if `None`. Only
-	///		particles with colliders having the same `name` will collide.
+	/// * `layers` - The layers the collider is on. Defaults to
+	///		`Layers::DEFAULT` if `None`. Use `Simulation::set_layer_interaction`
+	///		to control which layers collide with each other.
+	/// * `name` - The field name. Defaults to "Collider" if `None`.
 	pub fn new(
 		radius: f64,
 		coefficient_of_restitution: f64,
+		layers: Option<Layers>,
 		name: Option<String>)
 		-> Collider
 	{
@@ -389,15 +1289,35 @@ impl Collider {
 			None => String::from("Collider"),
 		};
 
+		let collider_layers = match layers {
+			Some(l) => l,
+			None => Layers::DEFAULT,
+		};
+
 		Collider {
 			radius: radius,
 			coefficient_of_restitution: coefficient_of_restitution,
+			layers: collider_layers,
 			name: field_name,
 		}
 	}
+
+	// Collider implements Field for every dimension, so calling a Field method
+	//	on a Collider directly would be ambiguous without these.
+	pub fn get_radius(&self) -> f64 {
+		self.radius
+	}
+
+	pub fn get_layers(&self) -> Layers {
+		self.layers
+	}
+
+	pub fn get_name(&self) -> &String {
+		&self.name
+	}
 }
 
-impl Field for Collider {
+impl<V: physical_quantities::Vector> Field<V> for Collider {
 	// NOTE: It may be necessary to add some (user configurable) threshold
 	//	value that a velocity must be greater than to allow a collision to
 	//	occur. Otherwise, floating point errors will probably make
@@ -409,104 +1329,117 @@ impl Field for Collider {
 	//	like a hack and would, itself, be unrealistic behavior.
 	fn effect(
 		&self,
-		simulation: &simulation::Simulation,
-		position: physical_quantities::Displacement,
+		simulation: &simulation::SimulationN<V>,
+		position: physical_quantities::DisplacementN<V>,
 		triggered_by: HashMap<Uuid, Vec<Option<FieldInfo>>>,
 		field_owner_id: Uuid,
 	) {
+		let owner_mass = simulation.get_mass(field_owner_id);
+		let owner_velocity = simulation.get_velocity(field_owner_id);
+		let tick_duration = simulation.get_tick_duration();
 
-		// TODO: Implement collisions.
-
-		let mut other_collider;
-		let mut other_radius;
-		let mut owner_velocity;
-		let mut other_velocity;
 		for (other_id, field_infos) in triggered_by {
-			// Find the other collider, if one exists.
-			// If the other particle doesn't have any fields with the same name
-			//	as this one, skip all of the collision logic.
+			// Find the other collider, if one exists. The simulation only
+			//	passes us fields on layers that can collide with this one, so
+			//	any field with a coefficient of restitution is a collider we
+			//	should collide with. If the other particle doesn't have one,
+			//	skip all of the collision logic.
 			// If multiple colliders on the other particle triggered this
 			//	effect, ignore all but the collider with the largest radius.
-			other_collider = None;
-			for option in field_infos {
-				let mut current_radius = None;
-				let mut new_radius;
-				if let Some(info) = option {
-					// Treat negative and positive radii the same way.
-					new_radius = info.get_radius().abs();
-					if (
-						other_collider.is_none() || new_radius > current_radius
-						.expect("Should set current_radius with other_collider.")
-						)
-						&& info.get_name() == self.get_name()
-					{
-						other_collider = Some(info);
-						current_radius = Some(new_radius);
-					}
+			let mut other_collider: Option<FieldInfo> = None;
+			for info in field_infos.into_iter().flatten() {
+				if info.get_coefficient_of_restitution().is_none() {
+					continue;
+				}
+				// Treat negative and positive radii the same way.
+				let is_larger = match &other_collider {
+					Some(current) =>
+						info.get_radius().abs() > current.get_radius().abs(),
+					None => true,
+				};
+				if is_larger {
+					other_collider = Some(info);
 				}
 			}
-			if let Some(other) = other_collider {
-				other_radius = other.get_radius();
-			} else {
+			let other_collider = match other_collider {
+				Some(c) => c,
 				// Skip the current particle if we didn't find a collider.
+				None => continue,
+			};
+
+			// The collision normal is the line through the centers of the two
+			//	particles. If the particles are in exactly the same place, there
+			//	is no normal. They will be moving apart (or not moving at all)
+			//	on the next tick, so there is no collision to resolve.
+			let other_position = simulation.get_position(other_id);
+			let normal = utilities::get_displacement_vector(position, other_position);
+			let distance = normal.get_magnitude();
+			if distance == V::Scalar::zero() {
 				continue;
 			}
+			let unit_normal = normal.get_vector().get_unit_vector();
 
-			owner_velocity = simulation.get_velocity(field_owner_id);
-			other_velocity = simulation.get_velocity(other_id);
 			// To calculate relative velocity, we'll consider this particle
 			//	to be stationary and the other particle to be moving with
 			//	respect to this one. I.e., relative_velocity is the velocity
 			//	vector this particle would see when observing the other particle.
-			relative_velocity = other_velocity - owner_velocity;
-
-			// If, based on the relative velocity and the particles' positions,
-			//	the particles have already passed each other, skip the collision
-			//	logic. This means that, if the particles have already collided
-			//	or were moving fast enough for the colliders to pass halfway
-			//	through each other prior to a collision being detected, no
-			//	collision will occur. We will consider the latter case to just
-			//	be a resolution issue and on the user to handle.
-			// We need to find a line passing through the field owner at a right
-			//	angle to the relative velocity vector. If the other particle is
-			//	moving away from that line, we will say the particles have
-			//	already passed each other.
-
-
-			// We will need the particles' positions at the time of the
-			//	collision. Perform calculations as if the collision happened at
-			//	the surface of the colliders, not where the particles are
-			//	actually overlapping on the current tick. Use the relative
-			//	velocity of the particles and the radii of the colliders to
-			//	determine where they first came into contact. We need to
-			//	account for the fact that colliders will overlap for more than
-			//	one tick after colliding. 
-
-			// Calculate the change in velocity of the other particle (Δvx,2'
-			//	and Δvy,2') by applying equations (7), (8), (9), (11), (14),
-			//	(16), (22), (23), (26), and (27)
-			//	from https://www.plasmaphysics.org.uk/collision2d.htm.
-			//	(7)		γv = arctan [(vy,1 - vy,2) / (vx,1 - vx,2)] 
-			//	(8)		Δvx,2' = 2[vx,1 - vx,2 + a * (vy,1 - vy,2)] / [(1 + a2) * (1 + m2 / m1)]
-			//	(9)		a = tan(θ) = tan(γv + α)
-			//	(11)	vy,2' = vy,2 + a * Δvx,2'
-			//			-> I.e. Δvy,2' = a * Δvx,2'
-			//	(14)	α = arcsin [d * sin(γx,y - γv) / (r1 + r2)]
-			//	(16)	γx,y = arctan [(y2 - y1) / (x2 - x1)]
-			//	(22)	vx,cm = ( m1 * vx,1 + m2 * vx,2)/(m1 + m2)
-			//	(23)	vy,cm = ( m1 * vy,1 + m2 * vy,2)/(m1 + m2)
-			//	(26)	vx,2'' = (vx,2' - vx,cm) * R + vx,cm
-			//	(27)	vy,2'' = (vy,2' - vy,cm) * R + vy,cm
-			//	Where v'' is velocity after the collision, v is velocity prior
-			//	to the collision, and R is the coefficient of restitution.
-
-			// Calculate a force that will cause the calculated change in the
-			//	other particle's velocity in a single tick. Apply that force to
-			//	the other particle. Do nothing to this particle, assuming that
-			//	the field attached to the other particle will handle that.
+			let other_velocity = simulation.get_velocity(other_id);
+			let relative_velocity = other_velocity - owner_velocity;
+
+			// If the other particle is moving away from this one along the
+			//	normal, the particles have already collided (or were moving fast
+			//	enough to pass halfway through each other before a collision was
+			//	detected). Either way, there's nothing to do. This also keeps
+			//	colliders that are still overlapping for a few ticks after a
+			//	collision from colliding again.
+			let closing_speed =
+				physical_quantities::Speed::new(relative_velocity.get_vector().dot(unit_normal));
+			if closing_speed.get_number() >= V::Scalar::zero() {
+				continue;
+			}
+
+			// Treat the collision as an instantaneous impulse along the normal.
+			//	For a coefficient of restitution, R, the magnitude of the
+			//	impulse is
+			//	J = -(1 + R) * (v_rel . n) / (1 / m_1 + 1 / m_2)
+			//	Where v_rel is the relative velocity, n is the unit normal, and
+			//	m_1 and m_2 are the masses of the particles.
+			let other_mass = simulation.get_mass(other_id);
+			let restitution = V::Scalar::from_f64(
+				self.coefficient_of_restitution
+				* other_collider.get_coefficient_of_restitution().unwrap_or(1.0)
+			);
+			let one = V::Scalar::from_f64(1.0);
+			let impulse = closing_speed * -(one + restitution)
+				/ (owner_mass.recip() + other_mass.recip());
+
+			// Calculate a force that will cause the impulse to be delivered
+			//	over a single tick (J = F * t). Apply that force to the other
+			//	particle. Do nothing to this particle. The collider attached to
+			//	the other particle calculates the same impulse in the opposite
+			//	direction and will apply it to this particle.
+			let force: physical_quantities::ForceN<V> =
+				(impulse / tick_duration).along(unit_normal);
+			simulation.apply_force(other_id, force);
+
+			// Both colliders resolve the collision, but only report it once.
+			if field_owner_id < other_id {
+				simulation.queue_event(events::EventKind::CollisionResolved {
+					particle_id_a: field_owner_id,
+					particle_id_b: other_id,
+				});
+			}
 		}
 	}
 
+	fn get_coefficient_of_restitution(&self) -> Option<f64> {
+		Some(self.coefficient_of_restitution)
+	}
+
+	fn get_layers(&self) -> Layers {
+		self.layers
+	}
+
 	fn get_radius(&self) -> f64 {
 		self.radius
 	}
@@ -530,6 +1463,81 @@ impl Field for Collider {
 	fn get_name(&self) -> &String {
 		&self.name
 	}
+
+	#[cfg(feature = "persistence")]
+	fn save(&self) -> Option<persistence::SavedObject> {
+		Some(persistence::SavedObject::new("Collider", self))
+	}
+}
+
+/// A set of collision layers, stored as a bitset. Every field is on one or
+/// more layers (see `Field::get_layers`), and a particle is on all of the
+/// layers its fields are on. The simulation's layer interaction matrix (see
+/// `Simulation::set_layer_interaction`) decides which layers trigger fields on
+/// which other layers, so pairs that can't interact are skipped before any
+/// field's `effect` is called.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layers(u32);
+
+impl Layers {
+	/// The number of available layers. Valid layer indices are
+	/// `0..Layers::COUNT`.
+	pub const COUNT: u32 = 32;
+	/// No layers.
+	pub const NONE: Layers = Layers(0);
+	/// Every layer.
+	pub const ALL: Layers = Layers(u32::MAX);
+	/// Layer 0. Fields are on this layer unless they say otherwise.
+	pub const DEFAULT: Layers = Layers(1);
+
+	/// Creates a set of layers from a bitset in which bit `n` represents
+	/// layer `n`.
+	pub fn new(bits: u32) -> Layers {
+		Layers(bits)
+	}
+
+	/// Creates a set containing a single layer.
+	///
+	/// # Panics
+	/// Panics if `index` is not less than `Layers::COUNT`.
+	pub fn layer(index: u32) -> Layers {
+		if index >= Layers::COUNT {
+			panic!("Layer index must be less than {}: {}", Layers::COUNT, index);
+		}
+
+		Layers(1 << index)
+	}
+
+	pub fn get_bits(&self) -> u32 {
+		self.0
+	}
+
+	/// Determines whether the layer at `index` is in this set.
+	pub fn contains(&self, index: u32) -> bool {
+		index < Layers::COUNT && self.0 & (1 << index) != 0
+	}
+
+	/// Determines whether this set and `other` have at least one layer in
+	/// common.
+	pub fn intersects(&self, other: Layers) -> bool {
+		self.0 & other.0 != 0
+	}
+
+	/// Gets a set containing every layer in either this set or `other`.
+	pub fn union(&self, other: Layers) -> Layers {
+		Layers(self.0 | other.0)
+	}
+}
+
+impl std::ops::BitOr for Layers {
+	type Output = Self;
+
+	fn bitor(self, other: Self) -> Self {
+		self.union(other)
+	}
 }
 
 #[derive(Debug)]
@@ -540,6 +1548,8 @@ pub struct FieldInfo {
 	triggers_on_fields: bool,
 	triggers_on_particles: bool,
 	name: String,
+	coefficient_of_restitution: Option<f64>,
+	layers: Layers,
 }
 
 impl FieldInfo {
@@ -550,6 +1560,7 @@ impl FieldInfo {
 		triggers_on_fields: bool,
 		triggers_on_particles: bool,
 		name: String,
+		layers: Layers,
 	) -> Self {
 		Self {
 			radius: radius,
@@ -558,9 +1569,21 @@ impl FieldInfo {
 			triggers_on_fields: triggers_on_fields,
 			triggers_on_particles: triggers_on_particles,
 			name: name,
+			coefficient_of_restitution: None,
+			layers: layers,
 		}
 	}
 
+	// Sets the coefficient of restitution of the field the info describes,
+	//	if it's a collider.
+	pub(crate) fn with_coefficient_of_restitution(
+		mut self,
+		coefficient_of_restitution: Option<f64>,
+	) -> Self {
+		self.coefficient_of_restitution = coefficient_of_restitution;
+		self
+	}
+
 	pub fn get_radius(&self) -> f64 {
 		self.radius
 	}
@@ -584,6 +1607,181 @@ impl FieldInfo {
 	pub fn get_name(&self) -> &String {
 		&self.name
 	}
+
+	pub fn get_coefficient_of_restitution(&self) -> Option<f64> {
+		self.coefficient_of_restitution
+	}
+
+	pub fn get_layers(&self) -> Layers {
+		self.layers
+	}
+}
+
+/// The part of the world in which a world field (see
+/// `Simulation::create_world_field`) affects particles. In three dimensions, a
+/// `Circle` is a sphere, a `Rectangle` is a box and a `Polygon` is a prism
+/// extending infinitely along the z axis.
+#[derive(Debug)]
+#[derive(Clone)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub enum Region<V = physical_quantities::Vector2> {
+	/// Contains every point. Useful for things like uniform gravity or global
+	/// drag.
+	Everywhere,
+	/// Contains every point within `radius` of `center`.
+	Circle {
+		center: physical_quantities::DisplacementN<V>,
+		radius: f64,
+	},
+	/// An axis-aligned rectangle with opposite corners at `min` and `max`.
+	Rectangle {
+		min: physical_quantities::DisplacementN<V>,
+		max: physical_quantities::DisplacementN<V>,
+	},
+	/// A polygon with the given vertices, in order. The last vertex is
+	/// connected to the first. Only the x and y coordinates of the vertices
+	/// are used.
+	Polygon {
+		vertices: Vec<physical_quantities::DisplacementN<V>>,
+	},
+}
+
+impl<V: physical_quantities::Vector> Region<V> {
+	/// Determines whether a point is inside this region. Points exactly on
+	/// the boundary of a region are considered to be outside of it.
+	pub fn contains(&self, point: physical_quantities::DisplacementN<V>) -> bool {
+		match self {
+			Region::Everywhere => true,
+			Region::Circle { center, radius } =>
+				utilities::is_within_radius(point, *radius, *center, true),
+			Region::Rectangle { min, max } => {
+				// Allow the corners to be given in any order.
+				let (min, max, point) = (min.get_vector(), max.get_vector(), point.get_vector());
+				(0..V::DIMENSIONS).all(|i| {
+					let (a, b) = (min.get_component(i), max.get_component(i));
+					let (low, high) = if a < b { (a, b) } else { (b, a) };
+					point.get_component(i) > low && point.get_component(i) < high
+				})
+			},
+			Region::Polygon { vertices } =>
+				utilities::is_within_polygon(point, vertices),
+		}
+	}
+
+	/// Gets the point passed to a world field's `effect` method as the
+	/// field's position. This is the origin for `Everywhere`, the center of a
+	/// circle or rectangle, or the average of a polygon's vertices.
+	pub fn get_center(&self) -> physical_quantities::DisplacementN<V> {
+		let origin = physical_quantities::DisplacementN::from_vector(V::zero());
+		match self {
+			Region::Everywhere => origin,
+			Region::Circle { center, .. } => *center,
+			Region::Rectangle { min, max } => physical_quantities::DisplacementN::from_vector(
+				(min.get_vector() + max.get_vector()) / V::Scalar::from_f64(2.0)
+			),
+			Region::Polygon { vertices } => {
+				if vertices.is_empty() {
+					return origin;
+				}
+				let count = V::Scalar::from_f64(vertices.len() as f64);
+				let total = vertices.iter().fold(origin, |total, v| total + *v);
+				physical_quantities::DisplacementN::from_vector(total.get_vector() / count)
+			},
+		}
+	}
+}
+
+/// A field owned by the simulation itself instead of a particle. It affects
+/// every particle inside its region.
+#[derive(Clone)]
+pub(crate) struct WorldField<V: physical_quantities::Vector = physical_quantities::Vector2> {
+	id: Uuid,
+	region: Region<V>,
+	// Shared with any snapshots kept for rewinding the simulation.
+	field: Rc<dyn Field<V>>,
+}
+
+impl<V: physical_quantities::Vector> WorldField<V> {
+	// The simulation supplies the ID so that it can hand out the same IDs
+	//	when re-simulating after a rewind or recreate saved world fields.
+	pub(crate) fn from_parts(id: Uuid, region: Region<V>, field: Box<dyn Field<V>>) -> Self {
+		Self {
+			id: id,
+			region: region,
+			field: Rc::from(field),
+		}
+	}
+
+	pub(crate) fn get_id(&self) -> Uuid {
+		self.id
+	}
+
+	pub(crate) fn get_region(&self) -> &Region<V> {
+		&self.region
+	}
+
+	pub(crate) fn get_field(&self) -> &dyn Field<V> {
+		self.field.as_ref()
+	}
+}
+
+/// The rotational state of a particle that can spin: its orientation, angular
+/// velocity and moment of inertia. Particles are points, so this is the
+/// rotation of whatever the particle represents about its own center. In three
+/// dimensions, only rotation about the z axis is modeled.
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rotation<S = f64> {
+	orientation: physical_quantities::Angle<S>,
+	angular_velocity: physical_quantities::AngularVelocity<S>,
+	moment_of_inertia: physical_quantities::MomentOfInertia<S>,
+}
+
+impl<S: physical_quantities::Scalar> Rotation<S> {
+	/// Creates an instance of `Rotation`.
+	///
+	/// # Arguments
+	/// * `orientation` - The angle the particle is turned counterclockwise
+	///		from its starting orientation. Not wrapped, so it also counts full
+	///		turns.
+	/// * `angular_velocity` - How fast the particle is spinning.
+	/// * `moment_of_inertia` - How hard the particle is to spin up or slow
+	///		down (e.g., 1/2 m r^2 for a solid disc of radius r).
+	pub fn new(
+		orientation: physical_quantities::Angle<S>,
+		angular_velocity: physical_quantities::AngularVelocity<S>,
+		moment_of_inertia: physical_quantities::MomentOfInertia<S>,
+	) -> Self {
+		Self {
+			orientation: orientation,
+			angular_velocity: angular_velocity,
+			moment_of_inertia: moment_of_inertia,
+		}
+	}
+
+	pub fn get_orientation(&self) -> physical_quantities::Angle<S> {
+		self.orientation
+	}
+
+	pub fn get_angular_velocity(&self) -> physical_quantities::AngularVelocity<S> {
+		self.angular_velocity
+	}
+
+	pub fn get_moment_of_inertia(&self) -> physical_quantities::MomentOfInertia<S> {
+		self.moment_of_inertia
+	}
+
+	/// Gets the angular momentum of the spin, I ω.
+	pub fn get_angular_momentum(&self) -> physical_quantities::AngularMomentum<S> {
+		self.moment_of_inertia * self.angular_velocity
+	}
+
+	/// Gets the kinetic energy of the spin, 1/2 I ω^2.
+	pub fn get_kinetic_energy(&self) -> physical_quantities::Energy<S> {
+		self.moment_of_inertia * S::from_f64(0.5) * self.angular_velocity * self.angular_velocity
+	}
 }
 
 // TODO: Should this (and probably other structs) actually be public? The
@@ -593,45 +1791,100 @@ impl FieldInfo {
 //	Maybe there's a way to make it accessible from modules within the library,
 //	but inaccessible to a user of the library, if desirable.
 /// Represents an infinitesimal massive particle. Stores the particle's mass,
-/// position, velocity, and attached `Field`s.
-pub struct Particle {
-	mass: physical_quantities::Mass,
-	position: physical_quantities::Displacement,
-	velocity: physical_quantities::Velocity,
-	// Vec<Box<dyn Field>> is a "trait object". This is apparently necessary to
-	//	make a Vec store an unknown type that implements a trait.
-	fields: Vec<Box<dyn Field>>,
+/// position, velocity, optional `Rotation`, and attached `Field`s. Cloning a
+/// particle shares its fields rather than copying them. The vector type sets
+/// the particle's dimensions and number type (e.g., `Particle<Vector2<f32>>`);
+/// it defaults to a two-dimensional `f64` vector.
+#[derive(Clone)]
+pub struct Particle<V: physical_quantities::Vector = physical_quantities::Vector2> {
+	mass: physical_quantities::Mass<V::Scalar>,
+	position: physical_quantities::DisplacementN<V>,
+	// Where the particle was before the most recent tick moved it. Lets
+	//	renderers interpolate between ticks.
+	previous_position: physical_quantities::DisplacementN<V>,
+	velocity: physical_quantities::VelocityN<V>,
+	// Only particles with a moment of inertia can spin. Others ignore torques.
+	rotation: Option<Rotation<V::Scalar>>,
+	// Vec<Rc<dyn Field>> is a "trait object". This is apparently necessary to
+	//	make a Vec store an unknown type that implements a trait. The fields
+	//	are reference counted so snapshots kept for rewinding the simulation
+	//	can share them.
+	fields: Vec<Rc<dyn Field<V>>>,
 	id: Uuid,
 }
 
-impl Particle {
+impl<V: physical_quantities::Vector> Particle<V> {
 	pub fn new(
-		mass: physical_quantities::Mass,
-		position: physical_quantities::Displacement,
-		velocity: physical_quantities::Velocity,
-		fields: Vec<Box<dyn Field>>,
+		mass: physical_quantities::Mass<V::Scalar>,
+		position: physical_quantities::DisplacementN<V>,
+		velocity: physical_quantities::VelocityN<V>,
+		fields: Vec<Box<dyn Field<V>>>,
+	) -> Self {
+		Self::from_parts(Uuid::new_v4(), mass, position, velocity, fields)
+	}
+
+	// Creates a particle with a given ID, e.g., when loading a saved
+	//	simulation or re-simulating after a rewind.
+	pub(crate) fn from_parts(
+		id: Uuid,
+		mass: physical_quantities::Mass<V::Scalar>,
+		position: physical_quantities::DisplacementN<V>,
+		velocity: physical_quantities::VelocityN<V>,
+		fields: Vec<Box<dyn Field<V>>>,
 	) -> Self {
 		Self {
 			mass: mass,
 			position: position,
+			previous_position: position,
 			velocity: velocity,
-			fields: fields,
-			id: Uuid::new_v4(),
+			rotation: None,
+			fields: fields.into_iter().map(Rc::from).collect(),
+			id: id,
 		}
 	}
 
-	pub fn get_mass(&self) -> physical_quantities::Mass {
+	// Sets the position before the most recent tick, e.g., when loading a
+	//	saved simulation.
+	#[cfg(feature = "persistence")]
+	pub(crate) fn with_previous_position(
+		mut self,
+		previous_position: physical_quantities::DisplacementN<V>,
+	) -> Self {
+		self.previous_position = previous_position;
+		self
+	}
+
+	/// Gives the particle a rotational state, so it can spin and be turned by
+	/// torques.
+	pub fn with_rotation(mut self, rotation: Rotation<V::Scalar>) -> Self {
+		self.rotation = Some(rotation);
+		self
+	}
+
+	pub fn get_mass(&self) -> physical_quantities::Mass<V::Scalar> {
 		self.mass
 	}
 
-	pub fn get_position(&self) -> physical_quantities::Displacement {
+	pub fn get_position(&self) -> physical_quantities::DisplacementN<V> {
 		self.position
 	}
 
-	pub fn get_velocity(&self) -> physical_quantities::Velocity {
+	/// Gets the particle's position before the most recent tick. For a
+	/// particle that hasn't been moved by a tick yet, this is its current
+	/// position.
+	pub fn get_previous_position(&self) -> physical_quantities::DisplacementN<V> {
+		self.previous_position
+	}
+
+	pub fn get_velocity(&self) -> physical_quantities::VelocityN<V> {
 		self.velocity
 	}
 
+	/// Gets the particle's rotational state, or `None` if it can't spin.
+	pub fn get_rotation(&self) -> Option<Rotation<V::Scalar>> {
+		self.rotation
+	}
+
 	pub fn get_id(&self) -> Uuid {
 		self.id
 	}
@@ -647,14 +1900,26 @@ impl Particle {
 				field.triggers_on_fields(),
 				field.triggers_on_particles(),
 				field.get_name().to_string(),
-			);
+				field.get_layers(),
+			).with_coefficient_of_restitution(field.get_coefficient_of_restitution());
 			field_info_vec.push(field_info);
 		}
 
 		field_info_vec
 	}
 
-	pub(crate) fn get_fields(&self) -> &Vec<Box<dyn Field>> {
+	/// Gets the layers this particle is on. This is every layer that any of
+	/// its fields are on, or `Layers::DEFAULT` if it has no fields.
+	pub fn get_layers(&self) -> Layers {
+		if self.fields.is_empty() {
+			return Layers::DEFAULT;
+		}
+
+		self.fields.iter()
+			.fold(Layers::NONE, |layers, field| layers | field.get_layers())
+	}
+
+	pub(crate) fn get_fields(&self) -> &Vec<Rc<dyn Field<V>>> {
 		&self.fields
 	}
 
@@ -679,29 +1944,46 @@ impl Particle {
 	//	starting velocity and set position.
 	pub fn accelerate(
 		&mut self,
-		forces: &Vec<physical_quantities::Force>,
-		time: physical_quantities::Time
+		forces: &[physical_quantities::ForceN<V>],
+		time: physical_quantities::Time<V::Scalar>
 	) {
 		let v_0 = self.velocity;
-		let mut x = 0.0;
-		let mut y = 0.0;
-
-		for force in forces {
-			x += force.x();
-			y += force.y();
-		}
+		let total_force: physical_quantities::ForceN<V> = forces.iter().copied().sum();
 
-		let total_force = physical_quantities::Force::new(x, y);
 		let acceleration = total_force / self.mass;
 
+		self.previous_position = self.position;
 		self.velocity = v_0 + acceleration * time;
 		self.position =
-			self.position + v_0 * time + 0.5 * acceleration * time * time;
+			self.position + v_0 * time + acceleration * time * (time * V::Scalar::from_f64(0.5));
 	}
 
 	// Given an amount of time, set the particle's new position based on its
 	//	velocity.
-	pub fn coast(&mut self, time: physical_quantities::Time) {
+	pub fn coast(&mut self, time: physical_quantities::Time<V::Scalar>) {
+		self.previous_position = self.position;
 		self.position += self.velocity * time;
 	}
+
+	// Given a list of torques and an amount of time, update the particle's
+	//	angular velocity and orientation the same way `accelerate` updates its
+	//	velocity and position. Particles without a rotation don't turn.
+	pub fn turn(
+		&mut self,
+		torques: &[physical_quantities::Torque<V::Scalar>],
+		time: physical_quantities::Time<V::Scalar>,
+	) {
+		let Some(rotation) = self.rotation.as_mut() else {
+			return;
+		};
+
+		let omega_0 = rotation.angular_velocity;
+		let total_torque: physical_quantities::Torque<V::Scalar> = torques.iter().copied().sum();
+		let angular_acceleration = total_torque / rotation.moment_of_inertia;
+
+		rotation.angular_velocity = omega_0 + angular_acceleration * time;
+		rotation.orientation = rotation.orientation
+			+ omega_0 * time
+			+ angular_acceleration * time * (time * V::Scalar::from_f64(0.5));
+	}
 }
@@ -0,0 +1,326 @@
+//! Approximate comparison of numbers, vectors and physical quantities, for
+//! tests. Enabled by the `testing` feature.
+//!
+//! Floating point results rarely match a hand-calculated value exactly, so
+//! `ApproxEq` compares values component by component within a `Tolerance`:
+//! an absolute difference, a difference relative to the values' size, or a
+//! number of representable values (units in the last place, or ULPs) between
+//! them. `assert_approx_eq!` panics with each component's difference when a
+//! comparison fails:
+//! ```
+//! use v0::assert_approx_eq;
+//! use v0::physical_quantities::Velocity;
+//! use v0::testing::Tolerance;
+//!
+//! let velocity = Velocity::new(0.1 + 0.2, 1.0);
+//! assert_approx_eq!(velocity, Velocity::new(0.3, 1.0), Tolerance::Ulps(1));
+//! ```
+
+use crate::physical_quantities;
+#[cfg(feature = "fixed-point")]
+use crate::fixed_point;
+use crate::physical_quantities::{Scalar, Vector};
+use std::fmt::Write;
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/********************* Tolerance ********************/
+
+	#[test]
+	fn absolute_tolerance_bounds_the_difference() {
+		assert!(Tolerance::Absolute(0.1).accepts(1.0, 1.05));
+		assert!(Tolerance::Absolute(0.1).accepts(-1.0, -0.95));
+		assert!(!Tolerance::Absolute(0.1).accepts(1.0, 1.2));
+		assert!(!Tolerance::Absolute(0.1).accepts(f64::NAN, f64::NAN));
+	}
+
+	#[test]
+	fn relative_tolerance_scales_with_the_values() {
+		assert!(Tolerance::Relative(1e-6).accepts(1.0e9, 1.0e9 + 100.0));
+		assert!(!Tolerance::Relative(1e-6).accepts(1.0e-9, 1.0e-9 + 1.0e-14));
+		assert!(Tolerance::Relative(0.0).accepts(0.0, 0.0));
+	}
+
+	#[test]
+	fn ulps_tolerance_counts_representable_values() {
+		assert!(Tolerance::Ulps(1).accepts(0.1 + 0.2, 0.3));
+		assert!(!Tolerance::Ulps(0).accepts(0.1 + 0.2, 0.3));
+		assert!(Tolerance::Ulps(0).accepts(0.0, -0.0));
+		assert!(Tolerance::Ulps(2).accepts(-f64::MIN_POSITIVE * f64::EPSILON, f64::MIN_POSITIVE * f64::EPSILON));
+		assert_eq!(1.0f32.ulps_between(1.0 + f32::EPSILON), 1);
+		assert_eq!(1.0f64.ulps_between(1.0 + f64::EPSILON), 1);
+		assert_eq!(1.0f64.ulps_between(f64::NAN), u64::MAX);
+	}
+
+	#[cfg(feature = "fixed-point")]
+	#[test]
+	fn ulps_of_fixed_point_numbers_are_raw_steps() {
+		let a = fixed_point::Fixed::from_raw(100);
+		assert_eq!(a.ulps_between(fixed_point::Fixed::from_raw(97)), 3);
+	}
+
+	/********************* ApproxEq ********************/
+
+	#[test]
+	fn quantities_compare_component_wise() {
+		let force = physical_quantities::Force::new(1.0, 2.0);
+		assert!(force.approx_eq(&physical_quantities::Force::new(1.05, 1.95), Tolerance::Absolute(0.1)));
+		assert!(!force.approx_eq(&physical_quantities::Force::new(1.0, 2.5), Tolerance::Absolute(0.1)));
+		assert!(physical_quantities::Energy::new(2.0).approx_eq(
+			&physical_quantities::Energy::new(2.0 + 1e-12),
+			Tolerance::Relative(1e-9),
+		));
+		assert!(physical_quantities::Vector3::new(1.0f32, 2.0, 3.0).approx_eq(
+			&physical_quantities::Vector3::new(1.0, 2.0, 3.0 + 3.0 * f32::EPSILON),
+			Tolerance::Ulps(2),
+		));
+	}
+
+	#[test]
+	fn compare_reports_each_component() {
+		let report = compare(
+			&physical_quantities::Velocity::new(1.0, 2.0),
+			&physical_quantities::Velocity::new(1.0, 2.5),
+			Tolerance::Absolute(0.1),
+		).expect("The velocities should differ.");
+		assert!(report.contains("[0]: left = 1, right = 1, difference = 0\n"), "{}", report);
+		assert!(report.contains("[1]: left = 2, right = 2.5, difference = 0.5 (out of tolerance)"), "{}", report);
+
+		assert_eq!(compare(&1.0, &1.0, Tolerance::Ulps(0)), None);
+	}
+
+	#[test]
+	fn assert_approx_eq_accepts_close_values() {
+		crate::assert_approx_eq!(0.1 + 0.2, 0.3, Tolerance::Ulps(1));
+		crate::assert_approx_eq!(
+			physical_quantities::Displacement3::new(1.0, 0.0, -1.0),
+			physical_quantities::Displacement3::new(1.0, 1e-10, -1.0),
+			Tolerance::Absolute(1e-9),
+			"after {} ticks",
+			3,
+		);
+	}
+
+	#[test]
+	#[should_panic(expected = "assertion `left ≈ right` failed: after 3 ticks")]
+	fn assert_approx_eq_panics_on_distant_values() {
+		crate::assert_approx_eq!(
+			physical_quantities::Time::new(1.0),
+			physical_quantities::Time::new(1.1),
+			Tolerance::Relative(0.01),
+			"after {} ticks",
+			3,
+		);
+	}
+}
+
+/// How close two numbers must be to be considered equal.
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub enum Tolerance {
+	/// The numbers differ by at most this much.
+	Absolute(f64),
+	/// The numbers differ by at most this fraction of the larger magnitude.
+	Relative(f64),
+	/// There are at most this many representable numbers between the two
+	/// (e.g., `Ulps(1)` accepts adjacent floats).
+	Ulps(u64),
+}
+
+impl Tolerance {
+	/// Checks whether two numbers are within this tolerance of each other. NaN
+	/// is never within tolerance of anything.
+	pub fn accepts<S: ApproxScalar>(&self, a: S, b: S) -> bool {
+		let difference = (a.to_f64() - b.to_f64()).abs();
+		match *self {
+			Tolerance::Absolute(tolerance) => difference <= tolerance,
+			Tolerance::Relative(tolerance) =>
+				difference <= tolerance * f64::max(a.to_f64().abs(), b.to_f64().abs()),
+			Tolerance::Ulps(tolerance) => a.ulps_between(b) <= tolerance,
+		}
+	}
+}
+
+/// A number type that can be compared with a `Tolerance`.
+pub trait ApproxScalar: Scalar {
+	/// Counts the representable numbers of this type between `self` and
+	/// `other`, so adjacent numbers are 1 apart. Returns `u64::MAX` if either
+	/// is NaN.
+	fn ulps_between(self, other: Self) -> u64;
+}
+
+impl ApproxScalar for f64 {
+	fn ulps_between(self, other: Self) -> u64 {
+		if self.is_nan() || other.is_nan() {
+			return u64::MAX;
+		}
+
+		// Orders the bit patterns so adjacent floats are adjacent integers and
+		//	both zeros are 0.
+		let ordered = |x: f64| {
+			let bits = x.to_bits() as i64;
+			if bits < 0 { i64::MIN.wrapping_sub(bits) } else { bits }
+		};
+		(ordered(self) as i128 - ordered(other) as i128).unsigned_abs().min(u64::MAX as u128) as u64
+	}
+}
+
+impl ApproxScalar for f32 {
+	fn ulps_between(self, other: Self) -> u64 {
+		if self.is_nan() || other.is_nan() {
+			return u64::MAX;
+		}
+
+		let ordered = |x: f32| {
+			let bits = x.to_bits() as i32;
+			if bits < 0 { i32::MIN.wrapping_sub(bits) } else { bits }
+		};
+		(ordered(self) as i64 - ordered(other) as i64).unsigned_abs()
+	}
+}
+
+#[cfg(feature = "fixed-point")]
+impl ApproxScalar for fixed_point::Fixed {
+	fn ulps_between(self, other: Self) -> u64 {
+		(self.get_raw() as i128 - other.get_raw() as i128).unsigned_abs().min(u64::MAX as u128) as u64
+	}
+}
+
+/// A value that can be compared approximately, one component at a time:
+/// numbers, vectors and physical quantities.
+pub trait ApproxEq {
+	type Scalar: ApproxScalar;
+
+	/// Gets the numbers to compare, e.g. the x and y components of a 2D
+	/// vector.
+	fn get_components(&self) -> Vec<Self::Scalar>;
+
+	/// Checks whether every component is within `tolerance` of the matching
+	/// component of `other`.
+	fn approx_eq(&self, other: &Self, tolerance: Tolerance) -> bool {
+		self.get_components().into_iter()
+			.zip(other.get_components())
+			.all(|(a, b)| tolerance.accepts(a, b))
+	}
+}
+
+impl ApproxEq for f64 {
+	type Scalar = f64;
+
+	fn get_components(&self) -> Vec<f64> {
+		vec![*self]
+	}
+}
+
+impl ApproxEq for f32 {
+	type Scalar = f32;
+
+	fn get_components(&self) -> Vec<f32> {
+		vec![*self]
+	}
+}
+
+#[cfg(feature = "fixed-point")]
+impl ApproxEq for fixed_point::Fixed {
+	type Scalar = fixed_point::Fixed;
+
+	fn get_components(&self) -> Vec<fixed_point::Fixed> {
+		vec![*self]
+	}
+}
+
+impl<S: ApproxScalar> ApproxEq for physical_quantities::Vector2<S> {
+	type Scalar = S;
+
+	fn get_components(&self) -> Vec<S> {
+		(0..Self::DIMENSIONS).map(|i| self.get_component(i)).collect()
+	}
+}
+
+impl<S: ApproxScalar> ApproxEq for physical_quantities::Vector3<S> {
+	type Scalar = S;
+
+	fn get_components(&self) -> Vec<S> {
+		(0..Self::DIMENSIONS).map(|i| self.get_component(i)).collect()
+	}
+}
+
+impl<D: crate::dimensions::Dimension, S: ApproxScalar> ApproxEq for physical_quantities::Quantity<D, S> {
+	type Scalar = S;
+
+	fn get_components(&self) -> Vec<S> {
+		vec![self.get_number()]
+	}
+}
+
+impl<D, V> ApproxEq for physical_quantities::QuantityN<D, V>
+where
+	D: crate::dimensions::Dimension,
+	V: Vector + ApproxEq<Scalar = <V as Vector>::Scalar>,
+	<V as Vector>::Scalar: ApproxScalar,
+{
+	type Scalar = <V as Vector>::Scalar;
+
+	fn get_components(&self) -> Vec<Self::Scalar> {
+		self.get_vector().get_components()
+	}
+}
+
+/// Compares two values, returning `None` if every component is within
+/// `tolerance`, or a description of each component's difference if not. Used
+/// by `assert_approx_eq!`.
+pub fn compare<T: ApproxEq>(left: &T, right: &T, tolerance: Tolerance) -> Option<String> {
+	if left.approx_eq(right, tolerance) {
+		return None;
+	}
+
+	let mut report = format!("tolerance: {:?}\n", tolerance);
+	for (i, (a, b)) in left.get_components().into_iter().zip(right.get_components()).enumerate() {
+		// Writing to a String can't fail.
+		let _ = write!(
+			report,
+			"[{}]: left = {}, right = {}, difference = {}",
+			i,
+			a.to_f64(),
+			b.to_f64(),
+			(a.to_f64() - b.to_f64()).abs(),
+		);
+		if !tolerance.accepts(a, b) {
+			report.push_str(" (out of tolerance)");
+		}
+		report.push('\n');
+	}
+
+	Some(report)
+}
+
+/// Asserts that two numbers, vectors or physical quantities are equal within a
+/// `testing::Tolerance`. On failure, panics with the difference between each
+/// pair of components. Like `assert_eq!`, an optional message may follow.
+///
+/// # Examples
+/// ```
+/// use v0::assert_approx_eq;
+/// use v0::physical_quantities::Energy;
+/// use v0::testing::Tolerance;
+///
+/// assert_approx_eq!(Energy::new(1.0 / 3.0), Energy::new(0.3333), Tolerance::Absolute(1e-4));
+/// assert_approx_eq!(0.1 + 0.2, 0.3, Tolerance::Relative(1e-12), "at step {}", 1);
+/// ```
+#[macro_export]
+macro_rules! assert_approx_eq {
+	($left:expr, $right:expr, $tolerance:expr $(,)?) => {
+		if let Some(report) = $crate::testing::compare(&$left, &$right, $tolerance) {
+			panic!("assertion `left ≈ right` failed\n{}", report);
+		}
+	};
+	($left:expr, $right:expr, $tolerance:expr, $($arg:tt)+) => {
+		if let Some(report) = $crate::testing::compare(&$left, &$right, $tolerance) {
+			panic!("assertion `left ≈ right` failed: {}\n{}", format_args!($($arg)+), report);
+		}
+	};
+}
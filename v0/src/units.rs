@@ -0,0 +1,718 @@
+//! Units for writing physical quantities as text and reading them back.
+//!
+//! `physical_quantities::Quantity` implements `Display`, which writes the
+//! number in SI units followed by the SI symbol for its dimension (e.g.,
+//! "9.81 m/s²"), and `FromStr`, which reads a number followed by an optional
+//! unit. Units may have SI prefixes ("km", "ms", "µs"), may be combined
+//! ("km/h", "kg m/s²", "N m") and may be raised to powers ("m^3", "s²",
+//! "s^-1"). Besides SI units, some common alternatives are understood, such as
+//! "t", "min", "h", "days", "yr", "AU" and "deg". A number without a unit is in
+//! SI units. Reading a unit of the wrong dimension (e.g., "5 m" as a `Mass`) is
+//! an error.
+//!
+//! A simulation doesn't have to work in SI units. A `UnitSystem` picks the
+//! units its numbers are in, such as astronomical units, solar masses and
+//! years for orbits, or ångströms, daltons and femtoseconds for molecules, so
+//! that numbers stay close to 1. `ScreenMapping` converts between positions in
+//! a simulation and pixels on a screen.
+
+use crate::{dimensions, physical_quantities};
+use std::fmt;
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::testing;
+
+	fn assert_close(actual: Result<f64, ParseQuantityError>, expected: f64) {
+		crate::assert_approx_eq!(actual.expect("Should parse."), expected, testing::Tolerance::Relative(1e-12));
+	}
+
+	/********************* Parsing ********************/
+
+	#[test]
+	fn parse_reads_numbers_in_si_units() {
+		assert_eq!(parse::<dimensions::Length>("5 m"), Ok(5.0));
+		assert_eq!(parse::<dimensions::Length>("2.5"), Ok(2.5));
+		assert_eq!(parse::<dimensions::Mass>(" 3e2 kg "), Ok(300.0));
+		assert_eq!(parse::<dimensions::Force>("-4N"), Ok(-4.0));
+	}
+
+	#[test]
+	fn parse_reads_prefixes() {
+		assert_eq!(parse::<dimensions::Length>("3 km"), Ok(3000.0));
+		assert_eq!(parse::<dimensions::Length>("40 cm"), Ok(0.4));
+		assert_eq!(parse::<dimensions::Mass>("500 g"), Ok(0.5));
+		assert_eq!(parse::<dimensions::Time>("20 ms"), Ok(0.02));
+		assert_close(parse::<dimensions::Time>("2 µs"), 2.0e-6);
+		assert_close(parse::<dimensions::Time>("2 us"), 2.0e-6);
+		assert_eq!(parse::<dimensions::Energy>("1.5 kJ"), Ok(1500.0));
+	}
+
+	#[test]
+	fn parse_reads_alternative_units() {
+		assert_eq!(parse::<dimensions::Mass>("2 t"), Ok(2000.0));
+		assert_eq!(parse::<dimensions::Time>("2 h"), Ok(7200.0));
+		assert_eq!(parse::<dimensions::Time>("2 days"), Ok(172800.0));
+		assert_eq!(parse::<dimensions::Length>("1 AU"), Ok(1.495978707e11));
+		assert_close(parse::<dimensions::Dimensionless>("90 deg"), std::f64::consts::FRAC_PI_2);
+	}
+
+	#[test]
+	fn parse_reads_combined_units() {
+		assert_close(parse::<dimensions::Velocity>("36 km/h"), 10.0);
+		assert_eq!(parse::<dimensions::Acceleration>("9.81 m/s²"), Ok(9.81));
+		assert_eq!(parse::<dimensions::Acceleration>("9.81 m/s^2"), Ok(9.81));
+		assert_eq!(parse::<dimensions::Acceleration>("9.81 m s^-2"), Ok(9.81));
+		assert_eq!(parse::<dimensions::Acceleration>("9.81 m/s/s"), Ok(9.81));
+		assert_eq!(parse::<dimensions::Force>("2 kg m/s²"), Ok(2.0));
+		assert_eq!(parse::<dimensions::Energy>("3 N m"), Ok(3.0));
+		assert_eq!(parse::<dimensions::Frequency>("4 1/s"), Ok(4.0));
+		assert_eq!(parse::<dimensions::GravitationalConstant>("6 m³/(kg s²)"), Ok(6.0));
+	}
+
+	#[test]
+	fn parse_fails_on_dimension_mismatch() {
+		let error = parse::<dimensions::Mass>("5 m").unwrap_err();
+		assert_eq!(error, ParseQuantityError::DimensionMismatch {
+			text: String::from("5 m"),
+			expected: (0, 1, 0),
+			found: (1, 0, 0),
+		});
+		assert_eq!(error.to_string(), "\"5 m\" is a length, but a mass is expected");
+		assert!(parse::<dimensions::Velocity>("5 m/s²").is_err());
+	}
+
+	#[test]
+	fn parse_fails_on_unknown_units_and_missing_numbers() {
+		assert_eq!(
+			parse::<dimensions::Time>("1 fortnight"),
+			Err(ParseQuantityError::UnknownUnit { unit: String::from("fortnight") }),
+		);
+		assert!(parse::<dimensions::Length>("1 km^x").is_err());
+		assert!(parse::<dimensions::Length>("1 m/").is_err());
+		assert_eq!(
+			parse::<dimensions::Length>("far"),
+			Err(ParseQuantityError::InvalidNumber { text: String::from("far") }),
+		);
+	}
+
+	/********************* Conversion ********************/
+
+	#[test]
+	fn convert_converts_from_si_units() {
+		assert_eq!(convert::<dimensions::Length>(1500.0, "km"), Ok(1.5));
+		assert_eq!(convert::<dimensions::Time>(7200.0, "h"), Ok(2.0));
+		assert!(convert::<dimensions::Length>(1500.0, "kg").is_err());
+	}
+
+	/********************* Symbols ********************/
+
+	#[test]
+	fn get_symbol_writes_si_units() {
+		assert_eq!(get_symbol::<dimensions::Dimensionless>(), "");
+		assert_eq!(get_symbol::<dimensions::Length>(), "m");
+		assert_eq!(get_symbol::<dimensions::Mass>(), "kg");
+		assert_eq!(get_symbol::<dimensions::Area>(), "m²");
+		assert_eq!(get_symbol::<dimensions::Frequency>(), "1/s");
+		assert_eq!(get_symbol::<dimensions::Acceleration>(), "m/s²");
+		assert_eq!(get_symbol::<dimensions::Momentum>(), "kg m/s");
+		assert_eq!(get_symbol::<dimensions::MomentOfInertia>(), "kg m²");
+		assert_eq!(get_symbol::<dimensions::Force>(), "N");
+		assert_eq!(get_symbol::<dimensions::Energy>(), "J");
+		assert_eq!(get_symbol::<dimensions::GravitationalConstant>(), "m³/(kg s²)");
+	}
+
+	#[test]
+	fn symbols_can_be_parsed() {
+		assert_eq!(parse::<dimensions::Momentum>("1 kg m/s"), Ok(1.0));
+		assert_eq!(parse::<dimensions::AngularMomentum>("1 kg m²/s"), Ok(1.0));
+		assert_eq!(parse::<dimensions::Pressure>("1 Pa"), Ok(1.0));
+	}
+
+	/********************* UnitSystem ********************/
+
+	#[test]
+	fn unit_system_converts_to_and_from_si() {
+		let astronomical = UnitSystem::ASTRONOMICAL;
+		assert_eq!(astronomical.to_si::<dimensions::Length>(2.0), 2.0 * 1.495978707e11);
+		assert_eq!(astronomical.from_si::<dimensions::Mass>(1.98847e30), 1.0);
+		assert_eq!(astronomical.get_scale::<dimensions::Velocity>(), 1.495978707e11 / 3.15576e7);
+		assert_eq!(UnitSystem::SI.get_scale::<dimensions::Energy>(), 1.0);
+
+		let molecular = UnitSystem::MOLECULAR;
+		assert!((molecular.from_si::<dimensions::Time>(1.0e-12) - 1000.0).abs() < 1e-9);
+		assert_eq!(molecular.parse::<dimensions::Length>("2 Å"), Ok(2.0));
+		assert!((molecular.parse::<dimensions::Length>("1 nm").unwrap() - 10.0).abs() < 1e-12);
+		assert!(molecular.parse::<dimensions::Length>("1 kg").is_err());
+	}
+
+	#[test]
+	fn unit_system_gets_gravitational_constant() {
+		assert_eq!(UnitSystem::SI.get_gravitational_constant(), GRAVITATIONAL_CONSTANT);
+
+		// Kepler's third law: with these units, a 1 AU orbit of the Sun takes
+		//	about one year, so G is about 4π².
+		let g = UnitSystem::ASTRONOMICAL.get_gravitational_constant();
+		let four_pi_squared = 4.0 * std::f64::consts::PI * std::f64::consts::PI;
+		assert!((g - four_pi_squared).abs() / four_pi_squared < 1e-3, "{}", g);
+	}
+
+	#[test]
+	#[should_panic(expected = "UnitSystem::new(): units must be positive")]
+	fn unit_system_new_panics_if_not_positive() {
+		UnitSystem::new(1.0, 0.0, 1.0);
+	}
+
+	/********************* ScreenMapping ********************/
+
+	#[test]
+	fn screen_mapping_converts_positions() {
+		let mapping = ScreenMapping::new(50.0, (400.0, 300.0));
+		assert_eq!(mapping.to_screen(physical_quantities::Displacement::new(0.0, 0.0)), (400.0, 300.0));
+		// Up in the world is up on the screen, where y grows downward.
+		assert_eq!(mapping.to_screen(physical_quantities::Displacement::new(2.0, 1.0)), (500.0, 250.0));
+		assert_eq!(mapping.to_world((500.0, 250.0)), physical_quantities::Displacement::new(2.0, 1.0));
+		assert_eq!(mapping.length_to_screen(0.5), 25.0);
+		assert_eq!(mapping.length_to_world(25.0), 0.5);
+	}
+
+	#[test]
+	fn screen_mapping_fits_a_view() {
+		// 10 AU across an 800 by 600 pixel window, centered on the origin.
+		let mapping = ScreenMapping::fit(
+			physical_quantities::Displacement::new(-5.0, -5.0),
+			physical_quantities::Displacement::new(5.0, 5.0),
+			(800.0, 600.0),
+		);
+		assert_eq!(mapping.get_pixels_per_unit(), 60.0);
+		assert_eq!(mapping.to_screen(physical_quantities::Displacement::new(0.0, 0.0)), (400.0, 300.0));
+		assert_eq!(mapping.to_screen(physical_quantities::Displacement::new(-5.0, 5.0)), (100.0, 0.0));
+	}
+}
+
+/// Something that went wrong while reading a quantity from text.
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone)]
+pub enum ParseQuantityError {
+	/// The text doesn't start with a number.
+	InvalidNumber {
+		text: String,
+	},
+	/// A unit isn't recognized, with or without an SI prefix.
+	UnknownUnit {
+		unit: String,
+	},
+	/// The units are of a different dimension than the quantity (e.g., a
+	/// length where a mass is expected). Dimensions are given as exponents of
+	/// length, mass and time; see `dimensions::get_exponents`.
+	DimensionMismatch {
+		text: String,
+		expected: (i32, i32, i32),
+		found: (i32, i32, i32),
+	},
+}
+
+impl fmt::Display for ParseQuantityError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ParseQuantityError::InvalidNumber { text } => write!(
+				f,
+				"expected a number and an optional unit, such as \"1.5 km\", found \"{}\"",
+				text,
+			),
+			ParseQuantityError::UnknownUnit { unit } => write!(f, "unknown unit \"{}\"", unit),
+			ParseQuantityError::DimensionMismatch { text, expected, found } => write!(
+				f,
+				"\"{}\" is {}, but {} is expected",
+				text,
+				describe_dimension(*found),
+				describe_dimension(*expected),
+			),
+		}
+	}
+}
+
+impl std::error::Error for ParseQuantityError {}
+
+// The exponents of length, mass and time in a unit. See
+//	`dimensions::get_exponents`.
+type Exponents = (i32, i32, i32);
+
+// Each unit, along with the number of SI units in one of it, the exponents of
+//	length, mass and time in it, and whether it can have an SI prefix.
+const UNITS: &[(&str, f64, Exponents, bool)] = &[
+	("m", 1.0, (1, 0, 0), true),
+	("g", 1.0e-3, (0, 1, 0), true),
+	("s", 1.0, (0, 0, 1), true),
+	("Hz", 1.0, (0, 0, -1), true),
+	("N", 1.0, (1, 1, -2), true),
+	("J", 1.0, (2, 1, -2), true),
+	("W", 1.0, (2, 1, -3), true),
+	("Pa", 1.0, (-1, 1, -2), true),
+	("L", 1.0e-3, (3, 0, 0), true),
+	("eV", 1.602176634e-19, (2, 1, -2), true),
+	("rad", 1.0, (0, 0, 0), false),
+	("deg", std::f64::consts::PI / 180.0, (0, 0, 0), false),
+	("°", std::f64::consts::PI / 180.0, (0, 0, 0), false),
+	("t", 1.0e3, (0, 1, 0), false),
+	("min", 60.0, (0, 0, 1), false),
+	("h", 3600.0, (0, 0, 1), false),
+	("d", 86400.0, (0, 0, 1), false),
+	("day", 86400.0, (0, 0, 1), false),
+	("days", 86400.0, (0, 0, 1), false),
+	// Julian years, as used in astronomy.
+	("yr", 3.15576e7, (0, 0, 1), false),
+	("years", 3.15576e7, (0, 0, 1), false),
+	("AU", 1.495978707e11, (1, 0, 0), false),
+	("Å", 1.0e-10, (1, 0, 0), false),
+	("Da", 1.66053906660e-27, (0, 1, 0), true),
+	("M☉", 1.98847e30, (0, 1, 0), false),
+	("ly", 9.4607304725808e15, (1, 0, 0), false),
+	("pc", 3.085677581491367e16, (1, 0, 0), false),
+];
+
+// SI prefixes, with "da" first so it isn't read as "d" followed by "a".
+const PREFIXES: &[(&str, f64)] = &[
+	("da", 1.0e1),
+	("Y", 1.0e24),
+	("Z", 1.0e21),
+	("E", 1.0e18),
+	("P", 1.0e15),
+	("T", 1.0e12),
+	("G", 1.0e9),
+	("M", 1.0e6),
+	("k", 1.0e3),
+	("h", 1.0e2),
+	("d", 1.0e-1),
+	("c", 1.0e-2),
+	("m", 1.0e-3),
+	("µ", 1.0e-6),
+	("μ", 1.0e-6),
+	("u", 1.0e-6),
+	("n", 1.0e-9),
+	("p", 1.0e-12),
+	("f", 1.0e-15),
+	("a", 1.0e-18),
+];
+
+// Derived units with symbols of their own. Other units are written in terms of
+//	kg, m and s.
+const NAMED_UNITS: &[(&str, Exponents)] = &[
+	("N", (1, 1, -2)),
+	("J", (2, 1, -2)),
+	("W", (2, 1, -3)),
+	("Pa", (-1, 1, -2)),
+];
+
+const SUPERSCRIPTS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+
+/// Gets the SI symbol for the units of dimension `D`, such as "m/s²" for
+/// `dimensions::Acceleration` or "N" for `dimensions::Force`. Dimensionless
+/// quantities have an empty symbol.
+pub fn get_symbol<D: dimensions::Dimension>() -> String {
+	format_symbol(dimensions::get_exponents::<D>())
+}
+
+fn format_symbol(exponents: Exponents) -> String {
+	if let Some((symbol, _)) = NAMED_UNITS.iter().find(|(_, e)| *e == exponents) {
+		return symbol.to_string();
+	}
+
+	let (length, mass, time) = exponents;
+	let terms = [("kg", mass), ("m", length), ("s", time)];
+	let numerator = terms.iter()
+		.filter(|(_, power)| *power > 0)
+		.map(|(symbol, power)| format_power(symbol, *power))
+		.collect::<Vec<_>>();
+	let denominator = terms.iter()
+		.filter(|(_, power)| *power < 0)
+		.map(|(symbol, power)| format_power(symbol, -power))
+		.collect::<Vec<_>>();
+
+	let numerator = if numerator.is_empty() && !denominator.is_empty() {
+		String::from("1")
+	} else {
+		numerator.join(" ")
+	};
+	match denominator.len() {
+		0 => numerator,
+		1 => format!("{}/{}", numerator, denominator[0]),
+		_ => format!("{}/({})", numerator, denominator.join(" ")),
+	}
+}
+
+// Writes a unit raised to a positive power, e.g. "m²".
+fn format_power(symbol: &str, power: i32) -> String {
+	if power == 1 {
+		return symbol.to_string();
+	}
+
+	let digits = power.to_string().chars()
+		.filter_map(|c| c.to_digit(10))
+		.map(|digit| SUPERSCRIPTS[digit as usize])
+		.collect::<String>();
+	format!("{}{}", symbol, digits)
+}
+
+// Names a dimension for error messages, e.g. "a length" or "an energy".
+fn describe_dimension(exponents: Exponents) -> String {
+	let name = match exponents {
+		(0, 0, 0) => "dimensionless number",
+		(1, 0, 0) => "length",
+		(0, 1, 0) => "mass",
+		(0, 0, 1) => "time",
+		(2, 0, 0) => "area",
+		(3, 0, 0) => "volume",
+		(0, 0, -1) => "frequency",
+		(1, 0, -1) => "speed",
+		(1, 0, -2) => "acceleration",
+		(1, 1, -2) => "force",
+		(1, 1, -1) => "momentum",
+		(2, 1, -2) => "energy",
+		(2, 1, -3) => "power",
+		(-1, 1, -2) => "pressure",
+		(2, 1, -1) => "angular momentum",
+		(2, 1, 0) => "moment of inertia",
+		_ => return format!("a quantity in {}", format_symbol(exponents)),
+	};
+
+	let article = if name.starts_with(['a', 'e', 'i', 'o', 'u']) { "an" } else { "a" };
+	format!("{} {}", article, name)
+}
+
+// Finds a unit, possibly with an SI prefix, returning the number of SI units in
+//	one of it and its dimension.
+fn find_unit(symbol: &str) -> Option<(f64, Exponents)> {
+	if let Some((_, factor, exponents, _)) = UNITS.iter().find(|(s, ..)| *s == symbol) {
+		return Some((*factor, *exponents));
+	}
+
+	PREFIXES.iter().find_map(|(prefix, multiplier)| {
+		let rest = symbol.strip_prefix(prefix)?;
+		let (_, factor, exponents, _) = UNITS.iter()
+			.find(|(s, _, _, prefixable)| *prefixable && *s == rest)?;
+		Some((multiplier * factor, *exponents))
+	})
+}
+
+// Reads one unit raised to a power, written as "m", "m^2", "s^-1", "m²" or
+//	"s⁻¹".
+fn parse_term(term: &str) -> Result<(f64, Exponents), ParseQuantityError> {
+	let (symbol, power) = match term.split_once('^') {
+		Some((symbol, power)) => (symbol, power.parse::<i32>().ok()),
+		None => {
+			let symbol = term.trim_end_matches(|c| SUPERSCRIPTS.contains(&c) || c == '⁻');
+			let power = term[symbol.len()..].chars()
+				.map(|c| match SUPERSCRIPTS.iter().position(|s| *s == c) {
+					Some(digit) => char::from(b'0' + digit as u8),
+					None => '-',
+				})
+				.collect::<String>();
+			if power.is_empty() {
+				(symbol, Some(1))
+			} else {
+				(symbol, power.parse::<i32>().ok())
+			}
+		},
+	};
+
+	let unknown = || ParseQuantityError::UnknownUnit { unit: term.to_string() };
+	let power = power.ok_or_else(unknown)?;
+	let (factor, (length, mass, time)) = find_unit(symbol).ok_or_else(unknown)?;
+	Ok((factor.powi(power), (length * power, mass * power, time * power)))
+}
+
+// Reads units such as "km/h" or "m³/(kg s²)", returning the number of SI units
+//	in one of them and their dimension. Units are separated by spaces, "*" or
+//	"·", and everything after a "/" is divided by.
+fn parse_unit(unit: &str) -> Result<(f64, Exponents), ParseQuantityError> {
+	let mut factor = 1.0;
+	let mut exponents = (0, 0, 0);
+	for (i, part) in unit.split('/').enumerate() {
+		let is_denominator = i > 0;
+		let part = part.trim().trim_start_matches('(').trim_end_matches(')');
+		let terms = part.split(|c: char| c.is_whitespace() || c == '*' || c == '·')
+			.filter(|term| !term.is_empty())
+			.collect::<Vec<_>>();
+		if is_denominator && terms.is_empty() {
+			return Err(ParseQuantityError::UnknownUnit { unit: unit.to_string() });
+		}
+
+		for term in terms {
+			// Allows, e.g., "1/s".
+			if !is_denominator && term == "1" {
+				continue;
+			}
+
+			let (term_factor, (length, mass, time)) = parse_term(term)?;
+			if is_denominator {
+				factor /= term_factor;
+				exponents = (exponents.0 - length, exponents.1 - mass, exponents.2 - time);
+			} else {
+				factor *= term_factor;
+				exponents = (exponents.0 + length, exponents.1 + mass, exponents.2 + time);
+			}
+		}
+	}
+
+	Ok((factor, exponents))
+}
+
+// Checks that units are of dimension `D`.
+fn check_dimension<D: dimensions::Dimension>(
+	text: &str,
+	found: Exponents,
+) -> Result<(), ParseQuantityError> {
+	let expected = dimensions::get_exponents::<D>();
+	if found != expected {
+		return Err(ParseQuantityError::DimensionMismatch {
+			text: text.to_string(),
+			expected: expected,
+			found: found,
+		});
+	}
+
+	Ok(())
+}
+
+// Converts a string such as "5 km" to a number in SI units.
+pub(crate) fn parse<D: dimensions::Dimension>(text: &str) -> Result<f64, ParseQuantityError> {
+	let text = text.trim();
+
+	// Find the longest prefix that is a number. The rest is the unit.
+	let split = (1..=text.len()).rev()
+		.filter(|i| text.is_char_boundary(*i))
+		.find(|i| text[..*i].trim_end().parse::<f64>().is_ok());
+	let Some(i) = split else {
+		return Err(ParseQuantityError::InvalidNumber { text: text.to_string() });
+	};
+	let number = text[..i].trim_end().parse::<f64>().unwrap_or(0.0);
+	let unit = text[i..].trim();
+
+	if unit.is_empty() {
+		return Ok(number);
+	}
+
+	let (factor, found) = parse_unit(unit)?;
+	check_dimension::<D>(text, found)?;
+	Ok(number * factor)
+}
+
+// Converts a number in SI units to a number in `unit`.
+pub(crate) fn convert<D: dimensions::Dimension>(
+	number: f64,
+	unit: &str,
+) -> Result<f64, ParseQuantityError> {
+	let (factor, found) = parse_unit(unit.trim())?;
+	check_dimension::<D>(unit, found)?;
+	Ok(number / factor)
+}
+
+/// The gravitational constant, G, in m³ / (kg s²).
+pub const GRAVITATIONAL_CONSTANT: f64 = 6.6743e-11;
+
+/// The units that the numbers in a simulation are in, given as the number of
+/// meters, kilograms and seconds in one unit of length, mass and time. Every
+/// other unit follows from these (e.g., speeds are in units of length per unit
+/// of time). See `Simulation::set_unit_system`.
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "persistence", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnitSystem {
+	length: f64,
+	mass: f64,
+	time: f64,
+}
+
+impl UnitSystem {
+	/// Meters, kilograms and seconds.
+	pub const SI: UnitSystem = UnitSystem { length: 1.0, mass: 1.0, time: 1.0 };
+	/// Astronomical units, solar masses and Julian years, for orbits. G is
+	/// about 4π² in these units.
+	pub const ASTRONOMICAL: UnitSystem = UnitSystem {
+		length: 1.495978707e11,
+		mass: 1.98847e30,
+		time: 3.15576e7,
+	};
+	/// Ångströms, daltons (unified atomic mass units) and femtoseconds, for
+	/// molecular dynamics.
+	pub const MOLECULAR: UnitSystem = UnitSystem {
+		length: 1.0e-10,
+		mass: 1.66053906660e-27,
+		time: 1.0e-15,
+	};
+
+	/// Creates a custom `UnitSystem`.
+	///
+	/// # Arguments
+	/// * `length` - The number of meters in one unit of length.
+	/// * `mass` - The number of kilograms in one unit of mass.
+	/// * `time` - The number of seconds in one unit of time.
+	///
+	/// # Panics
+	/// Panics if any unit is not positive.
+	pub fn new(length: f64, mass: f64, time: f64) -> Self {
+		if length <= 0.0 || mass <= 0.0 || time <= 0.0 {
+			panic!("UnitSystem::new(): units must be positive");
+		}
+
+		Self {
+			length: length,
+			mass: mass,
+			time: time,
+		}
+	}
+
+	pub fn get_length_unit(&self) -> f64 {
+		self.length
+	}
+
+	pub fn get_mass_unit(&self) -> f64 {
+		self.mass
+	}
+
+	pub fn get_time_unit(&self) -> f64 {
+		self.time
+	}
+
+	/// Gets the number of SI units in one of this system's units of dimension
+	/// `D` (e.g., meters per second in one AU per year).
+	pub fn get_scale<D: dimensions::Dimension>(&self) -> f64 {
+		let (length, mass, time) = dimensions::get_exponents::<D>();
+		self.length.powi(length) * self.mass.powi(mass) * self.time.powi(time)
+	}
+
+	/// Converts a number in this system's units of dimension `D` to SI units.
+	pub fn to_si<D: dimensions::Dimension>(&self, number: f64) -> f64 {
+		number * self.get_scale::<D>()
+	}
+
+	/// Converts a number in SI units of dimension `D` to this system's units.
+	pub fn from_si<D: dimensions::Dimension>(&self, number: f64) -> f64 {
+		number / self.get_scale::<D>()
+	}
+
+	/// Reads a number with a unit, such as "5 km", and converts it to this
+	/// system's units of dimension `D`. A number without a unit is already in
+	/// this system's units. See the module documentation for the units that
+	/// can be used.
+	pub fn parse<D: dimensions::Dimension>(&self, text: &str) -> Result<f64, ParseQuantityError> {
+		// Check for a bare number first, since parse treats it as SI.
+		if let Ok(number) = text.trim().parse::<f64>() {
+			return Ok(number);
+		}
+
+		parse::<D>(text).map(|number| self.from_si::<D>(number))
+	}
+
+	/// Gets the gravitational constant, G, in this system's units.
+	pub fn get_gravitational_constant(&self) -> f64 {
+		self.from_si::<dimensions::GravitationalConstant>(GRAVITATIONAL_CONSTANT)
+	}
+}
+
+impl Default for UnitSystem {
+	fn default() -> Self {
+		Self::SI
+	}
+}
+
+/// Converts between positions in a simulation and pixel coordinates on a
+/// screen, where y grows downward. Positions in the simulation are in its
+/// units of length (see `UnitSystem`), with y growing upward.
+#[derive(PartialEq)]
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+pub struct ScreenMapping {
+	pixels_per_unit: f64,
+	origin: (f64, f64),
+}
+
+impl ScreenMapping {
+	/// Creates an instance of `ScreenMapping`.
+	///
+	/// # Arguments
+	/// * `pixels_per_unit` - The number of pixels in one unit of length.
+	/// * `origin` - The pixel coordinates at which to draw the simulation's
+	///		origin.
+	///
+	/// # Panics
+	/// Panics if `pixels_per_unit` is not positive.
+	pub fn new(pixels_per_unit: f64, origin: (f64, f64)) -> Self {
+		if pixels_per_unit <= 0.0 {
+			panic!("ScreenMapping::new(): pixels_per_unit must be positive");
+		}
+
+		Self {
+			pixels_per_unit: pixels_per_unit,
+			origin: origin,
+		}
+	}
+
+	/// Creates a mapping that shows the rectangle from `min` to `max` as large
+	/// as possible, centered on a screen of size `screen_size` (width, height)
+	/// in pixels.
+	///
+	/// # Panics
+	/// Panics if the rectangle has no area.
+	pub fn fit<S: physical_quantities::Scalar>(
+		min: physical_quantities::Displacement<S>,
+		max: physical_quantities::Displacement<S>,
+		screen_size: (f64, f64),
+	) -> Self {
+		let (min_x, min_y) = (min.x().to_f64(), min.y().to_f64());
+		let (max_x, max_y) = (max.x().to_f64(), max.y().to_f64());
+		let pixels_per_unit = f64::min(screen_size.0 / (max_x - min_x), screen_size.1 / (max_y - min_y));
+		let center = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+
+		Self::new(
+			pixels_per_unit,
+			(
+				screen_size.0 / 2.0 - center.0 * pixels_per_unit,
+				screen_size.1 / 2.0 + center.1 * pixels_per_unit,
+			),
+		)
+	}
+
+	pub fn get_pixels_per_unit(&self) -> f64 {
+		self.pixels_per_unit
+	}
+
+	pub fn get_origin(&self) -> (f64, f64) {
+		self.origin
+	}
+
+	/// Gets the pixel coordinates at which to draw a position.
+	pub fn to_screen<S: physical_quantities::Scalar>(
+		&self,
+		position: physical_quantities::Displacement<S>,
+	) -> (f64, f64) {
+		(
+			self.origin.0 + position.x().to_f64() * self.pixels_per_unit,
+			self.origin.1 - position.y().to_f64() * self.pixels_per_unit,
+		)
+	}
+
+	/// Gets the position drawn at some pixel coordinates (e.g., where the user
+	/// clicked).
+	pub fn to_world(&self, screen_position: (f64, f64)) -> physical_quantities::Displacement {
+		physical_quantities::Displacement::new(
+			(screen_position.0 - self.origin.0) / self.pixels_per_unit,
+			(self.origin.1 - screen_position.1) / self.pixels_per_unit,
+		)
+	}
+
+	/// Converts a length, such as a radius, to pixels.
+	pub fn length_to_screen(&self, length: f64) -> f64 {
+		length * self.pixels_per_unit
+	}
+
+	/// Converts a length in pixels to the simulation's units.
+	pub fn length_to_world(&self, pixels: f64) -> f64 {
+		pixels / self.pixels_per_unit
+	}
+}
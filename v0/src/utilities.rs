@@ -1,6 +1,131 @@
 use crate::{physical_quantities};
 
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::physical_quantities::{Displacement, GravitationalConstant, Length, Mass, Speed, Velocity, VectorQuantity};
+	use crate::testing;
+
+	/********************* Layouts ********************/
+
+	#[test]
+	fn sample_circle_spaces_points_evenly() {
+		let points = sample_circle(Displacement::new(1.0, 2.0), Length::new(3.0), 4, 0.0);
+		assert_eq!(points.len(), 4);
+		crate::assert_approx_eq!(points[0], Displacement::new(4.0, 2.0), testing::Tolerance::Absolute(1e-12));
+		crate::assert_approx_eq!(points[1], Displacement::new(1.0, 5.0), testing::Tolerance::Absolute(1e-12));
+		crate::assert_approx_eq!(points[2], Displacement::new(-2.0, 2.0), testing::Tolerance::Absolute(1e-12));
+		crate::assert_approx_eq!(points[3], Displacement::new(1.0, -1.0), testing::Tolerance::Absolute(1e-12));
+		assert!(sample_circle(Displacement::new(0.0, 0.0), Length::new(1.0), 0, 0.0).is_empty());
+	}
+
+	#[test]
+	fn sample_circle_works_with_f32() {
+		let points = sample_circle(Displacement::<f32>::new(1.0, 2.0), Length::new(3.0), 2, 0.0);
+		crate::assert_approx_eq!(points[0], Displacement::<f32>::new(4.0, 2.0), testing::Tolerance::Absolute(1e-6));
+		crate::assert_approx_eq!(points[1], Displacement::<f32>::new(-2.0, 2.0), testing::Tolerance::Absolute(1e-6));
+	}
+
+	#[test]
+	fn layout_ring_gives_counterclockwise_tangential_velocities() {
+		let ring: Vec<(Displacement, Velocity)> =
+			layout_ring(Displacement::new(0.0, 0.0), Length::new(2.0), 3, 1.0, Speed::new(5.0));
+		assert_eq!(ring.len(), 3);
+		for (position, velocity) in ring {
+			crate::assert_approx_eq!(position.get_norm().get_number(), 2.0, testing::Tolerance::Ulps(4));
+			crate::assert_approx_eq!(velocity.get_norm(), Speed::new(5.0), testing::Tolerance::Ulps(4));
+			assert!(position.dot(velocity.get_vector()).abs() < 1e-12);
+			assert!(position.cross(velocity.get_vector()) > 0.0);
+		}
+	}
+
+	#[test]
+	fn layout_grid_fills_rows_then_columns() {
+		let points = layout_grid(Displacement::new(1.0, 1.0), 2, 3, Displacement::new(2.0, 0.5));
+		assert_eq!(
+			points,
+			vec![
+				Displacement::new(1.0, 1.0),
+				Displacement::new(3.0, 1.0),
+				Displacement::new(5.0, 1.0),
+				Displacement::new(1.0, 1.5),
+				Displacement::new(3.0, 1.5),
+				Displacement::new(5.0, 1.5),
+			],
+		);
+		assert!(layout_grid(Displacement::new(0.0, 0.0), 0, 3, Displacement::new(1.0, 1.0)).is_empty());
+	}
+
+	/********************* Random ********************/
+
+	#[test]
+	fn random_is_reproducible_from_its_seed() {
+		let mut a = Random::new(42);
+		let mut b = Random::new(42);
+		let mut c = Random::new(43);
+		let sequence: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+		assert_eq!(sequence, (0..10).map(|_| b.next_u64()).collect::<Vec<u64>>());
+		assert_ne!(sequence, (0..10).map(|_| c.next_u64()).collect::<Vec<u64>>());
+	}
+
+	#[test]
+	fn random_numbers_fall_within_their_ranges() {
+		let mut random = Random::new(7);
+		for _ in 0..1000 {
+			let unit = random.next_f64();
+			assert!((0.0..1.0).contains(&unit));
+			let ranged = random.next_in_range(-2.0, 3.0);
+			assert!((-2.0..3.0).contains(&ranged));
+		}
+	}
+
+	#[test]
+	fn random_points_fall_within_the_disc_and_cover_it() {
+		let mut random = Random::new(1);
+		let center = Displacement::new(10.0, -5.0);
+		let points: Vec<Displacement> = (0..2000).map(|_| random.get_point_in_disc(center, Length::new(4.0))).collect();
+		assert!(points.iter().all(|point| is_within_radius(*point, 4.0, center, false)));
+		// Uniform over the area, so about a quarter fall within half the radius.
+		let inner = points.iter().filter(|point| is_within_radius(**point, 2.0, center, true)).count();
+		assert!((400..600).contains(&inner));
+	}
+
+	/********************* Orbits ********************/
+
+	#[test]
+	fn circular_orbit_speed_balances_gravity() {
+		// Gravity supplies the centripetal acceleration: G M / r² = v² / r.
+		let speed = get_circular_orbit_speed(Mass::new(4.0), Length::new(2.0), GravitationalConstant::new(0.5));
+		assert_eq!(speed, Speed::new(1.0));
+		let speed = get_circular_orbit_speed(
+			Mass::new(5.972e24),
+			Length::new(6.771e6),
+			GravitationalConstant::new(crate::units::GRAVITATIONAL_CONSTANT),
+		);
+		crate::assert_approx_eq!(speed, Speed::new(7672.6), testing::Tolerance::Relative(1e-4));
+	}
+
+	#[test]
+	fn circular_orbit_speed_works_with_f32() {
+		let speed = get_circular_orbit_speed(
+			Mass::<f32>::new(4.0),
+			Length::new(2.0),
+			GravitationalConstant::new(0.5),
+		);
+		assert_eq!(speed, Speed::<f32>::new(1.0));
+	}
+
+	#[test]
+	fn circular_orbit_velocity_is_perpendicular_and_counterclockwise() {
+		let velocity = get_circular_orbit_velocity(Mass::new(4.0), Displacement::new(0.0, 2.0), GravitationalConstant::new(0.5));
+		assert_eq!(velocity, Velocity::new(-1.0, 0.0));
+		let velocity = get_circular_orbit_velocity(Mass::new(4.0), Displacement::new(-2.0, 0.0), GravitationalConstant::new(0.5));
+		assert_eq!(velocity, Velocity::new(0.0, -1.0));
+	}
+}
+
 /// Determines whether a given point is within a given radius of another point.
+/// Works in two or three dimensions; in three, the radius describes a sphere.
 ///
 /// # Arguments
 /// * `point` - The point that may fall within or outside of some radius.
@@ -22,34 +147,33 @@ use crate::{physical_quantities};
 ///		example point will be considered outside the radius and the function
 ///		will return `false`.
 // TODO: Consider making this a method of Displacement.
-pub fn is_within_radius(
-	point: physical_quantities::Displacement,
+pub fn is_within_radius<V: physical_quantities::Vector>(
+	point: physical_quantities::DisplacementN<V>,
 	radius: f64,
-	center_of_radius: physical_quantities::Displacement,
+	center_of_radius: physical_quantities::DisplacementN<V>,
 	use_strict_inequality: bool,
 ) -> bool {
-	// distance = sqrt((x2 - x1)^2 + (y2 - y1)^2)
+	// distance = sqrt((x2 - x1)^2 + (y2 - y1)^2 [+ (z2 - z1)^2])
 	let distance = measure_distance(point, center_of_radius);
+	let radius: V::Scalar = physical_quantities::Scalar::from_f64(radius.abs());
 	if use_strict_inequality {
-		return distance < radius.abs()
+		distance < radius
 	} else {
-		return distance <= radius.abs()
+		distance <= radius
 	}
 }
 
-/// Gets the distance between two points (`Displacements`) as an `f64`.
+/// Gets the distance between two points (`Displacements`), in two or three
+/// dimensions.
 ///
 /// # Arguments
 /// * `point1` - One point.
 /// * `point2` - The other point.
-pub fn measure_distance(
-	point1: physical_quantities::Displacement,
-	point2: physical_quantities::Displacement,
-) -> f64 {
-	(
-		(point1.x() - point2.x()).powf(2.0)
-		+ (point1.y() - point2.y()).powf(2.0)
-	).sqrt()
+pub fn measure_distance<V: physical_quantities::Vector>(
+	point1: physical_quantities::DisplacementN<V>,
+	point2: physical_quantities::DisplacementN<V>,
+) -> V::Scalar {
+	(point1 - point2).get_magnitude()
 }
 
 /// Gets the displacement vector from one point to another.
@@ -57,9 +181,243 @@ pub fn measure_distance(
 /// # Arguments
 /// * `from` - The point from which we're measuring/displacing.
 /// * `to` - The point to which the vector will point.
-pub fn get_displacement_vector(
-	from: physical_quantities::Displacement,
-	to: physical_quantities::Displacement,
-) -> physical_quantities::Displacement {
+pub fn get_displacement_vector<V: physical_quantities::Vector>(
+	from: physical_quantities::DisplacementN<V>,
+	to: physical_quantities::DisplacementN<V>,
+) -> physical_quantities::DisplacementN<V> {
 	to - from
 }
+
+/// Determines whether a given point is inside a polygon.
+///
+/// # Arguments
+/// * `point` - The point that may fall inside or outside of the polygon.
+/// * `vertices` - The polygon's vertices, in order. The last vertex is
+///		connected to the first. The polygon may be convex or concave, but should
+///		not intersect itself.
+///
+/// # Notes
+/// * Uses the even-odd (ray casting) rule. A point that falls exactly on an
+///		edge may be considered either inside or outside of the polygon.
+/// * A polygon with fewer than three vertices contains no points.
+/// * In three dimensions, only the x and y coordinates are used, so the polygon
+///		extends infinitely along the z axis.
+pub fn is_within_polygon<V: physical_quantities::Vector>(
+	point: physical_quantities::DisplacementN<V>,
+	vertices: &[physical_quantities::DisplacementN<V>],
+) -> bool {
+	if vertices.len() < 3 {
+		return false;
+	}
+
+	let x = |d: physical_quantities::DisplacementN<V>| d.get_vector().get_component(0);
+	let y = |d: physical_quantities::DisplacementN<V>| d.get_vector().get_component(1);
+
+	// Cast a ray from the point in the +x direction and count how many edges
+	//	it crosses. An odd number of crossings means the point is inside.
+	let mut is_inside = false;
+	let mut previous = vertices[vertices.len() - 1];
+	for current in vertices {
+		if (y(*current) > y(point)) != (y(previous) > y(point)) {
+			// The x-coordinate at which the edge crosses the ray's line.
+			let crossing_x = x(*current)
+				+ (y(point) - y(*current)) * (x(previous) - x(*current))
+				/ (y(previous) - y(*current));
+			if x(point) < crossing_x {
+				is_inside = !is_inside;
+			}
+		}
+		previous = *current;
+	}
+
+	is_inside
+}
+
+/// Gets `count` points evenly spaced around a circle, counterclockwise.
+///
+/// # Arguments
+/// * `center` - The center of the circle.
+/// * `radius` - The radius of the circle.
+/// * `count` - The number of points. Zero gives no points.
+/// * `start_angle` - The angle of the first point, in radians,
+///		counterclockwise from the positive x axis.
+pub fn sample_circle<S: physical_quantities::Scalar>(
+	center: physical_quantities::Displacement<S>,
+	radius: physical_quantities::Length<S>,
+	count: usize,
+	start_angle: S,
+) -> Vec<physical_quantities::Displacement<S>> {
+	(0..count)
+		.map(|n| center + physical_quantities::Displacement::from_polar(
+			radius,
+			get_ring_angle(n, count, start_angle),
+		))
+		.collect()
+}
+
+/// Gets the positions and velocities of `count` particles evenly spaced
+/// around a ring, each moving counterclockwise along it (e.g., a ring of
+/// particles orbiting a central mass, or a spinning wheel).
+///
+/// # Arguments
+/// * `center` - The center of the ring.
+/// * `radius` - The radius of the ring.
+/// * `count` - The number of particles. Zero gives no particles.
+/// * `start_angle` - The angle of the first particle, in radians,
+///		counterclockwise from the positive x axis.
+/// * `tangential_speed` - The speed of each particle along the ring. A
+///		negative speed moves the particles clockwise instead.
+pub fn layout_ring<S: physical_quantities::Scalar>(
+	center: physical_quantities::Displacement<S>,
+	radius: physical_quantities::Length<S>,
+	count: usize,
+	start_angle: S,
+	tangential_speed: physical_quantities::Speed<S>,
+) -> Vec<(physical_quantities::Displacement<S>, physical_quantities::Velocity<S>)> {
+	(0..count)
+		.map(|n| {
+			let angle = get_ring_angle(n, count, start_angle);
+			(
+				center + physical_quantities::Displacement::from_polar(radius, angle),
+				physical_quantities::Velocity::from_polar(tangential_speed, angle).get_perpendicular(),
+			)
+		})
+		.collect()
+}
+
+// The angle of the `n`th of `count` points evenly spaced around a circle.
+fn get_ring_angle<S: physical_quantities::Scalar>(n: usize, count: usize, start_angle: S) -> S {
+	start_angle + S::from_f64(2.0 * std::f64::consts::PI * n as f64 / count as f64)
+}
+
+/// Gets the points of a rectangular grid, row by row.
+///
+/// # Arguments
+/// * `origin` - The first point of the first row.
+/// * `rows` - The number of rows, stacked along the y axis.
+/// * `columns` - The number of points in each row, along the x axis.
+/// * `spacing` - The distance between neighboring columns (x) and rows (y).
+///		Negative components grow the grid in the negative direction.
+pub fn layout_grid<S: physical_quantities::Scalar>(
+	origin: physical_quantities::Displacement<S>,
+	rows: usize,
+	columns: usize,
+	spacing: physical_quantities::Displacement<S>,
+) -> Vec<physical_quantities::Displacement<S>> {
+	let mut points = Vec::with_capacity(rows * columns);
+	for row in 0..rows {
+		for column in 0..columns {
+			points.push(physical_quantities::Displacement::new(
+				origin.x() + S::from_f64(column as f64) * spacing.x(),
+				origin.y() + S::from_f64(row as f64) * spacing.y(),
+			));
+		}
+	}
+
+	points
+}
+
+/// A small, seedable pseudorandom number generator, for scattering initial
+/// conditions reproducibly. The same seed always gives the same sequence, on
+/// every platform.
+///
+/// # Notes
+/// * Uses SplitMix64. It is fast and statistically sound for laying out
+///		particles, but it is not suitable for cryptography.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Random {
+	state: u64,
+}
+
+impl Random {
+	pub fn new(seed: u64) -> Self {
+		Self {
+			state: seed,
+		}
+	}
+
+	/// Gets the next number in the sequence, uniformly distributed over all
+	/// `u64` values.
+	pub fn next_u64(&mut self) -> u64 {
+		self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+		let mut z = self.state;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+		z ^ (z >> 31)
+	}
+
+	/// Gets the next number in the sequence, uniformly distributed from 0
+	/// (inclusive) to 1 (exclusive).
+	pub fn next_f64(&mut self) -> f64 {
+		// The top 53 bits fill an `f64`'s mantissa exactly.
+		(self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+	}
+
+	/// Gets the next number in the sequence, uniformly distributed from `min`
+	/// (inclusive) to `max` (exclusive).
+	pub fn next_in_range(&mut self, min: f64, max: f64) -> f64 {
+		min + (max - min) * self.next_f64()
+	}
+
+	/// Gets a random point in a disc, uniformly distributed over its area.
+	///
+	/// # Arguments
+	/// * `center` - The center of the disc.
+	/// * `radius` - The radius of the disc.
+	pub fn get_point_in_disc<S: physical_quantities::Scalar>(
+		&mut self,
+		center: physical_quantities::Displacement<S>,
+		radius: physical_quantities::Length<S>,
+	) -> physical_quantities::Displacement<S> {
+		// Taking the square root keeps the density even: there's more area
+		//	near the rim than near the center.
+		let distance = radius * S::from_f64(self.next_f64().sqrt());
+		let angle = S::from_f64(self.next_in_range(0.0, 2.0 * std::f64::consts::PI));
+		center + physical_quantities::Displacement::from_polar(distance, angle)
+	}
+}
+
+/// Gets the speed of a circular orbit around a central mass, at which gravity
+/// supplies exactly the centripetal acceleration: v = √(G M / r).
+///
+/// # Arguments
+/// * `central_mass` - The mass being orbited.
+/// * `radius` - The distance from the central mass's center.
+/// * `gravitational_constant` - G, in the simulation's units. See
+///		`units::UnitSystem::get_gravitational_constant`.
+///
+/// # Panics
+/// * If `radius` is not positive.
+pub fn get_circular_orbit_speed<S: physical_quantities::Scalar>(
+	central_mass: physical_quantities::Mass<S>,
+	radius: physical_quantities::Length<S>,
+	gravitational_constant: physical_quantities::GravitationalConstant<S>,
+) -> physical_quantities::Speed<S> {
+	if radius.get_number() <= S::zero() {
+		panic!("get_circular_orbit_speed(): radius must be positive");
+	}
+
+	(gravitational_constant * central_mass / radius).sqrt()
+}
+
+/// Gets the velocity of a counterclockwise circular orbit around a central
+/// mass, relative to that mass. Add the central mass's velocity if it's
+/// moving. See `get_circular_orbit_speed`.
+///
+/// # Arguments
+/// * `central_mass` - The mass being orbited.
+/// * `offset` - The orbiting particle's position relative to the central
+///		mass. Use `get_displacement_vector`.
+/// * `gravitational_constant` - G, in the simulation's units.
+///
+/// # Panics
+/// * If `offset` is zero.
+pub fn get_circular_orbit_velocity<S: physical_quantities::Scalar>(
+	central_mass: physical_quantities::Mass<S>,
+	offset: physical_quantities::Displacement<S>,
+	gravitational_constant: physical_quantities::GravitationalConstant<S>,
+) -> physical_quantities::Velocity<S> {
+	let speed = get_circular_orbit_speed(central_mass, offset.get_norm(), gravitational_constant);
+	physical_quantities::Velocity::from_vector(offset.get_vector().get_unit_vector().get_perpendicular() * speed.get_number())
+}
@@ -0,0 +1,733 @@
+//! Compares simulated motion with the predictions of classical mechanics.
+//!
+//! Each `Scenario` sets up a simulation whose outcome is known analytically
+//! and measures how far the simulation ends up from that outcome. Because the
+//! simulation advances in discrete ticks, some error is expected. An
+//! `ErrorBound` describes how much, as a function of tick duration, so the
+//! same scenario can be checked at several resolutions.
+//!
+//! The built-in scenarios cover the engine's own fields. Crates that define
+//! their own fields can implement `Scenario` and use `run` and
+//! `assert_within_bounds` the same way.
+
+use crate::{physical_quantities, simulation, simulation_objects, utilities};
+use std::fmt;
+use uuid::Uuid;
+
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn tick_durations() -> Vec<physical_quantities::Time> {
+		vec![
+			physical_quantities::Time::new(0.01),
+			physical_quantities::Time::new(0.001),
+		]
+	}
+
+	// Orbits need shorter ticks than the other scenarios for their errors to
+	//	be in the first-order regime the bound describes.
+	fn orbit_tick_durations() -> Vec<physical_quantities::Time> {
+		vec![
+			physical_quantities::Time::new(0.001),
+			physical_quantities::Time::new(0.0005),
+		]
+	}
+
+	/********************* ErrorBound ********************/
+
+	#[test]
+	fn error_bound_scales_with_tick_duration() {
+		let bound = ErrorBound::new(2.0, 1.0, Some(0.5));
+
+		assert_eq!(bound.get_tolerance(physical_quantities::Time::new(0.25)), 1.0);
+		assert_eq!(bound.get_tolerance(physical_quantities::Time::new(0.0)), 0.5);
+
+		let bound = ErrorBound::new(3.0, 2.0, None);
+		assert_eq!(bound.get_tolerance(physical_quantities::Time::new(0.5)), 0.75);
+	}
+
+	/********************* Scenarios ********************/
+
+	#[test]
+	fn projectile_matches_kinematics() {
+		let scenario = Projectile::new(
+			None,
+			physical_quantities::Velocity::new(3.0, 20.0),
+			physical_quantities::Time::new(4.0),
+		);
+
+		assert_within_bounds(&scenario, &tick_durations());
+	}
+
+	// The orbits end partway around, so errors in the particles' phase
+	//	aren't hidden by measuring where they started.
+	#[test]
+	fn circular_orbit_matches_kepler() {
+		let scenario = TwoBodyOrbit::new(1.0, 1.0, 0.5, 1.0, 0.0, 1.75);
+
+		assert_within_bounds(&scenario, &orbit_tick_durations());
+	}
+
+	#[test]
+	fn elliptical_orbit_matches_kepler() {
+		let scenario = TwoBodyOrbit::new(1.0, 1.0, 0.01, 1.0, 0.5, 0.95);
+
+		assert_within_bounds(&scenario, &orbit_tick_durations());
+	}
+
+	#[test]
+	fn head_on_elastic_collision_matches_conservation_laws() {
+		let equal_masses = HeadOnCollision::new(1.0, 1.0, 2.0, -1.0, 0.5);
+		let unequal_masses = HeadOnCollision::new(1.0, 3.0, 4.0, 0.0, 0.5);
+
+		assert_within_bounds(&equal_masses, &tick_durations());
+		assert_within_bounds(&unequal_masses, &tick_durations());
+	}
+
+	#[test]
+	fn orbit_error_shrinks_with_tick_duration() {
+		let scenario = TwoBodyOrbit::new(1.0, 1.0, 0.5, 1.0, 0.0, 1.75);
+		let order = estimate_order(&scenario, physical_quantities::Time::new(0.004));
+
+		assert!(order > 0.8, "{}", order);
+	}
+
+	/********************* Harness ********************/
+
+	// Claims particles stay put, which isn't true for a moving particle.
+	struct WrongScenario;
+
+	impl Scenario for WrongScenario {
+		fn get_name(&self) -> String {
+			String::from("wrong")
+		}
+
+		fn build(
+			&self,
+			tick_duration: physical_quantities::Time,
+		) -> (simulation::Simulation, Vec<Uuid>) {
+			let simulation = simulation::Simulation::new(tick_duration, None, None);
+			let id = simulation.create_particle_with_velocity(
+				physical_quantities::Mass::new(1.0),
+				physical_quantities::Displacement::new(0.0, 0.0),
+				physical_quantities::Velocity::new(1.0, 0.0),
+				Vec::new(),
+			);
+			(simulation, vec![id])
+		}
+
+		fn get_duration(&self) -> physical_quantities::Time {
+			physical_quantities::Time::new(2.0)
+		}
+
+		fn measure_error(
+			&self,
+			simulation: &simulation::Simulation,
+			particle_ids: &[Uuid],
+			_time: physical_quantities::Time,
+		) -> f64 {
+			simulation.get_position(particle_ids[0]).get_magnitude()
+		}
+
+		fn get_error_bound(&self) -> ErrorBound {
+			ErrorBound::new(1.0, 1.0, None)
+		}
+	}
+
+	#[test]
+	fn run_reports_error_and_tolerance() {
+		let report = run(&WrongScenario, physical_quantities::Time::new(0.5));
+
+		assert_eq!(report.get_name(), "wrong");
+		assert_eq!(report.get_ticks(), physical_quantities::Ticks::new(4));
+		assert_eq!(report.get_error(), 2.0);
+		assert_eq!(report.get_tolerance(), 0.5);
+		assert!(!report.passed());
+	}
+
+	#[test]
+	#[should_panic(expected = "wrong with a tick duration of 0.5: error 2 exceeds tolerance 0.5")]
+	fn assert_within_bounds_panics_on_large_error() {
+		assert_within_bounds(&WrongScenario, &[physical_quantities::Time::new(0.5)]);
+	}
+}
+
+
+/// The largest error a scenario allows at a given tick duration, `dt`:
+/// `floor + coefficient * dt^order`. The order is how quickly the error is
+/// expected to shrink as ticks get shorter. The floor allows for rounding
+/// errors, which don't shrink.
+#[derive(Debug)]
+#[derive(Clone, Copy)]
+#[derive(PartialEq)]
+pub struct ErrorBound {
+	coefficient: f64,
+	order: f64,
+	floor: f64,
+}
+
+impl ErrorBound {
+	/// Creates an instance of `ErrorBound`.
+	///
+	/// # Arguments
+	/// * `coefficient` - The error allowed per `dt^order`.
+	/// * `order` - The power of the tick duration the error is proportional
+	///		to. 1.0 for first-order accurate, 2.0 for second-order, etc.
+	/// * `floor` - Error allowed regardless of tick duration. Defaults to
+	///		0.0 if `None`.
+	pub fn new(coefficient: f64, order: f64, floor: Option<f64>) -> Self {
+		Self {
+			coefficient: coefficient,
+			order: order,
+			floor: floor.unwrap_or(0.0),
+		}
+	}
+
+	/// Gets the error allowed at the given tick duration.
+	pub fn get_tolerance(&self, tick_duration: physical_quantities::Time) -> f64 {
+		self.floor + self.coefficient * tick_duration.get_number().powf(self.order)
+	}
+}
+
+/// A setup whose motion is known analytically. Implement this to validate
+/// custom fields with `run` and `assert_within_bounds`.
+pub trait Scenario {
+	/// Gets a name that identifies the scenario in reports.
+	fn get_name(&self) -> String;
+
+	/// Creates a simulation set up for the scenario. Returns the simulation
+	/// and the IDs of any particles that `measure_error` needs.
+	///
+	/// # Arguments
+	/// * `tick_duration` - The tick duration the simulation must use.
+	///
+	/// Particles are added to a simulation during the first tick, so the
+	/// harness runs one tick before it starts the clock. Time zero for the
+	/// analytic solution is when the particles are added, not when the
+	/// simulation is created.
+	fn build(
+		&self,
+		tick_duration: physical_quantities::Time,
+	) -> (simulation::Simulation, Vec<Uuid>);
+
+	/// Gets how much simulated time to run the scenario for.
+	fn get_duration(&self) -> physical_quantities::Time;
+
+	/// Compares the simulation with the analytic solution. Returns the error
+	/// (e.g., the largest distance between a particle's simulated and expected
+	/// positions).
+	///
+	/// # Arguments
+	/// * `simulation` - The simulation, after running for `time`.
+	/// * `particle_ids` - The IDs returned by `build`.
+	/// * `time` - The simulated time since the particles were added.
+	fn measure_error(
+		&self,
+		simulation: &simulation::Simulation,
+		particle_ids: &[Uuid],
+		time: physical_quantities::Time,
+	) -> f64;
+
+	/// Gets the largest error `measure_error` may return at a given tick
+	/// duration for the simulation to be considered correct.
+	fn get_error_bound(&self) -> ErrorBound;
+}
+
+/// The outcome of running a `Scenario` at one tick duration.
+#[derive(Debug)]
+#[derive(Clone)]
+pub struct Report {
+	name: String,
+	tick_duration: physical_quantities::Time,
+	ticks: physical_quantities::Ticks,
+	error: f64,
+	tolerance: f64,
+}
+
+impl Report {
+	pub fn get_name(&self) -> &String {
+		&self.name
+	}
+
+	pub fn get_tick_duration(&self) -> physical_quantities::Time {
+		self.tick_duration
+	}
+
+	/// Gets the number of ticks run after the particles were added.
+	pub fn get_ticks(&self) -> physical_quantities::Ticks {
+		self.ticks
+	}
+
+	/// Gets the error measured at the end of the run.
+	pub fn get_error(&self) -> f64 {
+		self.error
+	}
+
+	/// Gets the error the scenario allows at this tick duration.
+	pub fn get_tolerance(&self) -> f64 {
+		self.tolerance
+	}
+
+	/// Returns true if the error is within the tolerance.
+	pub fn passed(&self) -> bool {
+		self.error <= self.tolerance
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"{} with a tick duration of {}: error {} {} tolerance {}",
+			self.name,
+			self.tick_duration.get_number(),
+			self.error,
+			if self.passed() { "is within" } else { "exceeds" },
+			self.tolerance,
+		)
+	}
+}
+
+/// Runs a scenario at the given tick duration and measures its error. The
+/// scenario's duration is rounded to a whole number of ticks.
+pub fn run(scenario: &dyn Scenario, tick_duration: physical_quantities::Time) -> Report {
+	let (simulation, particle_ids) = scenario.build(tick_duration);
+
+	// Add the particles.
+	simulation.step();
+	let start_time = simulation.get_elapsed_time();
+
+	let ticks = (scenario.get_duration().get_number() / tick_duration.get_number()).round() as u64;
+	for _ in 0..ticks {
+		simulation.step();
+	}
+
+	let time = simulation.get_elapsed_time() - start_time;
+	let error = scenario.measure_error(&simulation, &particle_ids, time);
+
+	Report {
+		name: scenario.get_name(),
+		tick_duration: tick_duration,
+		ticks: physical_quantities::Ticks::new(ticks),
+		error: error,
+		tolerance: scenario.get_error_bound().get_tolerance(tick_duration),
+	}
+}
+
+/// Runs a scenario at each of the given tick durations.
+///
+/// # Panics
+/// Panics, with the report, if the error at any tick duration exceeds the
+/// scenario's error bound.
+pub fn assert_within_bounds(scenario: &dyn Scenario, tick_durations: &[physical_quantities::Time]) {
+	for tick_duration in tick_durations {
+		let report = run(scenario, *tick_duration);
+		if !report.passed() {
+			panic!("{}", report);
+		}
+	}
+}
+
+/// Estimates how quickly a scenario's error shrinks as ticks get shorter by
+/// running it at `tick_duration` and half of `tick_duration`. Returns
+/// `log2(error(dt) / error(dt / 2))`, which is about 1.0 for a first-order
+/// accurate simulation and 2.0 for second-order.
+pub fn estimate_order(scenario: &dyn Scenario, tick_duration: physical_quantities::Time) -> f64 {
+	let coarse = run(scenario, tick_duration).get_error();
+	let fine = run(scenario, tick_duration * 0.5).get_error();
+
+	(coarse / fine).log2()
+}
+
+// The largest distance between the positions of the given particles and the
+//	expected positions.
+fn max_position_error(
+	simulation: &simulation::Simulation,
+	particle_ids: &[Uuid],
+	expected: &[physical_quantities::Displacement],
+) -> f64 {
+	particle_ids.iter()
+		.zip(expected)
+		.map(|(id, position)| utilities::measure_distance(simulation.get_position(*id), *position))
+		.fold(0.0, f64::max)
+}
+
+/// A particle launched from the origin under `SimpleSelfGravityField`, which
+/// should follow `x = v_x * t, y = v_y * t - g * t^2 / 2`. The error is the
+/// distance between the simulated and expected positions.
+pub struct Projectile {
+	gravity: f64,
+	initial_velocity: physical_quantities::Velocity,
+	duration: physical_quantities::Time,
+}
+
+impl Projectile {
+	/// Creates an instance of `Projectile`.
+	///
+	/// # Arguments
+	/// * `gravity` - The downward acceleration due to gravity. Defaults to
+	///		9.81 if `None`.
+	/// * `initial_velocity` - The particle's launch velocity.
+	/// * `duration` - How long to let the particle fly.
+	pub fn new(
+		gravity: Option<f64>,
+		initial_velocity: physical_quantities::Velocity,
+		duration: physical_quantities::Time,
+	) -> Self {
+		Self {
+			gravity: gravity.unwrap_or(9.81),
+			initial_velocity: initial_velocity,
+			duration: duration,
+		}
+	}
+}
+
+impl Scenario for Projectile {
+	fn get_name(&self) -> String {
+		String::from("projectile")
+	}
+
+	fn build(
+		&self,
+		tick_duration: physical_quantities::Time,
+	) -> (simulation::Simulation, Vec<Uuid>) {
+		let simulation = simulation::Simulation::new(tick_duration, None, None);
+		let id = simulation.create_particle_with_velocity(
+			physical_quantities::Mass::new(1.0),
+			physical_quantities::Displacement::new(0.0, 0.0),
+			self.initial_velocity,
+			vec![Box::new(simulation_objects::SimpleSelfGravityField::new(
+				physical_quantities::Acceleration::new(0.0, -self.gravity),
+				None,
+			))],
+		);
+		(simulation, vec![id])
+	}
+
+	fn get_duration(&self) -> physical_quantities::Time {
+		self.duration
+	}
+
+	fn measure_error(
+		&self,
+		simulation: &simulation::Simulation,
+		particle_ids: &[Uuid],
+		time: physical_quantities::Time,
+	) -> f64 {
+		let t = time.get_number();
+		let expected = physical_quantities::Displacement::new(
+			self.initial_velocity.x() * t,
+			self.initial_velocity.y() * t - 0.5 * self.gravity * t * t,
+		);
+		max_position_error(simulation, particle_ids, &[expected])
+	}
+
+	// Constant acceleration is integrated exactly, so only rounding errors
+	//	remain.
+	fn get_error_bound(&self) -> ErrorBound {
+		ErrorBound::new(0.0, 1.0, Some(1.0e-9))
+	}
+}
+
+/// Two particles with `UniversalGravitationField`s orbiting their common
+/// center of mass, which stays at the origin. The particles start at
+/// periapsis. Their expected positions come from Kepler's equation. The error
+/// is the largest distance between a particle's simulated and expected
+/// positions. The error bound only holds for ticks short enough that the error
+/// stays small next to the orbit (several thousand ticks per orbit or more).
+pub struct TwoBodyOrbit {
+	gravitational_constant: f64,
+	mass_a: f64,
+	mass_b: f64,
+	semi_major_axis: f64,
+	eccentricity: f64,
+	periods: f64,
+}
+
+impl TwoBodyOrbit {
+	/// Creates an instance of `TwoBodyOrbit`.
+	///
+	/// # Arguments
+	/// * `gravitational_constant` - The gravitational constant, G.
+	/// * `mass_a` - The mass of the first particle.
+	/// * `mass_b` - The mass of the second particle.
+	/// * `semi_major_axis` - The semi-major axis of the relative orbit (the
+	///		average of the particles' closest and farthest separations).
+	/// * `eccentricity` - 0.0 for a circular orbit. Must be less than 1.0.
+	/// * `periods` - How many orbits to simulate.
+	///
+	/// # Panics
+	/// Panics if `eccentricity` is not in [0, 1).
+	pub fn new(
+		gravitational_constant: f64,
+		mass_a: f64,
+		mass_b: f64,
+		semi_major_axis: f64,
+		eccentricity: f64,
+		periods: f64,
+	) -> Self {
+		if !(0.0..1.0).contains(&eccentricity) {
+			panic!("eccentricity must be at least 0 and less than 1: {}", eccentricity);
+		}
+
+		Self {
+			gravitational_constant: gravitational_constant,
+			mass_a: mass_a,
+			mass_b: mass_b,
+			semi_major_axis: semi_major_axis,
+			eccentricity: eccentricity,
+			periods: periods,
+		}
+	}
+
+	// The mean motion, n = 2 * pi / period.
+	fn get_mean_motion(&self) -> f64 {
+		(self.gravitational_constant * (self.mass_a + self.mass_b)
+			/ self.semi_major_axis.powf(3.0)).sqrt()
+	}
+
+	// Gets the position of particle b relative to particle a at time t.
+	fn get_relative_position(&self, t: f64) -> (f64, f64) {
+		let e = self.eccentricity;
+		let mean_anomaly = self.get_mean_motion() * t;
+
+		// Solve Kepler's equation, M = E - e * sin(E), for the eccentric
+		//	anomaly, E, with Newton's method.
+		let mut eccentric_anomaly = mean_anomaly;
+		for _ in 0..50 {
+			let step = (eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly)
+				/ (1.0 - e * eccentric_anomaly.cos());
+			eccentric_anomaly -= step;
+			if step.abs() < 1.0e-15 {
+				break;
+			}
+		}
+
+		(
+			self.semi_major_axis * (eccentric_anomaly.cos() - e),
+			self.semi_major_axis * (1.0 - e * e).sqrt() * eccentric_anomaly.sin(),
+		)
+	}
+
+	// Splits a relative position or velocity between the particles so their
+	//	center of mass (or total momentum) is zero.
+	fn split(&self, (x, y): (f64, f64)) -> ((f64, f64), (f64, f64)) {
+		let total = self.mass_a + self.mass_b;
+		let fraction_a = -self.mass_b / total;
+		let fraction_b = self.mass_a / total;
+		((fraction_a * x, fraction_a * y), (fraction_b * x, fraction_b * y))
+	}
+}
+
+impl Scenario for TwoBodyOrbit {
+	fn get_name(&self) -> String {
+		format!("two-body orbit with eccentricity {}", self.eccentricity)
+	}
+
+	fn build(
+		&self,
+		tick_duration: physical_quantities::Time,
+	) -> (simulation::Simulation, Vec<Uuid>) {
+		let simulation = simulation::Simulation::new(tick_duration, None, None);
+
+		// At periapsis, the relative velocity is perpendicular to the
+		//	relative position, with the speed given by the vis-viva equation.
+		let a = self.semi_major_axis;
+		let e = self.eccentricity;
+		let speed = (self.gravitational_constant * (self.mass_a + self.mass_b)
+			* (1.0 + e) / (a * (1.0 - e))).sqrt();
+		let (position_a, position_b) = self.split((a * (1.0 - e), 0.0));
+		let (velocity_a, velocity_b) = self.split((0.0, speed));
+
+		let ids = [
+			(self.mass_a, position_a, velocity_a),
+			(self.mass_b, position_b, velocity_b),
+		].iter().map(|(mass, position, velocity)| {
+			simulation.create_particle_with_velocity(
+				physical_quantities::Mass::new(*mass),
+				physical_quantities::Displacement::new(position.0, position.1),
+				physical_quantities::Velocity::new(velocity.0, velocity.1),
+				vec![Box::new(simulation_objects::UniversalGravitationField::new(
+					f64::INFINITY,
+					Some(self.gravitational_constant),
+					None,
+					None,
+					None,
+					None,
+				))],
+			)
+		}).collect();
+
+		(simulation, ids)
+	}
+
+	fn get_duration(&self) -> physical_quantities::Time {
+		physical_quantities::Time::new(
+			self.periods * 2.0 * std::f64::consts::PI / self.get_mean_motion()
+		)
+	}
+
+	fn measure_error(
+		&self,
+		simulation: &simulation::Simulation,
+		particle_ids: &[Uuid],
+		time: physical_quantities::Time,
+	) -> f64 {
+		let (a, b) = self.split(self.get_relative_position(time.get_number()));
+		max_position_error(
+			simulation,
+			particle_ids,
+			&[
+				physical_quantities::Displacement::new(a.0, a.1),
+				physical_quantities::Displacement::new(b.0, b.1),
+			],
+		)
+	}
+
+	// The integrator is first-order accurate. Its energy error shifts the
+	//	orbital period, so the particles fall behind (or ahead of) their
+	//	expected phase and the error grows with the square of the angle they
+	//	have swept through. It peaks near periapsis of an eccentric orbit,
+	//	where the particles move fastest. Measured errors are at most about
+	//	0.5 * a * n * (2 pi periods)^2 * (1 + e) / (1 - e)^2 per unit of tick
+	//	duration. The bound allows 1.5 times that.
+	fn get_error_bound(&self) -> ErrorBound {
+		let e = self.eccentricity;
+		let swept_angle = 2.0 * std::f64::consts::PI * self.periods;
+		ErrorBound::new(
+			0.75 * self.semi_major_axis * self.get_mean_motion() * swept_angle.powf(2.0)
+				* (1.0 + e) / (1.0 - e).powf(2.0),
+			1.0,
+			Some(1.0e-9),
+		)
+	}
+}
+
+/// Two particles with `Collider`s (coefficient of restitution 1.0) moving
+/// toward each other along the x axis. Their velocities after the collision
+/// follow from conservation of momentum and kinetic energy. The error is the
+/// largest distance between a particle's simulated and expected positions,
+/// one second after the collision.
+pub struct HeadOnCollision {
+	mass_a: f64,
+	mass_b: f64,
+	velocity_a: f64,
+	velocity_b: f64,
+	radius: f64,
+}
+
+impl HeadOnCollision {
+	// The distance between the particles' centers at the start.
+	const SEPARATION: f64 = 4.0;
+
+	/// Creates an instance of `HeadOnCollision`. Particle a starts at the
+	/// origin and particle b starts 4 units to its right.
+	///
+	/// # Arguments
+	/// * `mass_a` - The mass of the particle on the left.
+	/// * `mass_b` - The mass of the particle on the right.
+	/// * `velocity_a` - The x velocity of the particle on the left.
+	/// * `velocity_b` - The x velocity of the particle on the right.
+	/// * `radius` - The radius of each particle's collider.
+	///
+	/// # Panics
+	/// Panics if the particles aren't moving toward each other or if they
+	/// start out touching.
+	pub fn new(mass_a: f64, mass_b: f64, velocity_a: f64, velocity_b: f64, radius: f64) -> Self {
+		if velocity_a <= velocity_b {
+			panic!("The particles must be moving toward each other.");
+		}
+		if 2.0 * radius >= Self::SEPARATION {
+			panic!("radius must be less than {}: {}", Self::SEPARATION / 2.0, radius);
+		}
+
+		Self {
+			mass_a: mass_a,
+			mass_b: mass_b,
+			velocity_a: velocity_a,
+			velocity_b: velocity_b,
+			radius: radius,
+		}
+	}
+
+	// The time at which the colliders first touch.
+	fn get_collision_time(&self) -> f64 {
+		(Self::SEPARATION - 2.0 * self.radius) / (self.velocity_a - self.velocity_b)
+	}
+
+	// The velocities of the particles after a 1D elastic collision:
+	//	v_a = ((m_a - m_b) * u_a + 2 * m_b * u_b) / (m_a + m_b)
+	//	v_b = ((m_b - m_a) * u_b + 2 * m_a * u_a) / (m_a + m_b)
+	fn get_final_velocities(&self) -> (f64, f64) {
+		let (m_a, m_b) = (self.mass_a, self.mass_b);
+		let (u_a, u_b) = (self.velocity_a, self.velocity_b);
+		(
+			((m_a - m_b) * u_a + 2.0 * m_b * u_b) / (m_a + m_b),
+			((m_b - m_a) * u_b + 2.0 * m_a * u_a) / (m_a + m_b),
+		)
+	}
+}
+
+impl Scenario for HeadOnCollision {
+	fn get_name(&self) -> String {
+		format!("head-on collision of masses {} and {}", self.mass_a, self.mass_b)
+	}
+
+	fn build(
+		&self,
+		tick_duration: physical_quantities::Time,
+	) -> (simulation::Simulation, Vec<Uuid>) {
+		let simulation = simulation::Simulation::new(tick_duration, None, None);
+		let ids = [
+			(self.mass_a, 0.0, self.velocity_a),
+			(self.mass_b, Self::SEPARATION, self.velocity_b),
+		].iter().map(|(mass, x, velocity)| {
+			simulation.create_particle_with_velocity(
+				physical_quantities::Mass::new(*mass),
+				physical_quantities::Displacement::new(*x, 0.0),
+				physical_quantities::Velocity::new(*velocity, 0.0),
+				vec![Box::new(simulation_objects::Collider::new(self.radius, 1.0, None, None))],
+			)
+		}).collect();
+
+		(simulation, ids)
+	}
+
+	fn get_duration(&self) -> physical_quantities::Time {
+		physical_quantities::Time::new(self.get_collision_time() + 1.0)
+	}
+
+	fn measure_error(
+		&self,
+		simulation: &simulation::Simulation,
+		particle_ids: &[Uuid],
+		time: physical_quantities::Time,
+	) -> f64 {
+		let collision_time = self.get_collision_time();
+		let after = time.get_number() - collision_time;
+		let (final_a, final_b) = self.get_final_velocities();
+		let contact_a = self.velocity_a * collision_time;
+		let contact_b = Self::SEPARATION + self.velocity_b * collision_time;
+
+		max_position_error(
+			simulation,
+			particle_ids,
+			&[
+				physical_quantities::Displacement::new(contact_a + final_a * after, 0.0),
+				physical_quantities::Displacement::new(contact_b + final_b * after, 0.0),
+			],
+		)
+	}
+
+	// The collision is detected up to a tick late and the impulse is spread
+	//	over a tick, so each particle can be off by about its change in
+	//	velocity times the tick duration.
+	fn get_error_bound(&self) -> ErrorBound {
+		let (final_a, final_b) = self.get_final_velocities();
+		let largest_change = (final_a - self.velocity_a).abs()
+			.max((final_b - self.velocity_b).abs());
+		ErrorBound::new(2.0 * largest_change, 1.0, Some(1.0e-9))
+	}
+}